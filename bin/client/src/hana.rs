@@ -12,7 +12,13 @@
 
 extern crate alloc;
 
-use alloc::string::String;
+use alloc::{
+    format,
+    string::{String, ToString},
+};
+use alloy_primitives::hex;
+use celestia_types::nmt::Namespace;
+use hana_client::single::CelestiaConfig;
 use kona_preimage::{HintWriter, OracleReader};
 use kona_std_fpvm::{FileChannel, FileDescriptor};
 use kona_std_fpvm_proc::client_entry;
@@ -31,6 +37,61 @@ static ORACLE_READER: OracleReader<FileChannel> = OracleReader::new(ORACLE_READE
 /// The global hint writer.
 static HINT_WRITER: HintWriter<FileChannel> = HintWriter::new(HINT_WRITER_PIPE);
 
+/// The hex encoded 29 byte Celestia namespace the rollup posts to, fixed when building the client
+/// program so that the host cannot serve the blobs of another namespace.
+const CELESTIA_NAMESPACE: Option<&str> = option_env!("HANA_CELESTIA_NAMESPACE");
+
+/// Parses the [CELESTIA_NAMESPACE] the client program was built for.
+fn celestia_namespace() -> Result<Namespace, String> {
+    let namespace = CELESTIA_NAMESPACE.ok_or_else(|| {
+        String::from("The client program was built without HANA_CELESTIA_NAMESPACE")
+    })?;
+    let bytes = hex::decode(namespace.trim())
+        .map_err(|err| format!("Celestia namespace {namespace:?} is not valid hex: {err}"))?;
+    let (version, id) = bytes
+        .split_first()
+        .ok_or_else(|| String::from("Celestia namespace is empty"))?;
+
+    Namespace::new(*version, id)
+        .map_err(|err| format!("Celestia namespace {namespace:?} is invalid: {err}"))
+}
+
+/// The storage slot of the Blobstream `state_dataCommitments` mapping, fixed when building the
+/// client program for Blobstream deployments whose storage layout differs from the canonical one.
+const CELESTIA_DATA_COMMITMENTS_SLOT: Option<&str> =
+    option_env!("HANA_CELESTIA_DATA_COMMITMENTS_SLOT");
+
+/// The DA layer byte tagging the rollup's Celestia pointers, as a decimal or a `0x` prefixed hex
+/// byte, fixed when building the client program for rollups posting with another byte than the
+/// default one.
+const CELESTIA_DA_LAYER_BYTE: Option<&str> = option_env!("HANA_CELESTIA_DA_LAYER_BYTE");
+
+/// Parses the [CELESTIA_DA_LAYER_BYTE] the client program was built for.
+fn celestia_da_layer_byte(byte: &str) -> Result<u8, String> {
+    let byte = byte.trim();
+    match byte.strip_prefix("0x") {
+        Some(hex) => u8::from_str_radix(hex, 16),
+        None => byte.parse(),
+    }
+    .map_err(|err| format!("Celestia DA layer byte {byte:?} is invalid: {err}"))
+}
+
+/// Parses the Celestia settings the client program was built for.
+fn celestia_config() -> Result<CelestiaConfig, String> {
+    let mut config = CelestiaConfig::new(celestia_namespace()?);
+    if let Some(slot) = CELESTIA_DATA_COMMITMENTS_SLOT {
+        let slot = slot.trim().parse().map_err(|err| {
+            format!("Blobstream data commitments slot {slot:?} is invalid: {err}")
+        })?;
+        config = config.with_data_commitments_slot(slot);
+    }
+    if let Some(byte) = CELESTIA_DA_LAYER_BYTE {
+        config = config.with_da_layer_byte(celestia_da_layer_byte(byte)?);
+    }
+
+    Ok(config)
+}
+
 #[client_entry(100_000_000)]
 fn main() -> Result<(), String> {
     #[cfg(feature = "client-tracing")]
@@ -42,5 +103,13 @@ fn main() -> Result<(), String> {
             .expect("Failed to set tracing subscriber");
     }
 
-    kona_proof::block_on(hana_client::single::run(ORACLE_READER, HINT_WRITER, None))
+    celestia_config().and_then(|celestia| {
+        kona_proof::block_on(hana_client::single::run(
+            ORACLE_READER,
+            HINT_WRITER,
+            celestia,
+            None,
+        ))
+        .map_err(|err| err.to_string())
+    })
 }
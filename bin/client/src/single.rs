@@ -1,6 +1,7 @@
 use alloc::sync::Arc;
 use alloy_consensus::Sealed;
 use alloy_primitives::B256;
+use celestia_types::nmt::Namespace;
 use core::fmt::Debug;
 use kona_client::single::FaultProofProgramError;
 use kona_driver::Driver;
@@ -16,14 +17,57 @@ use kona_proof::{
 };
 use tracing::{error, info};
 
+use hana_blobstream::blobstream::DATA_COMMITMENTS_SLOT;
+use hana_celestia::CELESTIA_DA_LAYER_BYTE;
 use hana_oracle::pipeline::OraclePipeline;
 use hana_oracle::provider::OracleCelestiaProvider;
 
-/// Executes the fault proof program with the given [PreimageOracleClient] and [HintWriterClient].
+/// The Celestia settings the client program derives the rollup under, which must match the
+/// host's
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CelestiaConfig {
+    /// The Celestia namespace the rollup posts its blobs to
+    pub namespace: Namespace,
+    /// The storage slot of the Blobstream `state_dataCommitments` mapping the storage proofs
+    /// served by the host are checked against
+    pub data_commitments_slot: u32,
+    /// The DA layer byte tagging the rollup's Celestia pointers in its batcher data
+    pub da_layer_byte: u8,
+}
+
+impl CelestiaConfig {
+    /// Creates the settings for the rollup posting to `namespace`, verified against the
+    /// canonical Blobstream deployments.
+    pub fn new(namespace: Namespace) -> Self {
+        Self {
+            namespace,
+            data_commitments_slot: DATA_COMMITMENTS_SLOT,
+            da_layer_byte: CELESTIA_DA_LAYER_BYTE,
+        }
+    }
+
+    /// Sets the storage slot of the Blobstream `state_dataCommitments` mapping, defaults to
+    /// [DATA_COMMITMENTS_SLOT].
+    pub fn with_data_commitments_slot(mut self, data_commitments_slot: u32) -> Self {
+        self.data_commitments_slot = data_commitments_slot;
+        self
+    }
+
+    /// Sets the DA layer byte tagging the rollup's Celestia pointers, defaults to
+    /// [CELESTIA_DA_LAYER_BYTE].
+    pub fn with_da_layer_byte(mut self, da_layer_byte: u8) -> Self {
+        self.da_layer_byte = da_layer_byte;
+        self
+    }
+}
+
+/// Executes the fault proof program with the given [PreimageOracleClient] and [HintWriterClient],
+/// deriving the rollup from its blobs under the [CelestiaConfig].
 #[inline]
 pub async fn run<P, H>(
     oracle_client: P,
     hint_client: H,
+    celestia: CelestiaConfig,
     handle_register: Option<
         KonaHandleRegister<
             OracleL2ChainProvider<CachingOracle<P, H>>,
@@ -59,7 +103,9 @@ where
     let mut l2_provider =
         OracleL2ChainProvider::new(safe_head_hash, rollup_config.clone(), oracle.clone());
     let beacon = OracleBlobProvider::new(oracle.clone());
-    let celestia_provider = OracleCelestiaProvider::new(oracle.clone());
+    let celestia_provider = OracleCelestiaProvider::new(oracle.clone(), celestia.namespace)
+        .with_data_commitments_slot(celestia.data_commitments_slot)
+        .with_skip_storage_proof(cfg!(feature = "skip-storage-proof"));
 
     // Fetch the safe head's block header.
     let safe_head = l2_provider
@@ -113,6 +159,7 @@ where
         l1_provider.clone(),
         l2_provider.clone(),
         celestia_provider.clone(),
+        celestia.da_layer_byte,
     )
     .await?;
     let executor = KonaExecutor::new(
@@ -44,6 +44,16 @@ pub enum HostMode {
     /// Run the host in single-chain mode.
     #[cfg(feature = "celestia")]
     Celestia(hana_host::celestia::CelestiaChainHost),
+    /// Fetch or load a Celestia oracle payload, print it and check each of its proofs.
+    #[cfg(feature = "celestia")]
+    InspectPayload(hana_host::celestia::InspectPayloadCmd),
+    /// Build the Celestia payloads referenced by the batches of an L2 block range into the disk
+    /// key-value store, for offline proving.
+    #[cfg(feature = "celestia")]
+    Prefetch(hana_host::celestia::PrefetchRangeCmd),
+    /// Re-check the proofs of every stored Celestia payload offline.
+    #[cfg(feature = "celestia")]
+    VerifyStore(hana_host::celestia::VerifyStoreCmd),
 }
 
 #[tokio::main(flavor = "multi_thread")]
@@ -56,6 +66,18 @@ async fn main() -> Result<()> {
         HostMode::Celestia(cfg) => {
             cfg.start().await?;
         }
+        #[cfg(feature = "celestia")]
+        HostMode::InspectPayload(cmd) => {
+            cmd.run().await?;
+        }
+        #[cfg(feature = "celestia")]
+        HostMode::Prefetch(cmd) => {
+            cmd.run().await?;
+        }
+        #[cfg(feature = "celestia")]
+        HostMode::VerifyStore(cmd) => {
+            cmd.run().await?;
+        }
     }
 
     info!("Exiting host program.");
@@ -0,0 +1,125 @@
+//! Archive of the Celestia payloads served by the host, to share or replay a proving run.
+
+use alloy_primitives::hex;
+use anyhow::{anyhow, Context, Result};
+use celestia_types::Commitment;
+use hana_oracle::hint::CelestiaHint;
+use kona_host::KeyValueStore;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Extension of the archived payload files
+const PAYLOAD_EXTENSION: &str = "payload";
+
+/// A directory holding one serialized [hana_oracle::payload::CelestiaPayload] per Celestia hint.
+///
+/// Payloads for a `celestia-da` hint are named `<height>_<commitment hex>.payload`, payloads for
+/// a `celestia-da-index` hint `<height>_index_<index>.payload` and payloads for a
+/// `celestia-da-blobs` hint `<height>_blobs_<commitment hex>.payload`.
+#[derive(Debug, Clone)]
+pub struct PayloadArchive {
+    dir: PathBuf,
+}
+
+impl PayloadArchive {
+    /// Creates an archive backed by `dir`.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Writes the serialized payload served for the given Celestia hint.
+    pub fn write(&self, hint: &CelestiaHint, payload: &[u8]) -> Result<()> {
+        let name = file_name(hint);
+
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("creating payload archive {}", self.dir.display()))?;
+
+        let path = self.dir.join(name).with_extension(PAYLOAD_EXTENSION);
+        fs::write(&path, payload).with_context(|| format!("writing payload {}", path.display()))
+    }
+
+    /// Returns the hint and the file of every archived payload.
+    pub fn entries(&self) -> Result<Vec<(CelestiaHint, PathBuf)>> {
+        let mut entries = Vec::new();
+
+        for entry in fs::read_dir(&self.dir)
+            .with_context(|| format!("reading payload archive {}", self.dir.display()))?
+        {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some(PAYLOAD_EXTENSION) {
+                continue;
+            }
+
+            let hint = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(parse_file_name)
+                .ok_or_else(|| anyhow!("invalid payload file name {}", path.display()))?;
+            entries.push((hint, path));
+        }
+
+        Ok(entries)
+    }
+
+    /// Stores every archived payload in `kv` under the same preimage key the host serves it
+    /// from, returning the number of payloads stored.
+    pub fn seed<K: KeyValueStore + ?Sized>(&self, kv: &mut K) -> Result<usize> {
+        let entries = self.entries()?;
+
+        for (hint, path) in &entries {
+            let payload =
+                fs::read(path).with_context(|| format!("reading payload {}", path.display()))?;
+            kv.set(hint.preimage_key().into(), payload)?;
+        }
+
+        Ok(entries.len())
+    }
+
+    /// Returns the directory backing the archive.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+}
+
+/// Returns the archive file name, without extension, of the payload for the given hint.
+fn file_name(hint: &CelestiaHint) -> String {
+    match hint {
+        CelestiaHint::Commitment { height, commitment } => {
+            format!("{height}_{}", hex::encode(commitment.hash()))
+        }
+        CelestiaHint::Index { height, index } => format!("{height}_index_{index}"),
+        CelestiaHint::Blobs { height, commitment } => {
+            format!("{height}_blobs_{}", hex::encode(commitment.hash()))
+        }
+    }
+}
+
+/// Rebuilds the hint of the payload with the given archive file name.
+fn parse_file_name(file_name: &str) -> Option<CelestiaHint> {
+    let (height, rest) = file_name.split_once('_')?;
+    let height = height.parse().ok()?;
+
+    let commitment = |hex_commitment: &str| -> Option<Commitment> {
+        let commitment: [u8; 32] = hex::decode(hex_commitment).ok()?.try_into().ok()?;
+        Some(Commitment::new(commitment))
+    };
+
+    if let Some(index) = rest.strip_prefix("index_") {
+        return Some(CelestiaHint::Index {
+            height,
+            index: index.parse().ok()?,
+        });
+    }
+    if let Some(rest) = rest.strip_prefix("blobs_") {
+        return Some(CelestiaHint::Blobs {
+            height,
+            commitment: commitment(rest)?,
+        });
+    }
+    Some(CelestiaHint::Commitment {
+        height,
+        commitment: commitment(rest)?,
+    })
+}
@@ -0,0 +1,93 @@
+//! Resolution of the Celestia node auth token from its configured sources.
+
+use anyhow::{anyhow, bail, Context, Result};
+use std::{fmt, fs, path::Path, process::Command, str::FromStr};
+
+/// Environment variable the auth token is read from when no other source is configured
+pub const AUTH_TOKEN_ENV: &str = "AUTH_TOKEN";
+
+/// A Celestia node auth token, redacted from debug output
+#[derive(Clone, PartialEq, Eq)]
+pub struct AuthToken(String);
+
+impl AuthToken {
+    /// Reads the token from the first line of the file at `path`.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("reading Celestia auth token file {}", path.display()))?;
+
+        Self::from_output(&contents)
+            .ok_or_else(|| anyhow!("Celestia auth token file {} is empty", path.display()))
+    }
+
+    /// Reads the token from the first line printed by the shell command `command`.
+    pub fn from_command(command: &str) -> Result<Self> {
+        let output = Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .output()
+            .with_context(|| format!("running Celestia auth token command `{command}`"))?;
+
+        // The output may hold the token, so it is left out of the errors
+        if !output.status.success() {
+            bail!(
+                "Celestia auth token command `{command}` exited with {}",
+                output.status
+            );
+        }
+
+        Self::from_output(&String::from_utf8_lossy(&output.stdout))
+            .ok_or_else(|| anyhow!("Celestia auth token command `{command}` printed nothing"))
+    }
+
+    /// Returns the token to pass to the Celestia RPC client.
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+
+    fn from_output(output: &str) -> Option<Self> {
+        output
+            .lines()
+            .next()
+            .map(str::trim)
+            .filter(|token| !token.is_empty())
+            .map(|token| Self(token.to_string()))
+    }
+}
+
+impl FromStr for AuthToken {
+    type Err = std::convert::Infallible;
+
+    fn from_str(token: &str) -> Result<Self, Self::Err> {
+        Ok(Self(token.to_string()))
+    }
+}
+
+impl fmt::Debug for AuthToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("AuthToken(<redacted>)")
+    }
+}
+
+/// Resolves the auth token from, in order of precedence, the explicit token, the token file, the
+/// token command and the [AUTH_TOKEN_ENV] environment variable.
+pub fn resolve_auth_token(
+    token: Option<&AuthToken>,
+    file: Option<&Path>,
+    command: Option<&str>,
+) -> Result<Option<AuthToken>> {
+    if let Some(token) = token {
+        return Ok(Some(token.clone()));
+    }
+    if let Some(file) = file {
+        return AuthToken::from_file(file).map(Some);
+    }
+    if let Some(command) = command {
+        return AuthToken::from_command(command).map(Some);
+    }
+
+    Ok(std::env::var(AUTH_TOKEN_ENV)
+        .ok()
+        .filter(|token| !token.is_empty())
+        .map(AuthToken))
+}
@@ -0,0 +1,165 @@
+//! This module contains the `prefetch` subcommand, which builds the Celestia payloads referenced
+//! by the batches of an L2 block range into the disk key-value store, ahead of offline proving.
+
+use alloy_consensus::Transaction;
+use alloy_primitives::Address;
+use alloy_provider::Provider;
+use anyhow::{anyhow, bail, ensure, Result};
+use clap::Parser;
+use hana_celestia::{batcher_pointers, BatcherTx};
+use hana_oracle::hint::CelestiaHint;
+use kona_cli::cli_styles;
+use kona_protocol::L1BlockInfoTx;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use tracing::info;
+
+use super::{
+    prefetch::describe_hint, prefetch_payloads, CelestiaChainHost, CelestiaChainProviders,
+};
+
+/// Builds the Celestia payloads referenced by the batches of an L2 block range into the disk
+/// key-value store and the payload archive.
+#[derive(Parser, Serialize, Clone, Debug)]
+#[command(styles = cli_styles())]
+pub struct PrefetchRangeCmd {
+    /// First L2 block of the range
+    #[clap(long)]
+    pub l2_start: u64,
+    /// Last L2 block of the range
+    #[clap(long)]
+    pub l2_end: u64,
+    #[clap(flatten)]
+    pub host: CelestiaChainHost,
+}
+
+impl PrefetchRangeCmd {
+    /// Runs the subcommand, erroring if the payload of any blob referenced in the range could not
+    /// be built.
+    pub async fn run(self) -> Result<()> {
+        ensure!(
+            self.l2_start <= self.l2_end,
+            "L2 start block {} is after the L2 end block {}",
+            self.l2_start,
+            self.l2_end
+        );
+        ensure!(
+            self.host.single_host.data_dir.is_some(),
+            "A data directory must be set to hold the prefetched payloads"
+        );
+
+        let rollup_config = self.host.read_rollup_config()?;
+        let providers = self.host.create_providers().await?;
+        self.host.preflight(&providers).await?;
+        let kv = self.host.create_key_value_store()?;
+
+        // The batches of the range are posted from the L1 origin of its first block, up to a
+        // sequencing window after the L1 origin of its last block.
+        let l1_start = l1_origin(&providers, self.l2_start).await?;
+        let l1_head = providers.l1().get_block_number().await?;
+        let l1_end = (l1_origin(&providers, self.l2_end).await? + rollup_config.seq_window_size)
+            .min(l1_head);
+
+        let batcher = rollup_config
+            .genesis
+            .system_config
+            .as_ref()
+            .map(|config| config.batcher_address);
+        let hints = find_celestia_hints(
+            &providers,
+            rollup_config.batch_inbox_address,
+            batcher,
+            self.host.celestia_args.da_layer_byte_or_default(),
+            l1_start,
+            l1_end,
+        )
+        .await?;
+        info!(
+            "Found {} Celestia pointers in L1 blocks [{}, {}] for L2 blocks [{}, {}]",
+            hints.len(),
+            l1_start,
+            l1_end,
+            self.l2_start,
+            self.l2_end
+        );
+
+        let report = prefetch_payloads(
+            providers,
+            hints,
+            self.host.celestia_args.prefetch_concurrency,
+            self.host.celestia_args.archive(),
+            kv,
+        )
+        .await;
+
+        println!(
+            "Wrote {} Celestia payloads, {} were already stored",
+            report.stored, report.already_stored
+        );
+        if report.failed.is_empty() {
+            return Ok(());
+        }
+
+        for hint in &report.failed {
+            eprintln!("Failed to build the payload of {}", describe_hint(hint));
+        }
+        let heights: BTreeSet<u64> = report.failed.iter().map(CelestiaHint::height).collect();
+        bail!(
+            "{} Celestia payloads could not be built, at heights {:?}",
+            report.failed.len(),
+            heights
+        )
+    }
+}
+
+/// Returns the number of the L1 origin of `l2_block`, read from its L1 info deposit.
+async fn l1_origin(providers: &CelestiaChainProviders, l2_block: u64) -> Result<u64> {
+    let block = providers
+        .l2()
+        .get_block_by_number(l2_block.into())
+        .full()
+        .await?
+        .ok_or_else(|| anyhow!("L2 block {l2_block} not found"))?;
+
+    let deposit = block
+        .transactions
+        .txns()
+        .next()
+        .ok_or_else(|| anyhow!("L2 block {l2_block} has no L1 info deposit"))?;
+    let info = L1BlockInfoTx::decode_calldata(deposit.input())
+        .map_err(|err| anyhow!("decoding the L1 info deposit of L2 block {l2_block}: {err}"))?;
+
+    Ok(info.id().number)
+}
+
+/// Returns the Celestia pointers tagged with `da_layer_byte` posted to the batch inbox in L1
+/// blocks `[from, to]`, by `batcher` if it is known.
+async fn find_celestia_hints(
+    providers: &CelestiaChainProviders,
+    batch_inbox: Address,
+    batcher: Option<Address>,
+    da_layer_byte: u8,
+    from: u64,
+    to: u64,
+) -> Result<Vec<CelestiaHint>> {
+    let mut hints = Vec::new();
+
+    for number in from..=to {
+        let block = providers
+            .l1()
+            .get_block_by_number(number.into())
+            .full()
+            .await?
+            .ok_or_else(|| anyhow!("L1 block {number} not found"))?;
+
+        let txs = block.transactions.txns().map(|tx| BatcherTx {
+            from: tx.from,
+            to: tx.to(),
+            input: tx.input(),
+        });
+        let pointers = batcher_pointers(txs, batch_inbox, batcher, da_layer_byte);
+        hints.extend(pointers.map(CelestiaHint::from));
+    }
+
+    Ok(hints)
+}
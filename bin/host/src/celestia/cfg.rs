@@ -2,36 +2,64 @@
 
 // Need to replicate single CLI since its not exposed / eported and can't wrap around it
 
-use alloy_provider::Provider;
+use alloy_provider::{network::Network, Provider, RootProvider};
+use alloy_rpc_types_eth::BlockId;
+use celestia_rpc::HeaderClient;
 use celestia_types::nmt::Namespace;
 use clap::Parser;
+use hana_blobstream::blobstream::{
+    SP1Blobstream, SP1BlobstreamDataCommitmentStored, DATA_COMMITMENTS_SLOT,
+};
+use hana_celestia::{CELESTIA_DA_LAYER_BYTE, DEFAULT_MAX_BLOB_BYTES};
+use hana_client::single::CelestiaConfig;
 use hana_oracle::hint::HintWrapper;
+use hana_proofs::{
+    blobstream_inclusion::{ProofConfig, ScanConfig, ScanStrategy},
+    contract::{BlobstreamContract, Sp1Blobstream},
+    rate_limit::RateLimiter,
+};
 use kona_genesis::RollupConfig;
 use kona_host::{
     eth::http_provider,
     single::{SingleChainHost, SingleChainHostError, SingleChainLocalInputs, SingleChainProviders},
-    DiskKeyValueStore, MemoryKeyValueStore, OfflineHostBackend, OnlineHostBackend,
+    DiskKeyValueStore, KeyValueStore, MemoryKeyValueStore, OfflineHostBackend, OnlineHostBackend,
     OnlineHostBackendCfg, PreimageServer, SharedKeyValueStore, SplitKeyValueStore,
 };
+use kona_registry::ROLLUP_CONFIGS;
 
 use kona_cli::cli_styles;
 use serde::Serialize;
 
-use alloy_primitives::{hex, Address};
-use anyhow::{anyhow, Result};
+use alloy_primitives::{address, Address, B256, U256};
+use anyhow::{anyhow, Context, Result};
 use kona_preimage::{
     BidirectionalChannel, Channel, HintReader, HintWriter, OracleReader, OracleServer,
 };
 use kona_providers_alloy::{OnlineBeaconClient, OnlineBlobProvider};
 use kona_std_fpvm::{FileChannel, FileDescriptor};
 use op_alloy_network::Optimism;
-use std::{str::FromStr, sync::Arc};
+use std::{
+    num::{NonZeroU32, NonZeroU64},
+    path::PathBuf,
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
+};
 use tokio::{
+    signal::unix::{signal, SignalKind},
     sync::RwLock,
-    task::{self, JoinHandle},
+    task::{self, AbortHandle, JoinHandle},
 };
+use tracing::{error, info, warn};
 
-use super::{CelestiaChainHintHandler, CelestiaChainProviders, OnlineCelestiaProvider};
+use super::{
+    auth::{resolve_auth_token, AuthToken},
+    http::{celestia_client, http_provider_with, redact_connection, RpcHeader},
+    namespace::parse_namespace,
+    prefetch_payloads, read_prefetch_list, watch_data_commitments, CelestiaChainHintHandler,
+    CelestiaChainProviders, OnlineCelestiaProvider, PayloadArchive, RunStats,
+    COMMITMENT_POLL_INTERVAL,
+};
 
 /// The host binary CLI application arguments.
 #[derive(Default, Parser, Serialize, Clone, Debug)]
@@ -41,6 +69,14 @@ pub struct CelestiaChainHost {
     pub single_host: SingleChainHost,
     #[clap(flatten)]
     pub celestia_args: CelestiaCfg,
+    /// Build the providers and run the preflight checks, print the resolved configuration and
+    /// exit without serving hints
+    #[clap(long)]
+    pub dry_run: bool,
+    /// The counters of the Celestia work done by the run, shared with the providers
+    #[clap(skip)]
+    #[serde(skip)]
+    pub run_stats: Arc<RunStats>,
 }
 
 /// The host binary CLI application arguments.
@@ -50,28 +86,262 @@ pub struct CelestiaCfg {
     /// Connection to celestia network
     #[clap(long, alias = "celestia-conn", env)]
     pub celestia_connection: Option<String>,
-    /// Token for the Celestia node connection
-    #[clap(long, alias = "celestia-auth", env)]
-    pub auth_token: Option<String>,
+    /// Number of connections opened to the Celestia node. Requests are spread round-robin over
+    /// them, so concurrent blob fetches don't queue on a single connection
+    #[clap(
+        long,
+        alias = "celestia-connection-pool-size",
+        env,
+        default_value_t = 1
+    )]
+    pub connection_pool_size: usize,
+    /// Token for the Celestia node connection. Takes precedence over the token file, the token
+    /// command and the `AUTH_TOKEN` environment variable, in that order
+    #[clap(long, alias = "celestia-auth")]
+    #[serde(skip)]
+    pub auth_token: Option<AuthToken>,
+    /// Extra header sent with every L1, L2 and Celestia RPC request, as `Name: value`. May be
+    /// repeated
+    #[clap(long = "rpc-header", alias = "celestia-rpc-header")]
+    #[serde(skip)]
+    pub rpc_headers: Vec<RpcHeader>,
+    /// HTTP proxy the L1 and L2 RPC requests go through
+    #[clap(long, alias = "celestia-http-proxy", env)]
+    pub http_proxy: Option<String>,
+    /// File holding the token for the Celestia node connection
+    #[clap(long, alias = "celestia-auth-token-file", env)]
+    pub auth_token_file: Option<PathBuf>,
+    /// Shell command printing the token for the Celestia node connection
+    #[clap(long, alias = "celestia-auth-token-command", env)]
+    pub auth_token_command: Option<String>,
     /// Celestia Namespace to fetch data from
     #[clap(long, alias = "celestia-namespace", env)]
     pub namespace: Option<String>,
+    /// Celestia Namespace version, overriding the version inferred from the namespace length
+    #[clap(long, alias = "celestia-namespace-version", env)]
+    pub namespace_version: Option<u8>,
+    /// L1 block the Blobstream contract was deployed at, bounding the event scan
+    #[clap(long, alias = "celestia-blobstream-deployment-block", env)]
+    pub blobstream_deployment_block: Option<u64>,
+    /// Number of L1 confirmations a Blobstream event needs before it is used. Higher values
+    /// protect against L1 reorgs at the cost of not finding the newest commitments
+    #[clap(long, alias = "celestia-l1-confirmations", env, default_value_t = 0)]
+    pub l1_confirmations: u64,
+    /// Size the Blobstream event scan windows from the event density instead of using fixed
+    /// windows
+    #[clap(long, alias = "celestia-adaptive-scan", env)]
+    pub adaptive_scan: bool,
+    /// Find Blobstream events by bisecting the L1 blocks on the contract state instead of
+    /// walking back in `get_logs` windows. Defaults to true on Arbitrum chains
+    #[clap(long, alias = "celestia-bisection-scan", env)]
+    pub bisection_scan: Option<bool>,
+    /// Number of L1 blocks in each `get_logs` window of the Blobstream event scan, overriding
+    /// the L1 chain's default. Must be non-zero
+    #[clap(long, alias = "celestia-scan-window", env)]
+    pub scan_window: Option<NonZeroU64>,
+    /// Maximum number of L1 blocks below the head the Blobstream event scan traverses before
+    /// giving up, bounding the RPC load of a single hint. Unbounded if unset
+    #[clap(long, alias = "celestia-max-scan-blocks", env)]
+    pub max_scan_blocks: Option<u64>,
+    /// Storage slot of the Blobstream `state_dataCommitments` mapping, for redeployed or forked
+    /// contracts whose storage layout differs from the canonical deployments. The client program
+    /// must be built with the same `HANA_CELESTIA_DATA_COMMITMENTS_SLOT`
+    #[clap(long, alias = "celestia-data-commitments-slot", env)]
+    pub data_commitments_slot: Option<u32>,
+    /// DA layer byte tagging the rollup's Celestia pointers in its batcher data, as a decimal or
+    /// a `0x` prefixed hex byte. Defaults to `0x0c`. The client program must be built with the
+    /// same `HANA_CELESTIA_DA_LAYER_BYTE`
+    #[clap(long, alias = "celestia-da-layer-byte", env)]
+    pub da_layer_byte: Option<DaLayerByte>,
+    /// Time allowed for each Celestia or L1 RPC call made while fetching Blobstream proofs, in
+    /// milliseconds
+    #[clap(long, alias = "celestia-rpc-timeout-ms", env)]
+    pub rpc_timeout_ms: Option<u64>,
+    /// Time above which serving a Celestia hint logs a warning with a breakdown of where the
+    /// time was spent, in milliseconds. Disabled if unset
+    #[clap(long, alias = "celestia-slow-hint-warn-ms", env)]
+    pub slow_hint_warn_ms: Option<u64>,
+    /// Maximum number of `get_logs` calls per second made to the L1 node by the Blobstream
+    /// event scan, which waits rather than errors once reached. Unlimited if unset
+    #[clap(long, alias = "celestia-l1-rps", env)]
+    pub l1_rps: Option<NonZeroU32>,
+    /// Blobstream program verification key the Blobstream contract must be configured with
+    #[clap(long, alias = "celestia-expected-vkey", env)]
+    pub expected_vkey: Option<B256>,
+    /// Leave the Blobstream storage proof out of the served payloads, so clients have to trust
+    /// the data commitment. Only meant for trusted setups
+    #[clap(long, alias = "celestia-skip-storage-proof", env)]
+    pub skip_storage_proof: bool,
+    /// Serve the Blobstream proofs without verifying them on the host first, leaving their
+    /// verification to the client alone. Only meant for trusted hosts building payloads at scale
+    #[clap(long, alias = "celestia-skip-host-verification", env)]
+    pub skip_host_verification: bool,
+    /// Blobstream data commitment event to prove the Celestia heights it covers against instead
+    /// of scanning L1, as `<proof nonce>:<start block>:<end block>:<data commitment>`. Checked
+    /// against the contract's stored commitment before use. May be repeated
+    #[clap(long = "pin-data-commitment", alias = "celestia-pin-data-commitment")]
+    #[serde(skip)]
+    pub pinned_commitments: Vec<PinnedCommitment>,
+    /// L1 block, as a number or a hash, to take the Blobstream storage proofs at and to start
+    /// the event scan from, to reproduce a past proof against an archive node. Latest if unset
+    #[clap(long, alias = "celestia-l1-proof-block", env)]
+    #[serde(skip)]
+    pub l1_proof_block: Option<L1ProofBlock>,
+    /// File to write a JSON summary of the Celestia hints served to when the run ends
+    #[clap(long, alias = "celestia-run-summary", env)]
+    pub run_summary: Option<PathBuf>,
+    /// Directory to archive every served Celestia payload in. In offline mode, the archived
+    /// payloads are loaded into the key-value store instead
+    #[clap(long, alias = "celestia-payload-archive", env)]
+    pub payload_archive: Option<PathBuf>,
+    /// Size in bytes above which blobs returned by the Celestia node are rejected, defaults to
+    /// the data of a square of the maximum size
+    #[clap(long, alias = "celestia-max-blob-bytes", env)]
+    pub max_blob_bytes: Option<usize>,
+    /// Lowest Celestia height the client's hints may reference, typically the height the rollup
+    /// started posting to Celestia at. Hints below it, or above the node's head, are rejected
+    /// before any Blobstream scan
+    #[clap(long, alias = "celestia-start-height", env)]
+    pub start_height: Option<u64>,
+    /// File listing blobs whose payloads are built ahead of the client's hints, one
+    /// `<height> <commitment hex>` pair per line
+    #[clap(long, alias = "celestia-prefetch", env)]
+    pub prefetch: Option<PathBuf>,
+    /// Number of prefetched payloads built concurrently
+    #[clap(
+        long,
+        alias = "celestia-prefetch-concurrency",
+        env,
+        default_value_t = 4
+    )]
+    pub prefetch_concurrency: usize,
+    /// Celestia network the node must be on: `mainnet`, `mocha`, `arabica` or a raw chain id
+    #[clap(long, alias = "celestia-expected-network", env)]
+    pub expected_network: Option<String>,
+    /// WebSocket URL of the L1 node, to track new Blobstream data commitments in the background
+    /// instead of scanning L1 for each hint. L1 is polled instead if the WebSocket is unavailable
+    #[clap(long, alias = "celestia-l1-ws-url", env)]
+    pub l1_ws_url: Option<String>,
+}
+
+impl CelestiaCfg {
+    /// Returns the [PayloadArchive] served payloads are written to, if configured.
+    pub fn archive(&self) -> Option<PayloadArchive> {
+        self.payload_archive.as_ref().map(PayloadArchive::new)
+    }
+
+    /// Creates an HTTP provider for the node at `url`, with the configured headers and proxy.
+    pub fn http_provider<N: Network>(&self, url: &str) -> Result<RootProvider<N>> {
+        if self.rpc_headers.is_empty() && self.http_proxy.is_none() {
+            return Ok(http_provider(url));
+        }
+        http_provider_with(url, &self.rpc_headers, self.http_proxy.as_deref())
+    }
+
+    /// Resolves the token for the Celestia node connection from its configured sources.
+    pub fn auth_token(&self) -> Result<Option<AuthToken>> {
+        resolve_auth_token(
+            self.auth_token.as_ref(),
+            self.auth_token_file.as_deref(),
+            self.auth_token_command.as_deref(),
+        )
+    }
+
+    /// Returns the storage slot of the Blobstream `state_dataCommitments` mapping, the canonical
+    /// one unless overridden.
+    pub fn data_commitments_slot_or_default(&self) -> u32 {
+        self.data_commitments_slot.unwrap_or(DATA_COMMITMENTS_SLOT)
+    }
+
+    /// Returns the DA layer byte tagging the rollup's Celestia pointers, [CELESTIA_DA_LAYER_BYTE]
+    /// unless overridden.
+    pub fn da_layer_byte_or_default(&self) -> u8 {
+        self.da_layer_byte
+            .map_or(CELESTIA_DA_LAYER_BYTE, |DaLayerByte(byte)| byte)
+    }
+
+    /// Returns the SP1Blobstream contract deployed at `address`, with the configured storage
+    /// layout.
+    pub fn blobstream_contract(&self, address: Address) -> Sp1Blobstream {
+        Sp1Blobstream::new(address)
+            .with_data_commitments_slot(self.data_commitments_slot_or_default())
+    }
+
+    /// Returns the time above which serving a Celestia hint is logged as slow, if configured.
+    pub fn slow_hint_threshold(&self) -> Option<Duration> {
+        self.slow_hint_warn_ms.map(Duration::from_millis)
+    }
+
+    /// Returns the [ProofConfig] used to fetch Blobstream proofs, starting from the scan
+    /// defaults of the L1 `chain` if known.
+    pub fn proof_config(&self, chain: Option<ChainId>) -> ProofConfig {
+        let default = ProofConfig::default();
+        let chain_scan = chain.map(|chain| chain.scan_config()).unwrap_or_default();
+
+        ProofConfig {
+            scan: ScanConfig {
+                deployment_block: self.blobstream_deployment_block,
+                l1_confirmations: self.l1_confirmations,
+                adaptive: self.adaptive_scan,
+                strategy: match self.bisection_scan {
+                    Some(true) => ScanStrategy::Bisection,
+                    Some(false) => ScanStrategy::Backward,
+                    None => chain_scan.strategy,
+                },
+                window: self.scan_window.or(chain_scan.window),
+                max_scan_blocks: self.max_scan_blocks,
+            },
+            rpc_timeout: self
+                .rpc_timeout_ms
+                .map(Duration::from_millis)
+                .unwrap_or(default.rpc_timeout),
+            expected_vkey: self.expected_vkey,
+            skip_storage_proof: self.skip_storage_proof,
+            verify_on_host: !self.skip_host_verification,
+            l1_rate_limit: self.l1_rps.map(RateLimiter::new),
+            pinned_commitments: self
+                .pinned_commitments
+                .iter()
+                .map(|pinned| pinned.0.clone())
+                .collect(),
+            l1_proof_block: self.l1_proof_block.map(|block| block.0),
+        }
+    }
 }
 
 impl CelestiaChainHost {
     /// Starts the [SingleChainHost] application.
     pub async fn start(self) -> Result<(), SingleChainHostError> {
+        if self.dry_run {
+            return self.dry_run().await;
+        }
+
         if self.single_host.server {
             let hint = FileChannel::new(FileDescriptor::HintRead, FileDescriptor::HintWrite);
             let preimage =
                 FileChannel::new(FileDescriptor::PreimageRead, FileDescriptor::PreimageWrite);
 
-            self.start_server(hint, preimage).await?.await?
+            let result = self.start_server(hint, preimage).await?.await?;
+            self.write_run_summary();
+            result
         } else {
             self.start_native().await
         }
     }
 
+    /// Writes the summary of the run to the configured run summary file, if any. Failures are
+    /// logged rather than returned, so they do not mask the outcome of the run.
+    fn write_run_summary(&self) {
+        let Some(path) = &self.celestia_args.run_summary else {
+            return;
+        };
+
+        match self.run_stats.write_summary(path) {
+            Ok(()) => info!("Wrote the run summary to {}", path.display()),
+            Err(err) => error!("Writing the run summary failed: {:#}", err),
+        }
+    }
+
     /// Starts the preimage server, communicating with the client over the provided channels.
     pub async fn start_server<C>(
         &self,
@@ -96,6 +366,35 @@ impl CelestiaChainHost {
             })
         } else {
             let providers = self.create_providers().await?;
+            self.preflight(&providers).await?;
+
+            // Background tasks feeding the server, stopped along with it
+            let mut background = BackgroundTasks::default();
+
+            if let Some(path) = &self.celestia_args.prefetch {
+                let hints = read_prefetch_list(path).map_err(|err| {
+                    error!("Reading the prefetch list failed: {:#}", err);
+                    SingleChainHostError::Other("Failed reading the Celestia prefetch list")
+                })?;
+                background.spawn(prefetch_payloads(
+                    providers.clone(),
+                    hints,
+                    self.celestia_args.prefetch_concurrency,
+                    self.celestia_args.archive(),
+                    kv_store.clone(),
+                ));
+            }
+
+            if let Some(ws_url) = &self.celestia_args.l1_ws_url {
+                background.spawn(watch_data_commitments(
+                    ws_url.clone(),
+                    providers.l1().clone(),
+                    providers.celestia.blobstream,
+                    providers.celestia.commitment_cache.clone(),
+                    COMMITMENT_POLL_INTERVAL,
+                ));
+            }
+
             let backend = OnlineHostBackend::new(
                 self.clone(),
                 kv_store.clone(),
@@ -103,7 +402,8 @@ impl CelestiaChainHost {
                 CelestiaChainHintHandler,
             );
 
-            task::spawn(async {
+            task::spawn(async move {
+                let _background = background;
                 PreimageServer::new(
                     OracleServer::new(preimage),
                     HintReader::new(hint),
@@ -124,17 +424,180 @@ impl CelestiaChainHost {
         let hint = BidirectionalChannel::new()?;
         let preimage = BidirectionalChannel::new()?;
 
-        let server_task = self.start_server(hint.host, preimage.host).await?;
-        let client_task = task::spawn(hana_client::single::run(
+        let celestia = CelestiaConfig::new(self.namespace()?)
+            .with_data_commitments_slot(self.celestia_args.data_commitments_slot_or_default())
+            .with_da_layer_byte(self.celestia_args.da_layer_byte_or_default());
+
+        let mut server_task = self.start_server(hint.host, preimage.host).await?;
+        let mut client_task = task::spawn(hana_client::single::run(
             OracleReader::new(preimage.client),
             HintWriter::new(hint.client),
+            celestia,
             None,
         ));
 
-        let (_, client_result) = tokio::try_join!(server_task, client_task)?;
+        let (name, exit_code) = tokio::select! {
+            joined = async { tokio::try_join!(&mut server_task, &mut client_task) } => {
+                let (_, client_result) = joined?;
+                self.write_run_summary();
+
+                // Bubble up the exit status of the client program if execution completes.
+                std::process::exit(client_result.is_err() as i32)
+            }
+            received = shutdown_signal_or_pending() => received,
+        };
+
+        warn!(
+            "Received {}, cancelling the client and the preimage server",
+            name
+        );
+        client_task.abort();
+        server_task.abort();
+
+        // Waiting for the cancelled tasks drops their handles on the key-value store, closing it
+        // cleanly so the payloads served so far are kept on disk.
+        let _ = client_task.await;
+        let _ = server_task.await;
+        self.write_run_summary();
+
+        std::process::exit(exit_code)
+    }
+
+    /// Builds the providers and runs the [Self::preflight] checks without serving hints, printing
+    /// the resolved namespace, L1 chain id and Blobstream address.
+    pub async fn dry_run(&self) -> Result<(), SingleChainHostError> {
+        let providers = self.create_providers().await?;
+        self.preflight(&providers).await?;
+
+        let chain_id = providers.l1().get_chain_id().await.map_err(|err| {
+            error!(target: "dry-run", "Fetching the L1 chain id failed: {}", err);
+            SingleChainHostError::Other("Dry run failed: could not fetch the L1 chain id")
+        })?;
+
+        println!("Celestia namespace: {:?}", providers.celestia.namespace);
+        match ChainId::from_u64(chain_id) {
+            Some(chain) => println!("L1 chain: {} ({chain_id})", chain.name()),
+            None => println!("L1 chain id: {chain_id}"),
+        }
+        println!(
+            "Blobstream address: {}",
+            providers.celestia.blobstream.address()
+        );
 
-        // Bubble up the exit status of the client program if execution completes.
-        std::process::exit(client_result.is_err() as i32)
+        Ok(())
+    }
+
+    /// Checks that the Celestia node and the Blobstream contract are usable before serving
+    /// hints, so misconfigurations fail at startup instead of deep in hint handling.
+    pub async fn preflight(
+        &self,
+        providers: &CelestiaChainProviders,
+    ) -> Result<(), SingleChainHostError> {
+        let head = providers
+            .celestia
+            .client()
+            .header_local_head()
+            .await
+            .map_err(|err| {
+                error!(target: "preflight", "Celestia header query failed: {}", err);
+                SingleChainHostError::Other(
+                    "Preflight failed: Celestia node did not respond to a header query",
+                )
+            })?;
+
+        if let Some(network) = &self.celestia_args.expected_network {
+            let expected = celestia_chain_id(network);
+            let actual = head.header.chain_id.as_str();
+            if actual != expected {
+                error!(
+                    target: "preflight",
+                    "Celestia node is on chain {} but {} was expected",
+                    actual,
+                    expected
+                );
+                return Err(SingleChainHostError::Other(
+                    "Preflight failed: Celestia node is not on the expected network",
+                ));
+            }
+        }
+
+        let blobstream_address = providers.celestia.blobstream.address();
+
+        let code = providers
+            .l1()
+            .get_code_at(blobstream_address)
+            .await
+            .map_err(|err| {
+                error!(target: "preflight", "Fetching Blobstream bytecode failed: {}", err);
+                SingleChainHostError::Other("Preflight failed: could not fetch Blobstream bytecode")
+            })?;
+        if code.is_empty() {
+            error!(target: "preflight", "No bytecode at Blobstream address {}", blobstream_address);
+            return Err(SingleChainHostError::Other(
+                "Preflight failed: Blobstream address has no bytecode",
+            ));
+        }
+
+        let blobstream = SP1Blobstream::new(blobstream_address, providers.l1().clone());
+
+        let latest_block = blobstream.latestBlock().call().await.map_err(|err| {
+            error!(target: "preflight", "Reading Blobstream latestBlock failed: {}", err);
+            SingleChainHostError::Other("Preflight failed: could not read Blobstream latestBlock")
+        })?;
+        if latest_block._0 == 0 {
+            return Err(SingleChainHostError::Other(
+                "Preflight failed: Blobstream latestBlock is zero",
+            ));
+        }
+
+        let frozen = blobstream.frozen().call().await.map_err(|err| {
+            error!(target: "preflight", "Reading Blobstream frozen failed: {}", err);
+            SingleChainHostError::Other("Preflight failed: could not read Blobstream frozen flag")
+        })?;
+        if frozen._0 {
+            return Err(SingleChainHostError::Other(
+                "Preflight failed: Blobstream contract is frozen",
+            ));
+        }
+
+        info!(
+            target: "preflight",
+            "Blobstream {} is live with latest Celestia block {}",
+            blobstream_address,
+            latest_block._0
+        );
+
+        if self.celestia_args.skip_storage_proof {
+            warn!(
+                target: "preflight",
+                "Blobstream storage proofs are DISABLED, served payloads only prove the data root against a trusted data commitment"
+            );
+        }
+        if self.celestia_args.skip_host_verification {
+            warn!(
+                target: "preflight",
+                "Host-side proof verification is DISABLED, served payloads are only verified by the client"
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Parses the Celestia namespace the rollup posts to.
+    pub fn namespace(&self) -> Result<Namespace, SingleChainHostError> {
+        parse_namespace(
+            self.celestia_args
+                .namespace
+                .as_ref()
+                .ok_or(SingleChainHostError::Other(
+                    "Celestia Namespace must be set",
+                ))?,
+            self.celestia_args.namespace_version,
+        )
+        .map_err(|err| {
+            error!("{}", err);
+            SingleChainHostError::Other("Invalid Celestia namespace")
+        })
     }
 
     /// Returns `true` if the host is running in offline mode.
@@ -145,18 +608,23 @@ impl CelestiaChainHost {
             && self.single_host.data_dir.is_some()
     }
 
-    /// Reads the [RollupConfig] from the file system and returns it as a string.
+    /// Reads the [RollupConfig] from the file system, or, without a rollup config path, takes
+    /// the config of the L2 chain from the superchain registry bundled with the host.
     pub fn read_rollup_config(&self) -> Result<RollupConfig> {
-        let path = self
-            .single_host
-            .rollup_config_path
-            .as_ref()
-            .ok_or_else(|| {
-                anyhow::anyhow!(
-                    "No rollup config path provided. Please provide a path to the rollup config."
+        let Some(path) = self.single_host.rollup_config_path.as_ref() else {
+            let l2_chain_id = self.single_host.l2_chain_id.ok_or_else(|| {
+                anyhow!(
+                    "No rollup config path or L2 chain id provided. Please provide a path to the rollup config, or the id of an L2 chain in the superchain registry."
                 )
             })?;
 
+            return ROLLUP_CONFIGS.get(&l2_chain_id).cloned().ok_or_else(|| {
+                anyhow!(
+                    "No rollup config path provided and L2 chain {l2_chain_id} is not in the superchain registry. Please provide a path to the rollup config."
+                )
+            });
+        };
+
         // Read the serialized config from the file system.
         let ser_config = std::fs::read_to_string(path)
             .map_err(|e| anyhow!("Error reading RollupConfig file: {e}"))?;
@@ -167,15 +635,19 @@ impl CelestiaChainHost {
     }
 
     /// Creates the key-value store for the host backend.
-    fn create_key_value_store(&self) -> Result<SharedKeyValueStore, SingleChainHostError> {
+    pub(crate) fn create_key_value_store(
+        &self,
+    ) -> Result<SharedKeyValueStore, SingleChainHostError> {
         let local_kv_store = SingleChainLocalInputs::new(self.single_host.clone());
 
         let kv_store: SharedKeyValueStore = if let Some(ref data_dir) = self.single_host.data_dir {
-            let disk_kv_store = DiskKeyValueStore::new(data_dir.clone());
+            let mut disk_kv_store = DiskKeyValueStore::new(data_dir.clone());
+            self.seed_from_archive(&mut disk_kv_store)?;
             let split_kv_store = SplitKeyValueStore::new(local_kv_store, disk_kv_store);
             Arc::new(RwLock::new(split_kv_store))
         } else {
-            let mem_kv_store = MemoryKeyValueStore::new();
+            let mut mem_kv_store = MemoryKeyValueStore::new();
+            self.seed_from_archive(&mut mem_kv_store)?;
             let split_kv_store = SplitKeyValueStore::new(local_kv_store, mem_kv_store);
             Arc::new(RwLock::new(split_kv_store))
         };
@@ -183,14 +655,45 @@ impl CelestiaChainHost {
         Ok(kv_store)
     }
 
-    /// Creates the providers required for the host backend.
-    async fn create_providers(&self) -> Result<CelestiaChainProviders, SingleChainHostError> {
-        let l1_provider = http_provider(
-            self.single_host
-                .l1_node_address
-                .as_ref()
-                .ok_or(SingleChainHostError::Other("Provider must be set"))?,
+    /// Loads the archived Celestia payloads into `kv` when running offline with a payload archive.
+    fn seed_from_archive<K: KeyValueStore>(&self, kv: &mut K) -> Result<(), SingleChainHostError> {
+        let Some(archive) = self.celestia_args.archive().filter(|_| self.is_offline()) else {
+            return Ok(());
+        };
+
+        let seeded = archive.seed(kv).map_err(|err| {
+            error!(
+                "Loading payload archive {} failed: {:#}",
+                archive.dir().display(),
+                err
+            );
+            SingleChainHostError::Other("Failed to load the Celestia payload archive")
+        })?;
+        info!(
+            "Loaded {} Celestia payloads from {}",
+            seeded,
+            archive.dir().display()
         );
+
+        Ok(())
+    }
+
+    /// Creates the providers required for the host backend.
+    pub(crate) async fn create_providers(
+        &self,
+    ) -> Result<CelestiaChainProviders, SingleChainHostError> {
+        let l1_provider = self
+            .celestia_args
+            .http_provider(
+                self.single_host
+                    .l1_node_address
+                    .as_ref()
+                    .ok_or(SingleChainHostError::Other("Provider must be set"))?,
+            )
+            .map_err(|err| {
+                error!("Creating the L1 provider failed: {:#}", err);
+                SingleChainHostError::Other("Failed creating the L1 provider")
+            })?;
         let blob_provider = OnlineBlobProvider::init(OnlineBeaconClient::new_http(
             self.single_host
                 .l1_beacon_address
@@ -198,47 +701,93 @@ impl CelestiaChainHost {
                 .ok_or(SingleChainHostError::Other("Beacon API URL must be set"))?,
         ))
         .await;
-        let l2_provider = http_provider::<Optimism>(
-            self.single_host
-                .l2_node_address
-                .as_ref()
-                .ok_or(SingleChainHostError::Other("L2 node address must be set"))?,
-        );
+        let l2_provider = self
+            .celestia_args
+            .http_provider::<Optimism>(
+                self.single_host
+                    .l2_node_address
+                    .as_ref()
+                    .ok_or(SingleChainHostError::Other("L2 node address must be set"))?,
+            )
+            .map_err(|err| {
+                error!("Creating the L2 provider failed: {:#}", err);
+                SingleChainHostError::Other("Failed creating the L2 provider")
+            })?;
 
-        let celestia_client =
-            celestia_rpc::Client::new(
-                self.celestia_args.celestia_connection.as_ref().ok_or(
-                    SingleChainHostError::Other("Celestia connection must be set"),
-                )?,
-                self.celestia_args.auth_token.as_ref().map(|x| x.as_str()),
+        let auth_token = self.celestia_args.auth_token().map_err(|err| {
+            error!("Resolving the Celestia auth token failed: {:#}", err);
+            SingleChainHostError::Other("Failed resolving the Celestia auth token")
+        })?;
+
+        let connection =
+            self.celestia_args
+                .celestia_connection
+                .as_ref()
+                .ok_or(SingleChainHostError::Other(
+                    "Celestia connection must be set",
+                ))?;
+        let pool_size = self.celestia_args.connection_pool_size;
+        let mut celestia_clients = Vec::with_capacity(pool_size);
+        for _ in 0..pool_size {
+            let client = celestia_client(
+                connection,
+                auth_token.as_ref(),
+                &self.celestia_args.rpc_headers,
             )
             .await
-            .expect("Failed creating rpc client");
+            .map_err(|err| {
+                error!("Creating the Celestia RPC client failed: {}", err);
+                SingleChainHostError::Other("Failed creating the Celestia RPC client")
+            })?;
+            celestia_clients.push(client);
+        }
 
-        let namespace_bytes = hex::decode(&self.celestia_args.namespace.as_ref().ok_or(
-            SingleChainHostError::Other("Celestia Namespace must be set"),
-        )?)
-        .expect("Invalid hex");
-        let namespace = Namespace::new_v0(&namespace_bytes).expect("Invalid namespace");
+        let namespace = self.namespace()?;
 
         // call l1 provider for chain id and check against mapping
 
-        let chain_id = l1_provider
-            .get_chain_id()
-            .await
-            .expect("unable to fetch chain id from root provider");
+        let chain_id = l1_provider.get_chain_id().await.map_err(|err| {
+            error!(
+                "Fetching the L1 chain id from {} failed: {}",
+                redact_connection(
+                    self.single_host
+                        .l1_node_address
+                        .as_deref()
+                        .unwrap_or_default()
+                ),
+                err
+            );
+            SingleChainHostError::Other("Unable to fetch the chain id from the L1 provider")
+        })?;
 
-        let blobstream_address = match ChainId::from_u64(chain_id) {
-            Some(chain) => chain.blostream_address(),
-            None => {
-                return Err(SingleChainHostError::Other(
-                    "Unknown chain id for blobstream address",
-                ))
-            }
-        };
+        let chain = ChainId::from_u64(chain_id).ok_or_else(|| {
+            error!(
+                "L1 chain id {} has no known Blobstream deployment, supported chains: {}",
+                chain_id,
+                supported_chains()
+            );
+            SingleChainHostError::Other("Unknown chain id for blobstream address")
+        })?;
 
-        let celestia_provider =
-            OnlineCelestiaProvider::new(celestia_client, namespace, blobstream_address);
+        let celestia_provider = OnlineCelestiaProvider::builder(
+            namespace,
+            self.celestia_args
+                .blobstream_contract(chain.blobstream_address()),
+        )
+        .clients(celestia_clients)
+        .proof_config(self.celestia_args.proof_config(Some(chain)))
+        .max_blob_bytes(
+            self.celestia_args
+                .max_blob_bytes
+                .unwrap_or(DEFAULT_MAX_BLOB_BYTES),
+        )
+        .start_height(self.celestia_args.start_height.unwrap_or_default())
+        .stats(self.run_stats.clone())
+        .build()
+        .map_err(|err| {
+            error!("Invalid Celestia provider configuration: {:#}", err);
+            SingleChainHostError::Other("Invalid Celestia provider configuration")
+        })?;
 
         Ok(CelestiaChainProviders {
             inner_providers: SingleChainProviders {
@@ -251,12 +800,93 @@ impl CelestiaChainHost {
     }
 }
 
+/// Tasks spawned alongside the preimage server, aborted when dropped
+#[derive(Default)]
+struct BackgroundTasks(Vec<AbortHandle>);
+
+impl BackgroundTasks {
+    fn spawn<F>(&mut self, future: F)
+    where
+        F: std::future::Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        self.0.push(task::spawn(future).abort_handle());
+    }
+}
+
+impl Drop for BackgroundTasks {
+    fn drop(&mut self) {
+        for task in &self.0 {
+            task.abort();
+        }
+    }
+}
+
+/// Waits for Ctrl-C or SIGTERM, returning the signal's name and the exit code to report for it.
+async fn shutdown_signal() -> Result<(&'static str, i32), SingleChainHostError> {
+    let mut terminate = signal(SignalKind::terminate()).map_err(|err| {
+        error!("Installing the SIGTERM handler failed: {}", err);
+        SingleChainHostError::Other("Failed installing the SIGTERM handler")
+    })?;
+
+    tokio::select! {
+        interrupted = tokio::signal::ctrl_c() => {
+            interrupted.map_err(|err| {
+                error!("Listening for Ctrl-C failed: {}", err);
+                SingleChainHostError::Other("Failed listening for Ctrl-C")
+            })?;
+            Ok(("Ctrl-C", 130))
+        }
+        _ = terminate.recv() => Ok(("SIGTERM", 143)),
+    }
+}
+
+/// Waits for a shutdown signal like [shutdown_signal], or forever if the signal handlers could not
+/// be installed, so the run carries on without them rather than being cut short.
+async fn shutdown_signal_or_pending() -> (&'static str, i32) {
+    match shutdown_signal().await {
+        Ok(received) => received,
+        Err(_) => {
+            warn!(
+                "Shutdown signals will not be handled, the run can only be stopped by killing it"
+            );
+            std::future::pending().await
+        }
+    }
+}
+
+/// Lists the chains with a known Blobstream deployment as `name (id)`.
+pub(crate) fn supported_chains() -> String {
+    ChainId::all()
+        .iter()
+        .map(|chain| format!("{} ({})", chain.name(), *chain as u64))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Maps a well known Celestia network name to its chain id, passing chain ids through unchanged.
+fn celestia_chain_id(network: &str) -> &str {
+    match network {
+        "mainnet" => "celestia",
+        "mocha" => "mocha-4",
+        "arabica" => "arabica-11",
+        chain_id => chain_id,
+    }
+}
+
 impl OnlineHostBackendCfg for CelestiaChainHost {
     type HintType = HintWrapper;
     // TODO: Modify so that is uses "CelestiaChainProviders"
     type Providers = CelestiaChainProviders;
 }
 
+/// `get_logs` window of a backward scan on Arbitrum, whose blocks are about 48 times faster
+/// than Ethereum's
+const ARBITRUM_SCAN_WINDOW: NonZeroU64 = match NonZeroU64::new(100_000) {
+    Some(window) => window,
+    None => unreachable!(),
+};
+
 // Enum for known EVM chain IDs
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ChainId {
@@ -272,6 +902,21 @@ pub enum ChainId {
 }
 
 impl ChainId {
+    /// Every chain with a known Blobstream deployment
+    const ALL: [Self; 6] = [
+        Self::EthereumMainnet,
+        Self::ArbitrumOne,
+        Self::Base,
+        Self::Sepolia,
+        Self::ArbitrumSepolia,
+        Self::BaseSepolia,
+    ];
+
+    /// Returns every chain with a known Blobstream deployment.
+    pub const fn all() -> &'static [Self] {
+        &Self::ALL
+    }
+
     pub fn from_u64(id: u64) -> Option<Self> {
         match id {
             1 => Some(Self::EthereumMainnet),
@@ -284,24 +929,195 @@ impl ChainId {
         }
     }
 
-    pub fn blostream_address(&self) -> Address {
+    /// Returns the human-readable name of the chain.
+    pub const fn name(&self) -> &'static str {
         match self {
-            Self::EthereumMainnet => {
-                Address::from_str("0x7Cf3876F681Dbb6EdA8f6FfC45D66B996Df08fAe").unwrap()
-            }
-            Self::ArbitrumOne => {
-                Address::from_str("0xA83ca7775Bc2889825BcDeDfFa5b758cf69e8794").unwrap()
-            }
-            Self::Base => Address::from_str("0xA83ca7775Bc2889825BcDeDfFa5b758cf69e8794").unwrap(),
-            Self::Sepolia => {
-                Address::from_str("0xF0c6429ebAB2e7DC6e05DaFB61128bE21f13cb1e").unwrap()
-            }
-            Self::ArbitrumSepolia => {
-                Address::from_str("0xc3e209eb245Fd59c8586777b499d6A665DF3ABD2").unwrap()
-            }
-            Self::BaseSepolia => {
-                Address::from_str("0xc3e209eb245Fd59c8586777b499d6A665DF3ABD2").unwrap()
+            Self::EthereumMainnet => "Ethereum Mainnet",
+            Self::ArbitrumOne => "Arbitrum One",
+            Self::Base => "Base",
+            Self::Sepolia => "Sepolia",
+            Self::ArbitrumSepolia => "Arbitrum Sepolia",
+            Self::BaseSepolia => "Base Sepolia",
+        }
+    }
+
+    /// Returns whether the chain is a testnet.
+    pub const fn is_testnet(&self) -> bool {
+        match self {
+            Self::EthereumMainnet | Self::ArbitrumOne | Self::Base => false,
+            Self::Sepolia | Self::ArbitrumSepolia | Self::BaseSepolia => true,
+        }
+    }
+
+    /// Returns the address of the SP1Blobstream contract deployed on the chain.
+    pub const fn blobstream_address(&self) -> Address {
+        match self {
+            Self::EthereumMainnet => address!("0x7Cf3876F681Dbb6EdA8f6FfC45D66B996Df08fAe"),
+            Self::ArbitrumOne => address!("0xA83ca7775Bc2889825BcDeDfFa5b758cf69e8794"),
+            Self::Base => address!("0xA83ca7775Bc2889825BcDeDfFa5b758cf69e8794"),
+            Self::Sepolia => address!("0xF0c6429ebAB2e7DC6e05DaFB61128bE21f13cb1e"),
+            Self::ArbitrumSepolia => address!("0xc3e209eb245Fd59c8586777b499d6A665DF3ABD2"),
+            Self::BaseSepolia => address!("0xc3e209eb245Fd59c8586777b499d6A665DF3ABD2"),
+        }
+    }
+
+    /// Returns the default Blobstream event scan for the chain. Arbitrum's fast blocks make
+    /// walking back in `get_logs` windows slow, so Arbitrum chains bisect instead.
+    pub fn scan_config(&self) -> ScanConfig {
+        match self {
+            Self::ArbitrumOne | Self::ArbitrumSepolia => ScanConfig {
+                strategy: ScanStrategy::Bisection,
+                window: Some(ARBITRUM_SCAN_WINDOW),
+                ..Default::default()
+            },
+            Self::EthereumMainnet | Self::Base | Self::Sepolia | Self::BaseSepolia => {
+                ScanConfig::default()
             }
         }
     }
+
+    #[deprecated(note = "use `blobstream_address` instead")]
+    pub const fn blostream_address(&self) -> Address {
+        self.blobstream_address()
+    }
+}
+
+/// An L1 block given on the command line, parsed from its decimal number or its `0x` prefixed
+/// hash
+#[derive(Debug, Clone, Copy)]
+pub struct L1ProofBlock(pub BlockId);
+
+impl FromStr for L1ProofBlock {
+    type Err = anyhow::Error;
+
+    fn from_str(block: &str) -> Result<Self> {
+        if block.starts_with("0x") {
+            let hash = B256::from_str(block).context("invalid L1 proof block hash")?;
+            return Ok(Self(BlockId::hash(hash)));
+        }
+
+        let number = block
+            .parse()
+            .context("L1 proof block must be a block number or a 0x prefixed block hash")?;
+        Ok(Self(BlockId::number(number)))
+    }
+}
+
+/// A DA layer byte given on the command line, parsed from a decimal or a `0x` prefixed hex byte
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct DaLayerByte(pub u8);
+
+impl FromStr for DaLayerByte {
+    type Err = anyhow::Error;
+
+    fn from_str(byte: &str) -> Result<Self> {
+        let parsed = match byte.strip_prefix("0x") {
+            Some(hex) => u8::from_str_radix(hex, 16),
+            None => byte.parse(),
+        };
+        parsed
+            .map(Self)
+            .context("DA layer byte must be a decimal or a 0x prefixed hex byte")
+    }
+}
+
+/// A Blobstream data commitment event given on the command line, parsed from
+/// `<proof nonce>:<start block>:<end block>:<data commitment>`
+#[derive(Debug, Clone)]
+pub struct PinnedCommitment(pub SP1BlobstreamDataCommitmentStored);
+
+impl FromStr for PinnedCommitment {
+    type Err = anyhow::Error;
+
+    fn from_str(pinned: &str) -> Result<Self> {
+        let [proof_nonce, start_block, end_block, data_commitment] = pinned
+            .split(':')
+            .collect::<Vec<_>>()
+            .try_into()
+            .map_err(|_| {
+                anyhow!("pinned data commitment must be `<nonce>:<start>:<end>:<commitment>`")
+            })?;
+
+        Ok(Self(SP1BlobstreamDataCommitmentStored {
+            proof_nonce: U256::from_str(proof_nonce).context("invalid pinned proof nonce")?,
+            start_block: start_block.parse().context("invalid pinned start block")?,
+            end_block: end_block.parse().context("invalid pinned end block")?,
+            data_commitment: data_commitment
+                .parse()
+                .context("invalid pinned data commitment")?,
+            l1_block_number: None,
+            l1_block_hash: None,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn every_chain_round_trips_through_its_id() {
+        for &chain in ChainId::all() {
+            assert_eq!(ChainId::from_u64(chain as u64), Some(chain), "{chain:?}");
+        }
+        assert_eq!(ChainId::from_u64(10), None);
+    }
+
+    #[test]
+    fn lists_every_chain_once() {
+        let chains: HashSet<_> = ChainId::all().iter().collect();
+        assert_eq!(chains.len(), ChainId::all().len());
+
+        let names: HashSet<_> = ChainId::all().iter().map(ChainId::name).collect();
+        assert_eq!(names.len(), ChainId::all().len());
+    }
+
+    #[test]
+    fn flags_the_testnets() {
+        let testnets: Vec<_> = ChainId::all()
+            .iter()
+            .filter(|chain| chain.is_testnet())
+            .copied()
+            .collect();
+
+        assert_eq!(
+            testnets,
+            [
+                ChainId::Sepolia,
+                ChainId::ArbitrumSepolia,
+                ChainId::BaseSepolia
+            ]
+        );
+    }
+
+    #[test]
+    fn supported_chains_lists_names_and_ids() {
+        let supported = supported_chains();
+
+        assert!(supported.starts_with("Ethereum Mainnet (1), Arbitrum One (42161)"));
+        for &chain in ChainId::all() {
+            assert!(
+                supported.contains(&format!("{} ({})", chain.name(), chain as u64)),
+                "{supported}"
+            );
+        }
+    }
+
+    #[test]
+    fn parses_da_layer_bytes_in_decimal_or_hex() {
+        assert_eq!("0x0c".parse::<DaLayerByte>().unwrap(), DaLayerByte(0x0c));
+        assert_eq!("206".parse::<DaLayerByte>().unwrap(), DaLayerByte(0xce));
+        assert!("0x100".parse::<DaLayerByte>().is_err());
+        assert!("0c".parse::<DaLayerByte>().is_err());
+    }
+
+    #[test]
+    fn defaults_to_the_celestia_da_layer_byte() {
+        let mut cfg = CelestiaCfg::default();
+        assert_eq!(cfg.da_layer_byte_or_default(), CELESTIA_DA_LAYER_BYTE);
+
+        cfg.da_layer_byte = Some(DaLayerByte(0xce));
+        assert_eq!(cfg.da_layer_byte_or_default(), 0xce);
+    }
 }
@@ -0,0 +1,65 @@
+//! Cache of the Blobstream data commitment events discovered by the host.
+//!
+//! Celestia data is immutable once finalized, so blobs, shares and their proofs against a data
+//! root never go stale. Everything derived from L1 can: a data commitment event, and any storage
+//! proof built against it, only holds while the L1 block that emitted the event stays canonical.
+//! The cache therefore only holds data commitment events, tagged with the L1 block they were
+//! found in, and drops them when that block is reorged out. Payloads already written to the
+//! key-value store are not affected.
+
+use hana_blobstream::blobstream::SP1BlobstreamDataCommitmentStored;
+use std::{collections::BTreeMap, sync::RwLock};
+
+/// Data commitment events keyed by the first Celestia height they cover
+#[derive(Debug, Default)]
+pub struct DataCommitmentCache {
+    events: RwLock<BTreeMap<u64, SP1BlobstreamDataCommitmentStored>>,
+}
+
+impl DataCommitmentCache {
+    /// Returns the cached event covering the given Celestia height, if any.
+    pub fn get(&self, celestia_height: u64) -> Option<SP1BlobstreamDataCommitmentStored> {
+        let events = self.events.read().expect("data commitment cache poisoned");
+
+        events
+            .range(..=celestia_height)
+            .next_back()
+            .map(|(_, event)| event)
+            .filter(|event| celestia_height < event.end_block)
+            .cloned()
+    }
+
+    /// Caches the event. Events without a known L1 block are not cached, as they could not be
+    /// invalidated on a reorg.
+    pub fn insert(&self, event: SP1BlobstreamDataCommitmentStored) {
+        if event.l1_block_number.is_none() || event.l1_block_hash.is_none() {
+            return;
+        }
+
+        self.events
+            .write()
+            .expect("data commitment cache poisoned")
+            .insert(event.start_block, event);
+    }
+
+    /// Drops every event emitted in an L1 block above `l1_block`, returning how many were dropped.
+    pub fn invalidate_above(&self, l1_block: u64) -> usize {
+        let mut events = self.events.write().expect("data commitment cache poisoned");
+
+        let before = events.len();
+        events.retain(|_, event| {
+            event
+                .l1_block_number
+                .is_some_and(|number| number <= l1_block)
+        });
+        before - events.len()
+    }
+
+    /// Drops every cached event.
+    pub fn clear(&self) {
+        self.events
+            .write()
+            .expect("data commitment cache poisoned")
+            .clear();
+    }
+}
@@ -0,0 +1,179 @@
+//! Background tracking of the Blobstream data commitment events.
+//!
+//! A long-running host would otherwise scan L1 for the covering event of every new Celestia
+//! height. The watcher feeds each new event into the [DataCommitmentCache] as it is emitted, so
+//! hints are served from the cache and L1 is only scanned for historical ranges. Events are
+//! received over a WebSocket subscription, falling back to polling `get_logs` when the WebSocket
+//! is unavailable or drops.
+
+use alloy_provider::{Provider, ProviderBuilder, RootProvider, WsConnect};
+use alloy_rpc_types_eth::Log;
+use anyhow::Result;
+use hana_proofs::{
+    blobstream_inclusion::{
+        check_data_commitment_range, data_commitment_events_filter, data_commitment_filter,
+    },
+    contract::{BlobstreamContract, Sp1Blobstream},
+};
+use std::{sync::Arc, time::Duration};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{debug, info, warn};
+
+use super::DataCommitmentCache;
+
+/// Interval at which L1 is polled for new events when no subscription is available
+pub const COMMITMENT_POLL_INTERVAL: Duration = Duration::from_secs(12);
+
+/// Keeps `cache` up to date with the data commitment events of `blobstream`, subscribing to them
+/// over `ws_url` and polling `l1_provider` every `poll_interval` if the subscription fails.
+///
+/// Events whose range exceeds the contract's `DATA_COMMITMENT_MAX` are dropped. Runs until the
+/// task is dropped, or returns right away if `DATA_COMMITMENT_MAX` cannot be read, leaving the
+/// events to the regular scan.
+pub async fn watch_data_commitments(
+    ws_url: String,
+    l1_provider: RootProvider,
+    blobstream: Sp1Blobstream,
+    cache: Arc<DataCommitmentCache>,
+    poll_interval: Duration,
+) {
+    let data_commitment_max = match blobstream.data_commitment_max(&l1_provider).await {
+        Ok(max) => max,
+        Err(err) => {
+            warn!(
+                "Reading the Blobstream DATA_COMMITMENT_MAX failed, not tracking events: {}",
+                err
+            );
+            return;
+        }
+    };
+    let recorder = EventRecorder {
+        blobstream,
+        cache,
+        data_commitment_max,
+    };
+
+    match subscribe_data_commitments(&ws_url, &recorder).await {
+        Ok(()) => warn!("Blobstream event subscription closed, polling L1 instead"),
+        Err(err) => warn!(
+            "Blobstream event subscription over {} failed, polling L1 instead: {:#}",
+            ws_url, err
+        ),
+    }
+
+    poll_data_commitments(&l1_provider, &recorder, poll_interval).await
+}
+
+/// Validates the data commitment events received and caches them
+struct EventRecorder {
+    blobstream: Sp1Blobstream,
+    cache: Arc<DataCommitmentCache>,
+    data_commitment_max: u64,
+}
+
+/// Caches the events received over a WebSocket subscription, returning once it closes.
+async fn subscribe_data_commitments(ws_url: &str, recorder: &EventRecorder) -> Result<()> {
+    let blobstream = &recorder.blobstream;
+    let provider = ProviderBuilder::default()
+        .on_ws(WsConnect::new(ws_url))
+        .await?;
+    let mut subscription = provider
+        .subscribe_logs(&data_commitment_events_filter(blobstream))
+        .await?;
+    info!(
+        "Subscribed to the data commitment events of Blobstream {}",
+        blobstream.address()
+    );
+
+    loop {
+        match subscription.recv().await {
+            Ok(log) => recorder.record(&log),
+            // Missed events are found by the regular scan when a hint needs them
+            Err(RecvError::Lagged(missed)) => {
+                warn!(
+                    "Blobstream event subscription lagged, {} events missed",
+                    missed
+                )
+            }
+            Err(RecvError::Closed) => return Ok(()),
+        }
+    }
+}
+
+/// Caches the events of the L1 blocks produced since the last poll, every `poll_interval`.
+async fn poll_data_commitments(
+    l1_provider: &RootProvider,
+    recorder: &EventRecorder,
+    poll_interval: Duration,
+) {
+    let mut interval = tokio::time::interval(poll_interval);
+    let mut last_polled = None;
+
+    loop {
+        interval.tick().await;
+
+        let latest = match l1_provider.get_block_number().await {
+            Ok(latest) => latest,
+            Err(err) => {
+                warn!("Polling the latest L1 block failed: {}", err);
+                continue;
+            }
+        };
+
+        // Events before the first poll are left to the regular scan
+        let Some(from) = last_polled.map(|block: u64| block + 1) else {
+            last_polled = Some(latest);
+            continue;
+        };
+        if latest < from {
+            continue;
+        }
+
+        match l1_provider
+            .get_logs(&data_commitment_filter(&recorder.blobstream, from, latest))
+            .await
+        {
+            Ok(logs) => {
+                for log in &logs {
+                    recorder.record(log);
+                }
+                last_polled = Some(latest);
+            }
+            Err(err) => warn!(
+                "Polling Blobstream events in L1 blocks {}..={} failed: {}",
+                from, latest, err
+            ),
+        }
+    }
+}
+
+impl EventRecorder {
+    /// Caches the event carried by `log`, or invalidates the cache from its L1 block if the log
+    /// was removed by a reorg.
+    fn record(&self, log: &Log) {
+        if log.removed {
+            if let Some(number) = log.block_number {
+                let dropped = self.cache.invalidate_above(number.saturating_sub(1));
+                warn!(
+                    "Blobstream event in L1 block {} was reorged out, dropped {} cached events",
+                    number, dropped
+                );
+            }
+            return;
+        }
+
+        let Some(event) = self.blobstream.decode_data_commitment(log) else {
+            return;
+        };
+        if let Err(err) = check_data_commitment_range(&event, self.data_commitment_max) {
+            warn!("Dropping Blobstream event: {}", err);
+            return;
+        }
+
+        debug!(
+            "Caching Blobstream data commitment for Celestia blocks {}..{}",
+            event.start_block, event.end_block
+        );
+        self.cache.insert(event);
+    }
+}
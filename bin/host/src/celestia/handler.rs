@@ -1,19 +1,33 @@
 //! [HintHandler] for the [CelestiaaChainHost].
 
-use alloy_primitives::{keccak256, Bytes};
-use anyhow::{ensure, Result};
+use alloy_primitives::{hex, Bytes};
+use alloy_provider::RootProvider;
+use anyhow::{anyhow, Result};
 use async_trait::async_trait;
-use celestia_rpc::BlobClient;
-use celestia_types::Commitment;
-use hana_oracle::{hint::HintWrapper, payload::OraclePayload};
-use hana_proofs::blobstream_inclusion::get_blobstream_proof;
+use celestia_rpc::Client;
+use celestia_types::{Blob, Commitment};
+use hana_oracle::{
+    hint::{CelestiaHint, HintWrapper},
+    payload::{CelestiaPayload, IndexedOraclePayload, NamespaceAbsencePayload, OraclePayload},
+};
+use hana_proofs::{
+    blobstream_inclusion::{
+        get_blobstream_proof, get_blobstream_proof_with_event, get_namespace_absence_proof,
+        ProofConfig,
+    },
+    contract::Sp1Blobstream,
+};
 use kona_host::{
     single::SingleChainHintHandler, HintHandler, OnlineHostBackendCfg, SharedKeyValueStore,
 };
-use kona_preimage::{PreimageKey, PreimageKeyType};
 use kona_proof::Hint;
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+use tracing::warn;
 
-use crate::celestia::cfg::CelestiaChainHost;
+use crate::celestia::{cfg::CelestiaChainHost, CelestiaChainProviders, PayloadArchive};
 
 /// The [HintHandler] for the [CelestiaChainHost].
 #[derive(Debug, Clone, Copy)]
@@ -49,60 +63,387 @@ impl HintHandler for CelestiaChainHintHandler {
                 }
             }
             HintWrapper::CelestiaDA => {
-                ensure!(hint.data.len() == 40, "Invalid hint data length");
-
-                let height = u64::from_le_bytes(hint.data[0..8].try_into().unwrap());
+                let celestia_hint = CelestiaHint::decode(hint.ty, &hint.data)?;
+                let CelestiaHint::Commitment { height, commitment } = celestia_hint else {
+                    unreachable!("celestia-da hints decode to commitment hints");
+                };
+                providers.celestia.stats.record_hint();
+                if is_stored(&celestia_hint, &kv).await {
+                    return Ok(());
+                }
 
-                let hash_array: [u8; 32] =
-                    hint.data[8..40].try_into().expect("Slice must be 32 bytes");
-                let commitment = Commitment::new(hash_array);
+                let started = Instant::now();
+                let mut timings = HintTimings::default();
+                let payload =
+                    build_blob_payload(providers, height, commitment, &mut timings).await?;
+                warn_if_slow(cfg, &celestia_hint, started.elapsed(), &timings);
+                let archive = cfg.celestia_args.archive();
+                store_celestia_payload(payload, &celestia_hint, archive.as_ref(), kv).await?;
+            }
+            HintWrapper::CelestiaDAIndex => {
+                let celestia_hint = CelestiaHint::decode(hint.ty, &hint.data)?;
+                let CelestiaHint::Index { height, index } = celestia_hint else {
+                    unreachable!("celestia-da-index hints decode to index hints");
+                };
+                providers.celestia.stats.record_hint();
+                if is_stored(&celestia_hint, &kv).await {
+                    return Ok(());
+                }
 
-                let blob = match providers
-                    .celestia
-                    .client
-                    .blob_get(height, providers.celestia.namespace, commitment)
-                    .await
-                {
-                    Ok(blob) => blob,
-                    Err(e) => anyhow::bail!("celestia blob not found: {:#}", e),
+                let started = Instant::now();
+                let mut timings = HintTimings::default();
+                let payload = build_index_payload(providers, height, index, &mut timings).await?;
+                warn_if_slow(cfg, &celestia_hint, started.elapsed(), &timings);
+                let archive = cfg.celestia_args.archive();
+                store_celestia_payload(payload, &celestia_hint, archive.as_ref(), kv).await?;
+            }
+            HintWrapper::CelestiaDABlobs => {
+                let celestia_hint = CelestiaHint::decode(hint.ty, &hint.data)?;
+                let CelestiaHint::Blobs { height, commitment } = celestia_hint else {
+                    unreachable!("celestia-da-blobs hints decode to blobs hints");
                 };
+                providers.celestia.stats.record_hint();
+                if is_stored(&celestia_hint, &kv).await {
+                    return Ok(());
+                }
 
-                let data = blob.data.clone();
+                let started = Instant::now();
+                let mut timings = HintTimings::default();
+                let payload =
+                    build_blobs_payload(providers, height, commitment, &mut timings).await?;
+                warn_if_slow(cfg, &celestia_hint, started.elapsed(), &timings);
+                let archive = cfg.celestia_args.archive();
+                store_celestia_payload(payload, &celestia_hint, archive.as_ref(), kv).await?;
+            }
+        }
+        Ok(())
+    }
+}
 
-                let blobstream_proof = get_blobstream_proof(
-                    providers.celestia.client.as_ref(),
-                    providers.l1(),
-                    height,
-                    blob,
-                    providers.celestia.blobstream_address,
-                )
-                .await?;
-
-                let payload = OraclePayload::new(
-                    Bytes::from(data),
-                    blobstream_proof.data_root,
-                    blobstream_proof.data_commitment,
-                    blobstream_proof.data_root_tuple_proof,
-                    blobstream_proof.share_proof,
-                    blobstream_proof.proof_nonce,
-                    blobstream_proof.storage_root,
-                    blobstream_proof.storage_proof,
-                )
-                .to_bytes()
-                .expect("failed to serialize celestia oracle payload");
+/// Time spent on the steps of serving a Celestia hint
+#[derive(Debug, Default, Clone, Copy)]
+struct HintTimings {
+    /// Fetching the blob from the Celestia node
+    blob: Duration,
+    /// Fetching the header and finding the Blobstream data commitment event of the height
+    scan: Duration,
+    /// Fetching and verifying the share and Blobstream proofs, or the namespace absence proof
+    proof: Duration,
+}
 
-                let mut kv_lock = kv.write().await;
+impl fmt::Display for HintTimings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "blob fetch {:?}, event scan {:?}, proof fetch and verification {:?}",
+            self.blob, self.scan, self.proof
+        )
+    }
+}
 
-                let celestia_commitment_hash = keccak256(&hint.data);
+/// Warns if serving `hint` took longer than the configured slow hint threshold, with the time
+/// spent on each step.
+fn warn_if_slow(
+    cfg: &CelestiaChainHost,
+    hint: &CelestiaHint,
+    elapsed: Duration,
+    timings: &HintTimings,
+) {
+    let Some(threshold) = cfg.celestia_args.slow_hint_threshold() else {
+        return;
+    };
+    if elapsed <= threshold {
+        return;
+    }
 
-                // store the blob data as a the preimage behind the hash of the height + blob commitment
-                kv_lock.set(
-                    PreimageKey::new(*celestia_commitment_hash, PreimageKeyType::GlobalGeneric)
-                        .into(),
-                    payload.into(),
-                )?;
-            }
+    match hint {
+        CelestiaHint::Commitment { height, commitment } => warn!(
+            "Slow celestia-da hint for height {} and commitment {}: took {:?}, {}",
+            height,
+            hex::encode(commitment.hash()),
+            elapsed,
+            timings
+        ),
+        CelestiaHint::Index { height, index } => warn!(
+            "Slow celestia-da-index hint for height {} and index {}: took {:?}, {}",
+            height, index, elapsed, timings
+        ),
+        CelestiaHint::Blobs { height, commitment } => warn!(
+            "Slow celestia-da-blobs hint for height {} and commitment {}: took {:?}, {}",
+            height,
+            hex::encode(commitment.hash()),
+            elapsed,
+            timings
+        ),
+    }
+}
+
+/// Returns whether the payload for the given hint is already in the key-value store, e.g.
+/// prefetched or served for an earlier hint.
+pub(crate) async fn is_stored(hint: &CelestiaHint, kv: &SharedKeyValueStore) -> bool {
+    kv.read().await.get(hint.preimage_key().into()).is_some()
+}
+
+/// Builds the [CelestiaPayload] for the blob referenced by `hint`, or the proof of the
+/// namespace's absence if the node has no such blob, without storing it.
+///
+/// Suits callers wanting the payload itself, e.g. tests or tools inspecting it.
+pub async fn build_hint_payload(
+    providers: &CelestiaChainProviders,
+    hint: &CelestiaHint,
+) -> Result<CelestiaPayload> {
+    let mut timings = HintTimings::default();
+    match *hint {
+        CelestiaHint::Commitment { height, commitment } => {
+            build_blob_payload(providers, height, commitment, &mut timings).await
+        }
+        CelestiaHint::Index { height, index } => {
+            build_index_payload(providers, height, index, &mut timings).await
+        }
+        CelestiaHint::Blobs { height, commitment } => {
+            build_blobs_payload(providers, height, commitment, &mut timings).await
         }
-        Ok(())
     }
 }
+
+/// Fetches the blob referenced by `hint` and stores its payload behind the hint's preimage key,
+/// see [build_hint_payload].
+pub(crate) async fn fetch_hint_payload(
+    providers: &CelestiaChainProviders,
+    hint: &CelestiaHint,
+    archive: Option<&PayloadArchive>,
+    kv: SharedKeyValueStore,
+) -> Result<()> {
+    let payload = build_hint_payload(providers, hint).await?;
+    store_celestia_payload(payload, hint, archive, kv).await
+}
+
+/// Fetches the blob with the given commitment at `height` and builds its [OraclePayload], or the
+/// proof of the namespace's absence if the node has no such blob, recording the time spent in
+/// `timings`.
+async fn build_blob_payload(
+    providers: &CelestiaChainProviders,
+    height: u64,
+    commitment: Commitment,
+    timings: &mut HintTimings,
+) -> Result<CelestiaPayload> {
+    providers.celestia.check_height(height).await?;
+
+    let started = Instant::now();
+    let blob = providers.celestia.blob_get(height, commitment).await;
+    timings.blob = started.elapsed();
+    let blob = match blob {
+        Ok(blob) => blob,
+        Err(e) if e.is_not_found() => {
+            // The height may legitimately hold no data in the rollup's namespace, in which case
+            // its absence is proven rather than failing the hint.
+            return build_namespace_absence(providers, height, timings)
+                .await
+                .map_err(|absence_err| {
+                    anyhow!(
+                        "celestia blob not found: {:#}, and namespace absence could not be proven: {:#}",
+                        e,
+                        absence_err
+                    )
+                });
+        }
+        // The node failed to answer, or is untrusted and served a blob other than the one asked
+        // for, neither of which proves the blob missing
+        Err(e) => return Err(e.into()),
+    };
+
+    build_payload(providers, height, blob, timings)
+        .await
+        .map(CelestiaPayload::Blob)
+}
+
+/// Fetches the blob whose first share is at `index` in the block at `height` and builds its
+/// [OraclePayload], or the proof of the namespace's absence if the node has no such blob,
+/// recording the time spent in `timings`.
+async fn build_index_payload(
+    providers: &CelestiaChainProviders,
+    height: u64,
+    index: u64,
+    timings: &mut HintTimings,
+) -> Result<CelestiaPayload> {
+    providers.celestia.check_height(height).await?;
+
+    let started = Instant::now();
+    let blob = providers.celestia.blob_get_at_index(height, index).await;
+    timings.blob = started.elapsed();
+    let blob = match blob {
+        Ok(blob) => blob,
+        Err(e) if e.is_not_found() => {
+            return build_namespace_absence(providers, height, timings)
+                .await
+                .map_err(|absence_err| {
+                    anyhow!(
+                        "{:#}, and namespace absence could not be proven: {:#}",
+                        e,
+                        absence_err
+                    )
+                });
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    build_payload(providers, height, blob, timings)
+        .await
+        .map(CelestiaPayload::Blob)
+}
+
+/// Fetches every blob with the given commitment at `height` and builds their
+/// [IndexedOraclePayload]s, or the proof of the namespace's absence if the node has no such
+/// blob, recording the time spent in `timings`.
+async fn build_blobs_payload(
+    providers: &CelestiaChainProviders,
+    height: u64,
+    commitment: Commitment,
+    timings: &mut HintTimings,
+) -> Result<CelestiaPayload> {
+    providers.celestia.check_height(height).await?;
+
+    let started = Instant::now();
+    let blobs = providers.celestia.blobs_get(height, commitment).await;
+    timings.blob = started.elapsed();
+    let blobs = match blobs {
+        Ok(blobs) => blobs,
+        Err(e) if e.is_not_found() => {
+            return build_namespace_absence(providers, height, timings)
+                .await
+                .map_err(|absence_err| {
+                    anyhow!(
+                        "{:#}, and namespace absence could not be proven: {:#}",
+                        e,
+                        absence_err
+                    )
+                });
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut payloads = Vec::with_capacity(blobs.len());
+    for (index, blob) in blobs {
+        let payload = build_payload(providers, height, blob, timings).await?;
+        payloads.push(IndexedOraclePayload::new(index, payload));
+    }
+
+    Ok(CelestiaPayload::Blobs(payloads))
+}
+
+/// Builds the [OraclePayload] for the given blob, reusing the cached header and data commitment
+/// event of its height. The time spent is added to `timings`, which may cover several blobs.
+async fn build_payload(
+    providers: &CelestiaChainProviders,
+    height: u64,
+    blob: Blob,
+    timings: &mut HintTimings,
+) -> Result<OraclePayload> {
+    let started = Instant::now();
+    let (header, event) = tokio::try_join!(
+        providers.celestia.header_get(height),
+        providers.celestia.data_commitment(providers.l1(), height),
+    )?;
+    let scan = started.elapsed();
+    timings.scan += scan;
+
+    let data = blob.data.clone();
+    let blobstream_proof = get_blobstream_proof_with_event(
+        providers.celestia.client(),
+        providers.l1(),
+        &header.header,
+        blob,
+        &providers.celestia.blobstream,
+        event,
+        &providers.celestia.proof_config,
+    )
+    .await?;
+    timings.proof += started.elapsed() - scan;
+    providers
+        .celestia
+        .stats
+        .record_proof_time(started.elapsed());
+    providers.celestia.stats.record_blob(height, data.len());
+
+    Ok(OraclePayload::from_proof(
+        Bytes::from(data),
+        blobstream_proof,
+    ))
+}
+
+/// Fetches the Blobstream proofs for the given blob and assembles them into an [OraclePayload].
+pub(crate) async fn build_oracle_payload(
+    client: &Client,
+    l1_provider: &RootProvider,
+    height: u64,
+    blob: Blob,
+    blobstream: &Sp1Blobstream,
+    proof_config: &ProofConfig,
+) -> Result<OraclePayload> {
+    let data = blob.data.clone();
+
+    let blobstream_proof =
+        get_blobstream_proof(client, l1_provider, height, blob, blobstream, proof_config).await?;
+
+    Ok(OraclePayload::from_proof(
+        Bytes::from(data),
+        blobstream_proof,
+    ))
+}
+
+/// Builds the [NamespaceAbsencePayload] for the provider's namespace at the given height.
+async fn build_namespace_absence(
+    providers: &CelestiaChainProviders,
+    height: u64,
+    timings: &mut HintTimings,
+) -> Result<CelestiaPayload> {
+    let started = Instant::now();
+    let (dah, namespace_data, inclusion_proof) = get_namespace_absence_proof(
+        providers.celestia.client(),
+        providers.l1(),
+        height,
+        providers.celestia.namespace,
+        &providers.celestia.blobstream,
+        &providers.celestia.proof_config,
+    )
+    .await?;
+    timings.proof = started.elapsed();
+    providers
+        .celestia
+        .stats
+        .record_proof_time(started.elapsed());
+    providers.celestia.stats.record_height(height);
+
+    Ok(CelestiaPayload::NamespaceAbsent(
+        NamespaceAbsencePayload::new(
+            providers.celestia.namespace,
+            dah,
+            namespace_data,
+            inclusion_proof,
+        ),
+    ))
+}
+
+/// Stores the serialized [CelestiaPayload] as the preimage behind the hint's preimage key, and in
+/// the payload archive if one is configured.
+async fn store_celestia_payload(
+    payload: CelestiaPayload,
+    hint: &CelestiaHint,
+    archive: Option<&PayloadArchive>,
+    kv: SharedKeyValueStore,
+) -> Result<()> {
+    let payload = payload
+        .to_bytes()
+        .expect("failed to serialize celestia oracle payload");
+
+    if let Some(archive) = archive {
+        archive.write(hint, &payload)?;
+    }
+
+    // store the blob data as a the preimage behind the hint's preimage key
+    kv.write()
+        .await
+        .set(hint.preimage_key().into(), payload.into())?;
+
+    Ok(())
+}
@@ -0,0 +1,73 @@
+//! Cache of the Celestia headers fetched by the host.
+//!
+//! A finalized Celestia header never changes, so the headers are kept until evicted to make
+//! room, and every blob proven at a height reuses the header fetched for the first one.
+
+use celestia_types::{hash::Hash, AppVersion, ExtendedHeader};
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, RwLock},
+};
+
+/// Number of headers kept before the lowest heights are evicted
+const HEADER_CACHE_CAPACITY: usize = 64;
+
+/// A Celestia header along with the data root its blobs are proven against
+#[derive(Debug, Clone)]
+pub struct CachedHeader {
+    /// The extended header
+    pub header: ExtendedHeader,
+    /// The data root of the block, the hash of the header's data availability header
+    pub data_root: Hash,
+}
+
+impl CachedHeader {
+    /// Returns the app version the block was produced under, if it is one known to the host.
+    pub fn app_version(&self) -> Option<AppVersion> {
+        AppVersion::from_u64(self.header.header.version.app)
+    }
+}
+
+impl From<ExtendedHeader> for CachedHeader {
+    fn from(header: ExtendedHeader) -> Self {
+        let data_root = header.dah.hash();
+        Self { header, data_root }
+    }
+}
+
+/// Celestia headers keyed by height. Derivation walks heights upwards, so the lowest heights are
+/// evicted first.
+#[derive(Debug, Default)]
+pub struct HeaderCache {
+    headers: RwLock<BTreeMap<u64, Arc<CachedHeader>>>,
+}
+
+impl HeaderCache {
+    /// Returns the cached header at `height`, if any.
+    pub fn get(&self, height: u64) -> Option<Arc<CachedHeader>> {
+        self.headers
+            .read()
+            .expect("header cache poisoned")
+            .get(&height)
+            .cloned()
+    }
+
+    /// Caches the header, returning the cached entry.
+    pub fn insert(&self, header: ExtendedHeader) -> Arc<CachedHeader> {
+        let height = header.height().value();
+        let header = Arc::new(CachedHeader::from(header));
+
+        let mut headers = self.headers.write().expect("header cache poisoned");
+        headers.insert(height, header.clone());
+        while headers.len() > HEADER_CACHE_CAPACITY {
+            headers.pop_first();
+        }
+
+        header
+    }
+
+    /// Drops every cached header.
+    pub fn clear(&self) {
+        self.headers.write().expect("header cache poisoned").clear();
+    }
+}
@@ -0,0 +1,179 @@
+//! HTTP settings of the L1, L2 and Celestia RPC clients, for gateways expecting extra headers or
+//! networks only reachable through a proxy.
+
+use alloy_provider::{network::Network, RootProvider};
+use alloy_rpc_client::RpcClient;
+use alloy_transport_http::Http;
+use anyhow::{anyhow, bail, Context, Result};
+use celestia_rpc::Client;
+use jsonrpsee::{http_client::HttpClientBuilder, ws_client::WsClientBuilder};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION};
+use reqwest::Url;
+use std::{error::Error as StdError, fmt, str::FromStr};
+use thiserror::Error;
+
+use super::auth::AuthToken;
+
+/// Largest response accepted from the Celestia node, leaving room for the base64 encoding of the
+/// largest blobs
+const MAX_CELESTIA_RESPONSE_SIZE: u32 = 256 * 1024 * 1024;
+
+/// An extra header sent with every RPC request, parsed from `Name: value`. The value is redacted
+/// from debug output, as it usually carries an API key.
+#[derive(Clone, PartialEq, Eq)]
+pub struct RpcHeader {
+    name: HeaderName,
+    value: HeaderValue,
+}
+
+impl FromStr for RpcHeader {
+    type Err = anyhow::Error;
+
+    fn from_str(header: &str) -> Result<Self> {
+        let (name, value) = header
+            .split_once(':')
+            .ok_or_else(|| anyhow!("RPC header must be `Name: value`"))?;
+
+        let name = HeaderName::from_str(name.trim()).context("invalid RPC header name")?;
+        let mut value = HeaderValue::from_str(value.trim()).context("invalid RPC header value")?;
+        value.set_sensitive(true);
+
+        Ok(Self { name, value })
+    }
+}
+
+impl fmt::Debug for RpcHeader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "RpcHeader({}: <redacted>)", self.name)
+    }
+}
+
+fn header_map(headers: &[RpcHeader]) -> HeaderMap {
+    headers
+        .iter()
+        .map(|header| (header.name.clone(), header.value.clone()))
+        .collect()
+}
+
+/// Creates an HTTP provider for the node at `url`, sending `headers` with every request and
+/// going through `proxy` if set.
+pub fn http_provider_with<N: Network>(
+    url: &str,
+    headers: &[RpcHeader],
+    proxy: Option<&str>,
+) -> Result<RootProvider<N>> {
+    let mut builder = reqwest::Client::builder().default_headers(header_map(headers));
+    if let Some(proxy) = proxy {
+        builder = builder.proxy(reqwest::Proxy::all(proxy).context("invalid HTTP proxy")?);
+    }
+    let client = builder.build().context("building the HTTP client")?;
+    let url = url
+        .parse()
+        .with_context(|| format!("invalid node URL {url}"))?;
+
+    Ok(RootProvider::new(RpcClient::new(
+        Http::with_client(client, url),
+        false,
+    )))
+}
+
+/// Query parameters of a connection string whose value is redacted from errors and logs
+const SECRET_QUERY_PARAMS: &[&str] = &["token", "auth", "key", "secret", "password"];
+
+/// A failure connecting to the Celestia node
+#[derive(Error, Debug)]
+#[error("failed connecting to the Celestia node at {connection}: {source}")]
+pub struct CelestiaClientError {
+    /// The connection string, with its credentials redacted
+    pub connection: String,
+    /// The error building the client
+    pub source: Box<dyn StdError + Send + Sync>,
+}
+
+/// Returns `connection` with its user info and the values of its token-like query parameters
+/// redacted, so it can be logged. Connection strings which are not URLs are returned as is.
+pub fn redact_connection(connection: &str) -> String {
+    let Ok(mut url) = Url::parse(connection) else {
+        return connection.to_string();
+    };
+
+    if !url.username().is_empty() || url.password().is_some() {
+        let _ = url.set_username("redacted");
+        let _ = url.set_password(None);
+    }
+
+    let is_secret = |name: &str| {
+        let name = name.to_ascii_lowercase();
+        SECRET_QUERY_PARAMS
+            .iter()
+            .any(|secret| name.contains(secret))
+    };
+    if url.query_pairs().any(|(name, _)| is_secret(&name)) {
+        let pairs: Vec<(String, String)> = url
+            .query_pairs()
+            .map(|(name, value)| {
+                let value = if is_secret(&name) {
+                    "redacted".to_string()
+                } else {
+                    value.into_owned()
+                };
+                (name.into_owned(), value)
+            })
+            .collect();
+        url.query_pairs_mut().clear().extend_pairs(pairs);
+    }
+
+    url.to_string()
+}
+
+/// Connects to the Celestia node at `connection`, sending `headers` along with the auth token
+/// with every request.
+///
+/// The Celestia client has no proxy support, so the node must be reachable directly.
+pub async fn celestia_client(
+    connection: &str,
+    auth_token: Option<&AuthToken>,
+    headers: &[RpcHeader],
+) -> Result<Client, CelestiaClientError> {
+    connect_celestia(connection, auth_token, headers)
+        .await
+        .map_err(|err| CelestiaClientError {
+            connection: redact_connection(connection),
+            source: err.into(),
+        })
+}
+
+async fn connect_celestia(
+    connection: &str,
+    auth_token: Option<&AuthToken>,
+    headers: &[RpcHeader],
+) -> Result<Client> {
+    if headers.is_empty() {
+        return Ok(Client::new(connection, auth_token.map(AuthToken::expose)).await?);
+    }
+
+    let mut headers = header_map(headers);
+    if let Some(token) = auth_token {
+        let mut value = HeaderValue::from_str(&format!("Bearer {}", token.expose()))
+            .context("invalid Celestia auth token")?;
+        value.set_sensitive(true);
+        headers.insert(AUTHORIZATION, value);
+    }
+
+    match connection.split_once(':').map(|(protocol, _)| protocol) {
+        Some("http" | "https") => Ok(Client::Http(
+            HttpClientBuilder::default()
+                .set_headers(headers)
+                .max_response_size(MAX_CELESTIA_RESPONSE_SIZE)
+                .build(connection)?,
+        )),
+        Some("ws" | "wss") => Ok(Client::Ws(
+            WsClientBuilder::default()
+                .set_headers(headers)
+                .max_response_size(MAX_CELESTIA_RESPONSE_SIZE)
+                .build(connection)
+                .await?,
+        )),
+        _ => bail!("unsupported protocol, expected http(s) or ws(s)"),
+    }
+}
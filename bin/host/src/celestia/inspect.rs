@@ -0,0 +1,262 @@
+//! This module contains the `inspect-payload` subcommand, which prints a Celestia oracle payload
+//! and checks each of its proofs.
+
+use alloy_primitives::{Bytes, B256};
+use alloy_provider::{Provider, RootProvider};
+use anyhow::{anyhow, bail, Result};
+use celestia_rpc::BlobClient;
+use celestia_types::Commitment;
+use clap::Parser;
+use hana_blobstream::blobstream::{
+    verify_data_commitment_storage, verify_data_root_tuple, verify_share_proof,
+};
+use hana_oracle::{
+    payload::{CelestiaPayload, NamespaceAbsencePayload, OraclePayload},
+    verify::verify_namespace_absence,
+};
+use kona_cli::cli_styles;
+use serde::Serialize;
+use std::path::PathBuf;
+
+use super::{
+    cfg::{supported_chains, ChainId},
+    handler::build_oracle_payload,
+    http::celestia_client,
+    namespace::parse_namespace,
+    CelestiaCfg,
+};
+
+/// Fetches or loads a Celestia oracle payload, prints it and checks each of its proofs.
+#[derive(Parser, Serialize, Clone, Debug)]
+#[command(styles = cli_styles())]
+pub struct InspectPayloadCmd {
+    /// Celestia block height of the blob
+    #[clap(long)]
+    pub height: u64,
+    /// Share commitment of the blob, required unless a payload file is given
+    #[clap(long, required_unless_present = "payload")]
+    pub commitment: Option<B256>,
+    /// Path to a serialized payload to verify offline instead of fetching it
+    #[clap(long)]
+    pub payload: Option<PathBuf>,
+    /// Address of the L1 execution node used to fetch the Blobstream proofs
+    #[clap(long, env)]
+    pub l1_node_address: Option<String>,
+    #[clap(flatten)]
+    pub celestia_args: CelestiaCfg,
+}
+
+impl InspectPayloadCmd {
+    /// Runs the subcommand, erroring if any of the payload's proofs fails to verify.
+    pub async fn run(self) -> Result<()> {
+        let payload = match &self.payload {
+            Some(path) => CelestiaPayload::from_bytes(&std::fs::read(path)?)
+                .map_err(|e| anyhow!("Error deserializing Celestia payload: {e}"))?,
+            None => CelestiaPayload::Blob(self.fetch_payload().await?),
+        };
+
+        let data_commitments_slot = self.celestia_args.data_commitments_slot_or_default();
+        let verified = match payload {
+            CelestiaPayload::Blob(payload) => {
+                inspect_oracle_payload(&payload, self.height, data_commitments_slot)
+            }
+            CelestiaPayload::NamespaceAbsent(payload) => {
+                inspect_namespace_absence(&payload, self.height, data_commitments_slot)
+            }
+            CelestiaPayload::Blobs(payloads) => payloads.iter().fold(true, |verified, indexed| {
+                println!("share index:         {}", indexed.index);
+                let share_index = indexed
+                    .payload
+                    .proof
+                    .verify_share_range(indexed.index)
+                    .map_err(|e| e.to_string());
+
+                let blob_verified =
+                    inspect_oracle_payload(&indexed.payload, self.height, data_commitments_slot);
+                let index_verified = report_step("share index", share_index);
+
+                verified && blob_verified && index_verified
+            }),
+        };
+
+        if !verified {
+            bail!("Celestia payload verification failed");
+        }
+        Ok(())
+    }
+
+    /// Fetches the blob and its Blobstream proofs from the configured nodes.
+    async fn fetch_payload(&self) -> Result<OraclePayload> {
+        let commitment = self
+            .commitment
+            .ok_or_else(|| anyhow!("Blob commitment must be set"))?;
+        let namespace = parse_namespace(
+            self.celestia_args
+                .namespace
+                .as_ref()
+                .ok_or_else(|| anyhow!("Celestia Namespace must be set"))?,
+            self.celestia_args.namespace_version,
+        )?;
+
+        let auth_token = self.celestia_args.auth_token()?;
+        let client = celestia_client(
+            self.celestia_args
+                .celestia_connection
+                .as_ref()
+                .ok_or_else(|| anyhow!("Celestia connection must be set"))?,
+            auth_token.as_ref(),
+            &self.celestia_args.rpc_headers,
+        )
+        .await?;
+        let l1_provider: RootProvider = self.celestia_args.http_provider(
+            self.l1_node_address
+                .as_ref()
+                .ok_or_else(|| anyhow!("L1 node address must be set"))?,
+        )?;
+
+        let chain_id = l1_provider.get_chain_id().await?;
+        let chain = ChainId::from_u64(chain_id).ok_or_else(|| {
+            anyhow!(
+                "Unknown chain id {chain_id} for blobstream address, supported chains: {}",
+                supported_chains()
+            )
+        })?;
+
+        let blob = client
+            .blob_get(self.height, namespace, Commitment::new(commitment.0))
+            .await?;
+
+        build_oracle_payload(
+            &client,
+            &l1_provider,
+            self.height,
+            blob,
+            &self
+                .celestia_args
+                .blobstream_contract(chain.blobstream_address()),
+            &self.celestia_args.proof_config(Some(chain)),
+        )
+        .await
+    }
+}
+
+/// Prints the [OraclePayload] and the outcome of each of its proofs, returning whether all of them
+/// verified.
+fn inspect_oracle_payload(
+    payload: &OraclePayload,
+    height: u64,
+    data_commitments_slot: u32,
+) -> bool {
+    let proof = &payload.proof;
+
+    println!("height:              {height}");
+    println!("blob size:           {} bytes", payload.blob.len());
+    println!("data root:           {}", proof.data_root);
+    println!("data commitment:     {}", proof.data_commitment);
+    println!("proof nonce:         {}", proof.proof_nonce);
+    println!("storage root:        {}", proof.storage_root);
+    println!(
+        "storage proof block: {}",
+        storage_proof_block(proof.storage_proof_block)
+    );
+    println!(
+        "storage proof nodes: {}",
+        storage_proof_nodes(proof.storage_proof.as_deref())
+    );
+    println!("shares:              {}", proof.share_proof.shares().len());
+
+    let share_proof =
+        verify_share_proof(&proof.share_proof, proof.data_root).map_err(|e| e.to_string());
+    let data_root_tuple_proof = verify_data_root_tuple(
+        &proof.data_root_tuple_proof,
+        height,
+        &proof.data_root,
+        proof.data_commitment,
+    )
+    .map_err(|e| e.to_string());
+    let storage_proof = match &proof.storage_proof {
+        Some(storage_proof) => verify_data_commitment_storage(
+            proof.storage_root,
+            storage_proof.clone(),
+            data_commitments_slot,
+            proof.proof_nonce,
+            proof.data_commitment,
+        )
+        .map_err(|e| e.to_string()),
+        None => Err("payload carries no storage proof".to_string()),
+    };
+
+    [
+        report_step("share proof", share_proof),
+        report_step("data root tuple proof", data_root_tuple_proof),
+        report_step("storage proof", storage_proof),
+    ]
+    .iter()
+    .all(|verified| *verified)
+}
+
+/// Prints the [NamespaceAbsencePayload] and the outcome of its verification, returning whether it
+/// verified.
+fn inspect_namespace_absence(
+    payload: &NamespaceAbsencePayload,
+    height: u64,
+    data_commitments_slot: u32,
+) -> bool {
+    println!("height:              {height}");
+    println!("absent namespace:    {:?}", payload.namespace);
+    println!("data root:           {}", payload.dah.hash());
+    println!("absence proof rows:  {}", payload.namespace_data.rows.len());
+    println!(
+        "data commitment:     {}",
+        payload.inclusion_proof.data_commitment
+    );
+    println!(
+        "proof nonce:         {}",
+        payload.inclusion_proof.proof_nonce
+    );
+    println!(
+        "storage root:        {}",
+        payload.inclusion_proof.storage_root
+    );
+    println!(
+        "storage proof block: {}",
+        storage_proof_block(payload.inclusion_proof.storage_proof_block)
+    );
+    println!(
+        "storage proof nodes: {}",
+        storage_proof_nodes(payload.inclusion_proof.storage_proof.as_deref())
+    );
+
+    report_step(
+        "namespace absence",
+        verify_namespace_absence(payload, height, data_commitments_slot, false)
+            .map_err(|e| e.to_string()),
+    )
+}
+
+/// Describes the number of nodes of a storage proof, which the host may have skipped.
+fn storage_proof_nodes(storage_proof: Option<&[Bytes]>) -> String {
+    storage_proof.map_or_else(
+        || "none, skipped by the host".to_string(),
+        |nodes| nodes.len().to_string(),
+    )
+}
+
+/// Describes the L1 block a storage proof was taken at.
+fn storage_proof_block(block: Option<B256>) -> String {
+    block.map_or_else(|| "unknown".to_string(), |hash| hash.to_string())
+}
+
+/// Prints the outcome of a verification step, returning whether it passed.
+fn report_step(name: &str, result: Result<(), String>) -> bool {
+    match result {
+        Ok(()) => {
+            println!("{name:<21}ok");
+            true
+        }
+        Err(err) => {
+            println!("{name:<21}FAILED: {err}");
+            false
+        }
+    }
+}
@@ -1,12 +1,52 @@
 //! This module contains the celestia-single-chain mode for the host.
 mod cfg;
-pub use cfg::{CelestiaCfg, CelestiaChainHost};
+pub use cfg::{CelestiaCfg, CelestiaChainHost, L1ProofBlock, PinnedCommitment};
+
+mod archive;
+pub use archive::PayloadArchive;
+
+mod backfill;
+pub use backfill::PrefetchRangeCmd;
+
+mod auth;
+pub use auth::{resolve_auth_token, AuthToken, AUTH_TOKEN_ENV};
+
+mod namespace;
+pub use namespace::{parse_namespace, NamespaceParseError};
+
+mod http;
+pub use http::{
+    celestia_client, http_provider_with, redact_connection, CelestiaClientError, RpcHeader,
+};
+
+mod commitment_cache;
+pub use commitment_cache::DataCommitmentCache;
+
+mod commitment_watcher;
+pub use commitment_watcher::{watch_data_commitments, COMMITMENT_POLL_INTERVAL};
+
+mod header_cache;
+pub use header_cache::{CachedHeader, HeaderCache};
 
 mod handler;
-pub use handler::CelestiaChainHintHandler;
+pub use handler::{build_hint_payload, CelestiaChainHintHandler};
+
+mod inspect;
+pub use inspect::InspectPayloadCmd;
+
+mod prefetch;
+pub use prefetch::{prefetch_payloads, read_prefetch_list, PrefetchReport};
+
+mod run_stats;
+pub use run_stats::{RunStats, RunSummary};
 
 mod providers;
 pub use providers::CelestiaChainProviders;
 
+mod verify_store;
+pub use verify_store::VerifyStoreCmd;
+
 mod online_provider;
-pub use online_provider::OnlineCelestiaProvider;
+pub use online_provider::{
+    CelestiaProviderError, OnlineCelestiaProvider, OnlineCelestiaProviderBuilder,
+};
@@ -0,0 +1,94 @@
+//! Parsing of the Celestia namespace given on the command line.
+
+use alloy_primitives::hex;
+use celestia_types::nmt::Namespace;
+use thiserror::Error;
+
+/// Size in bytes of a full namespace ID.
+const NAMESPACE_ID_SIZE: usize = 28;
+
+/// Size in bytes of the user-specifiable suffix of a version 0 namespace ID.
+const NAMESPACE_ID_V0_SIZE: usize = 10;
+
+/// An error parsing a Celestia namespace
+#[derive(Error, Debug)]
+pub enum NamespaceParseError {
+    /// The namespace is empty
+    #[error("Celestia namespace is empty")]
+    Empty,
+    /// The namespace is not hex encoded
+    #[error("Celestia namespace {namespace:?} is not valid hex: {source}")]
+    InvalidHex {
+        /// The namespace as given
+        namespace: String,
+        /// The decoding error
+        source: hex::FromHexError,
+    },
+    /// The decoded namespace has none of the accepted lengths
+    #[error(
+        "Celestia namespace of {0} bytes must be at most {NAMESPACE_ID_V0_SIZE} bytes (v0), a \
+         {NAMESPACE_ID_SIZE} byte ID or a 29 byte namespace"
+    )]
+    InvalidLength(usize),
+    /// The namespace is not valid for its version
+    #[error(
+        "Celestia namespace is not a valid version {version} namespace, try setting \
+         --namespace-version: {source}"
+    )]
+    InvalidForVersion {
+        /// The version, inferred or given
+        version: u8,
+        /// The error building the namespace
+        source: celestia_types::Error,
+    },
+    /// The namespace is reserved by Celestia, e.g. for transactions, pay-for-blob messages or
+    /// parity shares, so it never holds rollup blobs
+    #[error("Celestia namespace {0:?} is reserved by Celestia and never holds rollup blobs")]
+    Reserved(Namespace),
+}
+
+/// Parses a hex encoded Celestia namespace, with or without a `0x` prefix, rejecting the
+/// namespaces reserved by Celestia.
+///
+/// The version is inferred from the decoded length unless `version` is provided:
+/// - up to 10 bytes is the user-specifiable suffix of a version 0 namespace ID,
+/// - 28 bytes is a full namespace ID, which is version 0 if it carries the 18 leading zero
+///   bytes reserved by version 0 and version 1 otherwise,
+/// - 29 bytes is a full namespace, whose leading byte is the version.
+pub fn parse_namespace(
+    namespace: &str,
+    version: Option<u8>,
+) -> Result<Namespace, NamespaceParseError> {
+    let trimmed = namespace.trim();
+    let digits = trimmed.strip_prefix("0x").unwrap_or(trimmed);
+    if digits.is_empty() {
+        return Err(NamespaceParseError::Empty);
+    }
+
+    let bytes = hex::decode(digits).map_err(|source| NamespaceParseError::InvalidHex {
+        namespace: namespace.to_string(),
+        source,
+    })?;
+
+    let (inferred_version, id) = match bytes.len() {
+        len if len <= NAMESPACE_ID_V0_SIZE => (0, bytes.as_slice()),
+        NAMESPACE_ID_SIZE => {
+            let is_v0 = bytes[..NAMESPACE_ID_SIZE - NAMESPACE_ID_V0_SIZE]
+                .iter()
+                .all(|b| *b == 0);
+            (if is_v0 { 0 } else { 1 }, bytes.as_slice())
+        }
+        len if len == NAMESPACE_ID_SIZE + 1 => (bytes[0], &bytes[1..]),
+        len => return Err(NamespaceParseError::InvalidLength(len)),
+    };
+
+    let version = version.unwrap_or(inferred_version);
+    let namespace = Namespace::new(version, id)
+        .map_err(|source| NamespaceParseError::InvalidForVersion { version, source })?;
+
+    if namespace.is_reserved() {
+        return Err(NamespaceParseError::Reserved(namespace));
+    }
+
+    Ok(namespace)
+}
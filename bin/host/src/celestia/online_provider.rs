@@ -1,35 +1,678 @@
-use alloy_primitives::Address;
-use celestia_rpc::Client;
-use celestia_types::nmt::Namespace;
-use std::sync::Arc;
+use alloy_primitives::{hex, Bytes};
+use alloy_provider::{Provider, RootProvider};
+use anyhow::{ensure, Result};
+use async_trait::async_trait;
+use celestia_rpc::{BlobClient, Client, HeaderClient};
+use celestia_types::{hash::Hash, nmt::Namespace, Blob, Commitment};
+use hana_blobstream::blobstream::SP1BlobstreamDataCommitmentStored;
+use hana_celestia::{verify_blob_commitment, CelestiaProvider, DEFAULT_MAX_BLOB_BYTES};
+use hana_proofs::{
+    blobstream_inclusion::{
+        find_data_commitment_with_stats, find_data_commitments, with_timeout, ProofConfig,
+        RpcTimeout, ScanStats,
+    },
+    contract::Sp1Blobstream,
+};
+use jsonrpsee::core::ClientError;
+use kona_derive::errors::{PipelineError, PipelineErrorKind};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc,
+    },
+};
+use tracing::{debug, warn};
+
+use super::{
+    commitment_cache::DataCommitmentCache,
+    header_cache::{CachedHeader, HeaderCache},
+    run_stats::RunStats,
+};
+
+/// Fragment of the error message celestia-node returns when it holds no blob matching a request
+const BLOB_NOT_FOUND_MESSAGE: &str = "blob: not found";
+
+/// Errors returned while fetching blobs from the Celestia node
+#[derive(Debug, thiserror::Error)]
+pub enum CelestiaProviderError {
+    /// A Celestia RPC call failed, including when the node holds no matching blob, see
+    /// [CelestiaProviderError::is_not_found]
+    #[error("Celestia RPC error: {0}")]
+    Rpc(#[from] ClientError),
+    /// A Celestia RPC call timed out
+    #[error(transparent)]
+    RpcTimeout(#[from] RpcTimeout),
+    /// The namespace holds no blob starting at the share index
+    #[error("no celestia blob at index {index} for height {height}")]
+    NotFoundAtIndex {
+        /// The Celestia height of the block
+        height: u64,
+        /// The share index asked for
+        index: u64,
+    },
+    /// The namespace holds no blob with the commitment
+    #[error("no celestia blob with commitment {} at height {height}", hex::encode(.commitment.hash()))]
+    NotFound {
+        /// The Celestia height of the block
+        height: u64,
+        /// The commitment asked for
+        commitment: Commitment,
+    },
+    /// The node returned a blob without the index of its first share, which its blobs are
+    /// ordered by
+    #[error(
+        "celestia node returned a blob with commitment {} at height {height} without its share index",
+        hex::encode(.commitment.hash())
+    )]
+    MissingIndex {
+        /// The Celestia height of the block
+        height: u64,
+        /// The commitment of the blob
+        commitment: Commitment,
+    },
+    /// The node returned a blob with another commitment than the one asked for
+    #[error(
+        "celestia node returned a blob with commitment {} at height {height}, expected {}",
+        hex::encode(.returned.hash()),
+        hex::encode(.expected.hash())
+    )]
+    CommitmentMismatch {
+        /// The Celestia height of the block
+        height: u64,
+        /// The commitment asked for
+        expected: Commitment,
+        /// The commitment of the returned blob
+        returned: Commitment,
+    },
+    /// The node returned a blob whose data does not match its commitment
+    #[error(
+        "celestia node returned a blob at height {height} whose data does not match commitment {}",
+        hex::encode(.commitment.hash())
+    )]
+    BlobDataMismatch {
+        /// The Celestia height of the block
+        height: u64,
+        /// The commitment asked for
+        commitment: Commitment,
+    },
+    /// The node returned a blob in another namespace than the provider's
+    #[error(
+        "celestia node returned a blob in namespace {returned:?} at height {height}, expected {expected:?}"
+    )]
+    NamespaceMismatch {
+        /// The Celestia height of the block
+        height: u64,
+        /// The provider's namespace
+        expected: Namespace,
+        /// The namespace of the returned blob
+        returned: Namespace,
+    },
+    /// The block was produced under an app version unknown to the host, whose share layout the
+    /// blob commitments cannot be recomputed under
+    #[error("celestia block at height {height} has the unknown app version {version}")]
+    UnsupportedAppVersion {
+        /// The Celestia height of the block
+        height: u64,
+        /// The app version of the block
+        version: u64,
+    },
+    /// The blob is larger than the configured maximum
+    #[error("celestia blob at height {height} is {len} bytes, above the {max} byte limit")]
+    BlobTooLarge {
+        /// The Celestia height of the blob
+        height: u64,
+        /// The size of the blob in bytes
+        len: usize,
+        /// The maximum blob size in bytes
+        max: usize,
+    },
+}
+
+impl CelestiaProviderError {
+    /// Returns whether the error comes from the network, so retrying may succeed, as opposed to
+    /// the node serving data that failed verification.
+    pub const fn is_temporary(&self) -> bool {
+        matches!(self, Self::Rpc(_) | Self::RpcTimeout(_))
+    }
+
+    /// Returns whether the node answered that it holds no such blob, as opposed to failing to
+    /// answer or serving a blob failing verification.
+    pub fn is_not_found(&self) -> bool {
+        match self {
+            Self::NotFoundAtIndex { .. } | Self::NotFound { .. } => true,
+            Self::Rpc(ClientError::Call(err)) => err.message().contains(BLOB_NOT_FOUND_MESSAGE),
+            _ => false,
+        }
+    }
+
+    /// Returns whether the node served a blob failing verification, rather than no blob.
+    pub const fn is_verification_failure(&self) -> bool {
+        matches!(
+            self,
+            Self::CommitmentMismatch { .. }
+                | Self::BlobDataMismatch { .. }
+                | Self::NamespaceMismatch { .. }
+                | Self::MissingIndex { .. }
+                | Self::BlobTooLarge { .. }
+        )
+    }
+}
+
+impl From<CelestiaProviderError> for PipelineErrorKind {
+    fn from(err: CelestiaProviderError) -> Self {
+        let err_kind = PipelineError::Provider(err.to_string());
+        if err.is_temporary() {
+            err_kind.temp()
+        } else {
+            err_kind.crit()
+        }
+    }
+}
 
 /// Online client to fetch data from a Celestia network
+///
+/// Clones share the connection pool and the caches, and are `Send + Sync` so the hint handler
+/// and the background tasks can use them concurrently: the caches sit behind mutexes and the
+/// round-robin counter and the node's head are atomics.
 #[derive(Clone)]
 pub struct OnlineCelestiaProvider {
-    /// The node client
-    pub client: Arc<Client>,
+    /// The node clients, each holding its own connection
+    clients: Vec<Arc<Client>>,
+    /// The index of the client serving the next request
+    next_client: Arc<AtomicUsize>,
     /// The namespace to fetch data from
     pub namespace: Namespace,
-    /// The Blobstream contract address
-    pub blobstream_address: Address,
+    /// The Blobstream contract
+    pub blobstream: Sp1Blobstream,
+    /// The configuration of the Blobstream proof fetching
+    pub proof_config: ProofConfig,
+    /// The data commitment events discovered so far
+    pub commitment_cache: Arc<DataCommitmentCache>,
+    /// The Celestia headers fetched so far
+    pub header_cache: Arc<HeaderCache>,
+    /// The size above which blobs returned by the node are rejected
+    pub max_blob_bytes: usize,
+    /// The lowest Celestia height hints may reference
+    pub start_height: u64,
+    /// The highest Celestia height the node was seen at, refreshed when a hint goes past it
+    known_head: Arc<AtomicU64>,
+    /// The counters of the work done for the run
+    pub stats: Arc<RunStats>,
 }
 
-impl OnlineCelestiaProvider {
-    pub fn new(client: Client, namespace: Namespace, blobstream_address: Address) -> Self {
-        OnlineCelestiaProvider {
-            client: Arc::new(client),
+/// Builder of an [OnlineCelestiaProvider], defaulting every option but the node clients, the
+/// namespace and the Blobstream contract
+pub struct OnlineCelestiaProviderBuilder {
+    clients: Vec<Client>,
+    namespace: Namespace,
+    blobstream: Sp1Blobstream,
+    proof_config: ProofConfig,
+    max_blob_bytes: usize,
+    start_height: u64,
+    stats: Arc<RunStats>,
+}
+
+impl OnlineCelestiaProviderBuilder {
+    /// Creates a builder for a provider fetching data in `namespace`, proven against the
+    /// `blobstream` contract.
+    pub fn new(namespace: Namespace, blobstream: Sp1Blobstream) -> Self {
+        Self {
+            clients: Vec::new(),
             namespace,
-            blobstream_address,
+            blobstream,
+            proof_config: ProofConfig::default(),
+            max_blob_bytes: DEFAULT_MAX_BLOB_BYTES,
+            start_height: 0,
+            stats: Arc::default(),
         }
     }
+
+    /// Adds a node client to the pool of connections.
+    pub fn client(mut self, client: Client) -> Self {
+        self.clients.push(client);
+        self
+    }
+
+    /// Adds node clients to the pool of connections, so concurrent requests are spread across
+    /// connections instead of queueing on a single one.
+    pub fn clients(mut self, clients: impl IntoIterator<Item = Client>) -> Self {
+        self.clients.extend(clients);
+        self
+    }
+
+    /// Sets the configuration of the Blobstream proof fetching.
+    pub fn proof_config(mut self, proof_config: ProofConfig) -> Self {
+        self.proof_config = proof_config;
+        self
+    }
+
+    /// Sets the size above which blobs returned by the node are rejected, defaults to
+    /// [DEFAULT_MAX_BLOB_BYTES].
+    pub fn max_blob_bytes(mut self, max_blob_bytes: usize) -> Self {
+        self.max_blob_bytes = max_blob_bytes;
+        self
+    }
+
+    /// Sets the lowest Celestia height hints may reference, e.g. the height the rollup started
+    /// posting to Celestia at.
+    pub fn start_height(mut self, start_height: u64) -> Self {
+        self.start_height = start_height;
+        self
+    }
+
+    /// Sets the counters the provider records its work in, e.g. to share them with the caller.
+    pub fn stats(mut self, stats: Arc<RunStats>) -> Self {
+        self.stats = stats;
+        self
+    }
+
+    /// Builds the provider, failing if the configuration is unusable.
+    pub fn build(self) -> Result<OnlineCelestiaProvider> {
+        ensure!(
+            !self.clients.is_empty(),
+            "the Celestia connection pool needs at least one client"
+        );
+        ensure!(
+            self.max_blob_bytes > 0,
+            "the maximum Celestia blob size must be positive"
+        );
+
+        Ok(OnlineCelestiaProvider {
+            clients: self.clients.into_iter().map(Arc::new).collect(),
+            next_client: Arc::new(AtomicUsize::new(0)),
+            namespace: self.namespace,
+            blobstream: self.blobstream,
+            proof_config: self.proof_config,
+            commitment_cache: Arc::new(DataCommitmentCache::default()),
+            header_cache: Arc::new(HeaderCache::default()),
+            max_blob_bytes: self.max_blob_bytes,
+            start_height: self.start_height,
+            known_head: Arc::new(AtomicU64::new(0)),
+            stats: self.stats,
+        })
+    }
+}
+
+impl OnlineCelestiaProvider {
+    /// Creates a provider over a single node connection, with the other options defaulted. See
+    /// [OnlineCelestiaProvider::builder] to configure them.
+    pub fn new(
+        client: Client,
+        namespace: Namespace,
+        blobstream: Sp1Blobstream,
+        proof_config: ProofConfig,
+    ) -> Self {
+        Self::builder(namespace, blobstream)
+            .client(client)
+            .proof_config(proof_config)
+            .build()
+            .expect("the default configuration is valid")
+    }
+
+    /// Returns a builder for a provider fetching data in `namespace`, proven against the
+    /// `blobstream` contract.
+    pub fn builder(
+        namespace: Namespace,
+        blobstream: Sp1Blobstream,
+    ) -> OnlineCelestiaProviderBuilder {
+        OnlineCelestiaProviderBuilder::new(namespace, blobstream)
+    }
+
+    /// Returns the node client serving the next request, going round-robin over the pool.
+    pub fn client(&self) -> &Client {
+        let index = self.next_client.fetch_add(1, Ordering::Relaxed) % self.clients.len();
+        &self.clients[index]
+    }
+
+    /// Returns the number of node connections in the pool.
+    pub fn pool_size(&self) -> usize {
+        self.clients.len()
+    }
+
+    /// Checks that `height` lies between the configured start height and the node's head, so
+    /// pointers decoded from corrupted batcher data are rejected before any scan. The node's
+    /// head is only queried when `height` is above the last head seen.
+    pub async fn check_height(&self, height: u64) -> Result<()> {
+        ensure!(
+            height >= self.start_height,
+            "Celestia height {height} is below the start height {}",
+            self.start_height
+        );
+
+        if height <= self.known_head.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+
+        let head = with_timeout(
+            "header.LocalHead",
+            self.proof_config.rpc_timeout,
+            self.client().header_local_head(),
+        )
+        .await??
+        .height()
+        .value();
+        self.known_head.fetch_max(head, Ordering::Relaxed);
+
+        ensure!(
+            height <= head,
+            "Celestia height {height} is above the node's head {head}"
+        );
+        Ok(())
+    }
+
+    /// Returns the Celestia header at `height` along with its data root, fetching it only if it
+    /// is not cached yet.
+    pub async fn header_get(&self, height: u64) -> Result<Arc<CachedHeader>> {
+        Ok(self.cached_header(height).await?)
+    }
+
+    /// Returns the cached Celestia header at `height`, fetching it on a cache miss.
+    async fn cached_header(&self, height: u64) -> Result<Arc<CachedHeader>, CelestiaProviderError> {
+        if let Some(header) = self.header_cache.get(height) {
+            return Ok(header);
+        }
+
+        let header = with_timeout(
+            "header.GetByHeight",
+            self.proof_config.rpc_timeout,
+            self.client().header_get_by_height(height),
+        )
+        .await??;
+
+        Ok(self.header_cache.insert(header))
+    }
+
+    /// Returns the data root of the Celestia block at `height`.
+    pub async fn data_root(&self, height: u64) -> Result<Hash> {
+        Ok(self.header_get(height).await?.data_root)
+    }
+
+    /// Returns the Blobstream data commitment event covering the Celestia `height`.
+    ///
+    /// A cached event is reused as long as the L1 block it was emitted in is still canonical.
+    /// Otherwise an L1 reorg happened: every event from that block onwards is invalidated and L1
+    /// is scanned again.
+    pub async fn data_commitment(
+        &self,
+        l1_provider: &RootProvider,
+        height: u64,
+    ) -> Result<SP1BlobstreamDataCommitmentStored> {
+        if let Some(event) = self.commitment_cache.get(height) {
+            // Cached events always carry their L1 block
+            let number = event.l1_block_number.unwrap_or_default();
+
+            let canonical_hash = with_timeout(
+                "eth_getBlockByNumber",
+                self.proof_config.rpc_timeout,
+                l1_provider.get_block_by_number(number.into()),
+            )
+            .await??
+            .map(|block| block.header.hash);
+
+            if canonical_hash == event.l1_block_hash {
+                return Ok(event);
+            }
+
+            warn!(
+                "L1 block {} holding the data commitment for Celestia height {} was reorged out",
+                number, height
+            );
+            self.invalidate_above(number.saturating_sub(1));
+        }
+
+        self.stats.record_commitment_scan();
+        let mut stats = ScanStats::default();
+        let event = find_data_commitment_with_stats(
+            height,
+            &self.blobstream,
+            l1_provider,
+            &self.proof_config,
+            &mut stats,
+        )
+        .await;
+        debug!(
+            "Blobstream event scan for Celestia height {} fetched {} L1 blocks in {} windows with {} get_logs and {} contract calls",
+            height, stats.blocks_scanned, stats.windows, stats.get_logs_calls, stats.contract_calls
+        );
+        let event = event?;
+        self.commitment_cache.insert(event.clone());
+
+        Ok(event)
+    }
+
+    /// Returns the Blobstream data commitment events covering each of `heights`.
+    ///
+    /// Heights covered by a cached event are answered from the cache, without checking the
+    /// event's L1 block for reorgs, and the others are resolved with a single L1 scan whose
+    /// events are cached.
+    pub async fn resolve_commitments(
+        &self,
+        l1_provider: &RootProvider,
+        heights: &[u64],
+    ) -> Result<HashMap<u64, SP1BlobstreamDataCommitmentStored>> {
+        let mut resolved = HashMap::with_capacity(heights.len());
+        let mut missing = Vec::new();
+        for &height in heights {
+            match self.commitment_cache.get(height) {
+                Some(event) => {
+                    resolved.insert(height, event);
+                }
+                None => missing.push(height),
+            }
+        }
+        if missing.is_empty() {
+            return Ok(resolved);
+        }
+
+        self.stats.record_commitment_scan();
+        let mut stats = ScanStats::default();
+        let scanned = find_data_commitments(
+            &missing,
+            &self.blobstream,
+            l1_provider,
+            &self.proof_config,
+            &mut stats,
+        )
+        .await;
+        debug!(
+            "Blobstream event scan for {} Celestia heights fetched {} L1 blocks in {} windows with {} get_logs and {} contract calls",
+            missing.len(), stats.blocks_scanned, stats.windows, stats.get_logs_calls, stats.contract_calls
+        );
+
+        for (height, event) in scanned? {
+            self.commitment_cache.insert(event.clone());
+            resolved.insert(height, event);
+        }
+
+        Ok(resolved)
+    }
+
+    /// Invalidates the cached L1-derived artifacts emitted above `l1_block`, to be called when L1
+    /// reorgs past it. Celestia data is immutable and is never invalidated.
+    pub fn invalidate_above(&self, l1_block: u64) {
+        let dropped = self.commitment_cache.invalidate_above(l1_block);
+        if dropped > 0 {
+            warn!(
+                "Dropped {} cached data commitment events above L1 block {}",
+                dropped, l1_block
+            );
+        }
+    }
+
+    /// Fetches the blob in the provider's namespace with the given commitment in the block at
+    /// `height`.
+    ///
+    /// The node is untrusted, so the blob is checked to be the one asked for before it is
+    /// returned.
+    pub async fn blob_get(
+        &self,
+        height: u64,
+        commitment: Commitment,
+    ) -> Result<Blob, CelestiaProviderError> {
+        let blob = with_timeout(
+            "blob.Get",
+            self.proof_config.rpc_timeout,
+            self.client().blob_get(height, self.namespace, commitment),
+        )
+        .await??;
+
+        if blob.commitment != commitment {
+            return Err(CelestiaProviderError::CommitmentMismatch {
+                height,
+                expected: commitment,
+                returned: blob.commitment,
+            });
+        }
+        self.check_blob(&blob, height).await?;
+
+        Ok(blob)
+    }
+
+    /// Fetches every blob in the provider's namespace with the given commitment in the block at
+    /// `height`, along with the index of its first share, in the order the node lists them.
+    ///
+    /// Each blob is checked like [OnlineCelestiaProvider::blob_get], and errors as not found if
+    /// the namespace holds none.
+    pub async fn blobs_get(
+        &self,
+        height: u64,
+        commitment: Commitment,
+    ) -> Result<Vec<(u64, Blob)>, CelestiaProviderError> {
+        let blobs = with_timeout(
+            "blob.GetAll",
+            self.proof_config.rpc_timeout,
+            self.client().blob_get_all(height, &[self.namespace]),
+        )
+        .await??
+        .unwrap_or_default();
+
+        let mut matching = Vec::new();
+        for blob in blobs {
+            if blob.commitment != commitment {
+                continue;
+            }
+            let index = blob
+                .index
+                .ok_or(CelestiaProviderError::MissingIndex { height, commitment })?;
+            self.check_blob(&blob, height).await?;
+            matching.push((index, blob));
+        }
+
+        if matching.is_empty() {
+            return Err(CelestiaProviderError::NotFound { height, commitment });
+        }
+        Ok(matching)
+    }
+
+    /// Checks that a blob returned for a commitment is in the provider's namespace, within the
+    /// size limit, and that its data matches its commitment.
+    async fn check_blob(&self, blob: &Blob, height: u64) -> Result<(), CelestiaProviderError> {
+        let commitment = blob.commitment;
+        if blob.namespace != self.namespace {
+            return Err(CelestiaProviderError::NamespaceMismatch {
+                height,
+                expected: self.namespace,
+                returned: blob.namespace,
+            });
+        }
+        self.check_blob_size(blob, height)?;
+        let header = self.cached_header(height).await?;
+        let Some(app_version) = header.app_version() else {
+            return Err(CelestiaProviderError::UnsupportedAppVersion {
+                height,
+                version: header.header.header.version.app,
+            });
+        };
+        if !verify_blob_commitment(&blob.data, self.namespace, &commitment, app_version) {
+            return Err(CelestiaProviderError::BlobDataMismatch { height, commitment });
+        }
+
+        Ok(())
+    }
+
+    /// Fetches the blob in the provider's namespace whose first share is at `index` in the
+    /// block at `height`.
+    pub async fn blob_get_at_index(
+        &self,
+        height: u64,
+        index: u64,
+    ) -> Result<Blob, CelestiaProviderError> {
+        let blobs = with_timeout(
+            "blob.GetAll",
+            self.proof_config.rpc_timeout,
+            self.client().blob_get_all(height, &[self.namespace]),
+        )
+        .await??
+        .unwrap_or_default();
+
+        let blob = blobs
+            .into_iter()
+            .find(|blob| blob.index == Some(index))
+            .ok_or(CelestiaProviderError::NotFoundAtIndex { height, index })?;
+        self.check_blob_size(&blob, height)?;
+
+        Ok(blob)
+    }
+
+    /// Rejects blobs larger than the configured maximum, before proving them.
+    fn check_blob_size(&self, blob: &Blob, height: u64) -> Result<(), CelestiaProviderError> {
+        if blob.data.len() > self.max_blob_bytes {
+            return Err(CelestiaProviderError::BlobTooLarge {
+                height,
+                len: blob.data.len(),
+                max: self.max_blob_bytes,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Serves the blobs straight from the node, without Blobstream proofs, e.g. to drive a pipeline
+/// natively outside the oracle.
+#[async_trait]
+impl CelestiaProvider for OnlineCelestiaProvider {
+    type Error = CelestiaProviderError;
+
+    fn is_temporary(error: &Self::Error) -> bool {
+        error.is_temporary()
+    }
+
+    fn namespace(&self) -> Option<Namespace> {
+        Some(self.namespace)
+    }
+
+    async fn blob_get(&self, height: u64, commitment: Commitment) -> Result<Bytes, Self::Error> {
+        let blob = OnlineCelestiaProvider::blob_get(self, height, commitment).await?;
+        Ok(blob.data.into())
+    }
+
+    async fn blobs_get(
+        &self,
+        height: u64,
+        commitment: Commitment,
+    ) -> Result<Vec<(u64, Bytes)>, Self::Error> {
+        let blobs = OnlineCelestiaProvider::blobs_get(self, height, commitment).await?;
+        Ok(blobs
+            .into_iter()
+            .map(|(index, blob)| (index, blob.data.into()))
+            .collect())
+    }
+
+    async fn blob_get_at_index(&self, height: u64, index: u64) -> Result<Bytes, Self::Error> {
+        let blob = OnlineCelestiaProvider::blob_get_at_index(self, height, index).await?;
+        Ok(blob.data.into())
+    }
 }
 
 impl core::fmt::Debug for OnlineCelestiaProvider {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("OnlineCelestiaProvider")
             .field("namespace", &self.namespace)
-            .field("blobstream_address", &self.blobstream_address)
-            // Skip debugging the client field since it doesn't implement Debug
+            .field("blobstream", &self.blobstream)
+            .field("proof_config", &self.proof_config)
+            .field("pool_size", &self.clients.len())
+            // Skip debugging the clients since they don't implement Debug
             .finish_non_exhaustive()
     }
 }
@@ -0,0 +1,151 @@
+//! Prefetching of the Celestia payloads of a known set of blobs, ahead of the client's hints.
+
+use alloy_primitives::hex;
+use anyhow::{anyhow, Context, Result};
+use celestia_types::Commitment;
+use hana_oracle::hint::CelestiaHint;
+use kona_host::SharedKeyValueStore;
+use std::{fs, path::Path, sync::Arc};
+use tokio::{sync::Semaphore, task::JoinSet};
+use tracing::{info, warn};
+
+use super::{
+    handler::{fetch_hint_payload, is_stored},
+    CelestiaChainProviders, PayloadArchive,
+};
+
+/// Reads the blobs to prefetch from the file at `path`, holding one `<height> <commitment hex>`
+/// pair per line. Blank lines and lines starting with `#` are ignored.
+pub fn read_prefetch_list(path: &Path) -> Result<Vec<CelestiaHint>> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("reading prefetch list {}", path.display()))?;
+
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            parse_prefetch_line(line)
+                .ok_or_else(|| anyhow!("invalid prefetch entry `{line}` in {}", path.display()))
+        })
+        .collect()
+}
+
+fn parse_prefetch_line(line: &str) -> Option<CelestiaHint> {
+    let (height, commitment) = line.split_once(char::is_whitespace)?;
+    let height = height.parse().ok()?;
+    let commitment: [u8; 32] = hex::decode(commitment.trim()).ok()?.try_into().ok()?;
+
+    // The client asks for every blob with the commitment of a pointer
+    Some(CelestiaHint::Blobs {
+        height,
+        commitment: Commitment::new(commitment),
+    })
+}
+
+/// The outcome of a [prefetch_payloads] run
+#[derive(Debug, Clone, Default)]
+pub struct PrefetchReport {
+    /// Number of payloads built and stored
+    pub stored: usize,
+    /// Number of payloads skipped because they were already in the key-value store
+    pub already_stored: usize,
+    /// The hints whose payload could not be built
+    pub failed: Vec<CelestiaHint>,
+}
+
+/// Builds and stores the payloads of the blobs referenced by the given hints, at most
+/// `concurrency` at once.
+///
+/// Payloads already in the key-value store are skipped. Failures are logged and reported, the
+/// hint handler fetches the payload again when the client asks for it.
+pub async fn prefetch_payloads(
+    providers: CelestiaChainProviders,
+    hints: Vec<CelestiaHint>,
+    concurrency: usize,
+    archive: Option<PayloadArchive>,
+    kv: SharedKeyValueStore,
+) -> PrefetchReport {
+    // Resolve the data commitments of all the heights with one L1 scan, so the payloads below
+    // find them in the cache
+    let heights: Vec<u64> = hints.iter().map(CelestiaHint::height).collect();
+    if let Err(err) = providers
+        .celestia
+        .resolve_commitments(providers.l1(), &heights)
+        .await
+    {
+        warn!(
+            "Resolving the Blobstream data commitments of the prefetched blobs failed: {:#}",
+            err
+        );
+    }
+
+    let semaphore = Arc::new(Semaphore::new(concurrency.max(1)));
+    let mut tasks = JoinSet::new();
+
+    for hint in hints {
+        let permit = semaphore
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("prefetch semaphore is never closed");
+        let providers = providers.clone();
+        let archive = archive.clone();
+        let kv = kv.clone();
+
+        tasks.spawn(async move {
+            let _permit = permit;
+
+            if is_stored(&hint, &kv).await {
+                return (hint, Ok(false));
+            }
+
+            let result = fetch_hint_payload(&providers, &hint, archive.as_ref(), kv).await;
+            (hint, result.map(|()| true))
+        });
+    }
+
+    let mut report = PrefetchReport::default();
+    while let Some(result) = tasks.join_next().await {
+        match result {
+            Ok((_, Ok(true))) => report.stored += 1,
+            Ok((_, Ok(false))) => report.already_stored += 1,
+            Ok((hint, Err(err))) => {
+                warn!(
+                    "Prefetching the Celestia payload of {} failed: {:#}",
+                    describe_hint(&hint),
+                    err
+                );
+                report.failed.push(hint);
+            }
+            Err(err) => warn!("Prefetch task failed: {}", err),
+        }
+    }
+    info!(
+        "Prefetched {} Celestia payloads, {} already stored, {} failed",
+        report.stored,
+        report.already_stored,
+        report.failed.len()
+    );
+
+    report
+}
+
+/// Describes the blob referenced by `hint` for logs and reports.
+pub(crate) fn describe_hint(hint: &CelestiaHint) -> String {
+    match hint {
+        CelestiaHint::Commitment { height, commitment } => format!(
+            "commitment {} at height {}",
+            hex::encode(commitment.hash()),
+            height
+        ),
+        CelestiaHint::Index { height, index } => {
+            format!("share index {} at height {}", index, height)
+        }
+        CelestiaHint::Blobs { height, commitment } => format!(
+            "every blob with commitment {} at height {}",
+            hex::encode(commitment.hash()),
+            height
+        ),
+    }
+}
@@ -0,0 +1,94 @@
+//! Counters of the Celestia work done by a host run, written as a JSON summary when it ends.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::Path,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::Duration,
+};
+
+/// Counters of the Celestia hints served by the host, shared by every clone of the providers
+#[derive(Debug, Default)]
+pub struct RunStats {
+    hints_served: AtomicU64,
+    blobs: AtomicU64,
+    blob_bytes: AtomicU64,
+    heights: Mutex<BTreeSet<u64>>,
+    commitment_scans: AtomicU64,
+    proof_nanos: AtomicU64,
+}
+
+/// The summary of a host run, as written to the run summary file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct RunSummary {
+    /// Number of `celestia-da` and `celestia-da-index` hints received from the client
+    pub hints_served: u64,
+    /// Number of blob payloads built
+    pub blobs: u64,
+    /// Total size in bytes of the blobs of the payloads built
+    pub blob_bytes: u64,
+    /// Number of distinct Celestia heights payloads were built for
+    pub unique_heights: u64,
+    /// Number of L1 scans for Blobstream data commitment events
+    pub commitment_scans: u64,
+    /// Total time spent building Blobstream proofs, in milliseconds
+    pub proof_generation_ms: u64,
+}
+
+impl RunStats {
+    /// Records a Celestia hint received from the client.
+    pub fn record_hint(&self) {
+        self.hints_served.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records a blob payload built for `height`, whose blob is `len` bytes long.
+    pub fn record_blob(&self, height: u64, len: usize) {
+        self.blobs.fetch_add(1, Ordering::Relaxed);
+        self.blob_bytes.fetch_add(len as u64, Ordering::Relaxed);
+        self.record_height(height);
+    }
+
+    /// Records a payload built for `height`, holding a blob or the namespace's absence.
+    pub fn record_height(&self, height: u64) {
+        self.heights
+            .lock()
+            .expect("run stats poisoned")
+            .insert(height);
+    }
+
+    /// Records an L1 scan for Blobstream data commitment events.
+    pub fn record_commitment_scan(&self) {
+        self.commitment_scans.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records `elapsed` spent building Blobstream proofs.
+    pub fn record_proof_time(&self, elapsed: Duration) {
+        self.proof_nanos
+            .fetch_add(elapsed.as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    /// Returns the summary of the work recorded so far.
+    pub fn summary(&self) -> RunSummary {
+        RunSummary {
+            hints_served: self.hints_served.load(Ordering::Relaxed),
+            blobs: self.blobs.load(Ordering::Relaxed),
+            blob_bytes: self.blob_bytes.load(Ordering::Relaxed),
+            unique_heights: self.heights.lock().expect("run stats poisoned").len() as u64,
+            commitment_scans: self.commitment_scans.load(Ordering::Relaxed),
+            proof_generation_ms: self.proof_nanos.load(Ordering::Relaxed) / 1_000_000,
+        }
+    }
+
+    /// Writes the [RunSummary] of the work recorded so far to `path` as JSON.
+    pub fn write_summary(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(&self.summary())?;
+        fs::write(path, json)
+            .with_context(|| format!("writing the run summary to {}", path.display()))
+    }
+}
@@ -0,0 +1,154 @@
+//! This module contains the `verify-store` subcommand, which re-checks the proofs of every stored
+//! Celestia payload offline, ahead of a proving run.
+//!
+//! The disk key-value store is keyed by the hash of each hint, so the payloads to check are
+//! listed from the payload archive written alongside it, whose file names carry the hints.
+
+use anyhow::{anyhow, bail, Result};
+use celestia_types::{nmt::Namespace, AppVersion};
+use clap::Parser;
+use hana_blobstream::blobstream::DATA_COMMITMENTS_SLOT;
+use hana_oracle::{
+    hint::CelestiaHint,
+    payload::CelestiaPayload,
+    verify::{
+        verify_namespace_absence, verify_oracle_blobs, verify_oracle_payload,
+        verify_oracle_payload_at_index,
+    },
+};
+use kona_cli::cli_styles;
+use kona_host::{DiskKeyValueStore, KeyValueStore};
+use serde::Serialize;
+use std::{fs, path::PathBuf};
+
+use super::{namespace::parse_namespace, prefetch::describe_hint, PayloadArchive};
+
+/// Re-checks the proofs of every stored Celestia payload without a Celestia or L1 node.
+#[derive(Parser, Serialize, Clone, Debug)]
+#[command(styles = cli_styles())]
+pub struct VerifyStoreCmd {
+    /// Payload archive listing the payloads to check
+    #[clap(long, alias = "celestia-payload-archive")]
+    pub payload_archive: PathBuf,
+    /// Disk key-value store to check the payloads of. When unset, the archived payloads are
+    /// checked instead.
+    #[clap(long)]
+    pub data_dir: Option<PathBuf>,
+    /// Celestia namespace the payloads were served for
+    #[clap(long, alias = "celestia-namespace", env)]
+    pub namespace: String,
+    /// Celestia namespace version, overriding the version inferred from the namespace length
+    #[clap(long, alias = "celestia-namespace-version", env)]
+    pub namespace_version: Option<u8>,
+    /// Celestia app version whose share layout the blob commitments are recomputed under,
+    /// defaults to the latest one
+    #[clap(long)]
+    pub app_version: Option<u64>,
+    /// Storage slot of the Blobstream `state_dataCommitments` mapping the storage proofs were
+    /// taken at, defaults to the slot of the canonical deployments
+    #[clap(long, alias = "celestia-data-commitments-slot", env)]
+    pub data_commitments_slot: Option<u32>,
+}
+
+impl VerifyStoreCmd {
+    /// Runs the subcommand, erroring if any payload is missing or fails to verify.
+    pub async fn run(self) -> Result<()> {
+        let entries = PayloadArchive::new(&self.payload_archive).entries()?;
+        let store = self.data_dir.clone().map(DiskKeyValueStore::new);
+        let namespace = parse_namespace(&self.namespace, self.namespace_version)?;
+        let app_version = match self.app_version {
+            Some(version) => AppVersion::from_u64(version)
+                .ok_or_else(|| anyhow!("Unknown Celestia app version {version}"))?,
+            None => AppVersion::latest(),
+        };
+        let data_commitments_slot = self.data_commitments_slot.unwrap_or(DATA_COMMITMENTS_SLOT);
+
+        let mut verified = 0;
+        let mut failed = 0;
+        for (hint, path) in &entries {
+            let payload = match &store {
+                Some(store) => store.get(hint.preimage_key().into()),
+                None => Some(fs::read(path)?),
+            };
+
+            match payload
+                .ok_or_else(|| anyhow!("payload is not in the store"))
+                .and_then(|payload| {
+                    verify_payload(
+                        hint,
+                        &payload,
+                        namespace,
+                        app_version,
+                        data_commitments_slot,
+                    )
+                }) {
+                Ok(()) => verified += 1,
+                Err(err) => {
+                    eprintln!("Payload of {} failed: {err}", describe_hint(hint));
+                    failed += 1;
+                }
+            }
+        }
+
+        println!("Verified {verified} Celestia payloads, {failed} failed");
+        if failed > 0 {
+            bail!("{failed} of {} Celestia payloads failed", entries.len());
+        }
+
+        Ok(())
+    }
+}
+
+/// Checks every proof of the serialized `payload` served for `hint` in `namespace`, including the
+/// Blobstream storage proof taken at `data_commitments_slot`.
+fn verify_payload(
+    hint: &CelestiaHint,
+    payload: &[u8],
+    namespace: Namespace,
+    app_version: AppVersion,
+    data_commitments_slot: u32,
+) -> Result<()> {
+    let payload = CelestiaPayload::from_bytes(payload)
+        .map_err(|e| anyhow!("Error deserializing Celestia payload: {e}"))?;
+
+    match (hint, payload) {
+        (CelestiaHint::Commitment { height, commitment }, CelestiaPayload::Blob(payload)) => {
+            verify_oracle_payload(
+                &payload,
+                *height,
+                namespace,
+                commitment,
+                app_version,
+                data_commitments_slot,
+                false,
+            )?
+        }
+        (CelestiaHint::Index { height, index }, CelestiaPayload::Blob(payload)) => {
+            verify_oracle_payload_at_index(
+                &payload,
+                *height,
+                namespace,
+                *index,
+                data_commitments_slot,
+                false,
+            )?
+        }
+        (CelestiaHint::Blobs { height, commitment }, CelestiaPayload::Blobs(payloads)) => {
+            verify_oracle_blobs(
+                &payloads,
+                *height,
+                namespace,
+                commitment,
+                app_version,
+                data_commitments_slot,
+                false,
+            )?
+        }
+        (_, CelestiaPayload::NamespaceAbsent(payload)) => {
+            verify_namespace_absence(&payload, hint.height(), data_commitments_slot, false)?
+        }
+        _ => bail!("payload is of another kind than the ones served for the hint"),
+    }
+
+    Ok(())
+}
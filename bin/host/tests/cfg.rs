@@ -0,0 +1,17 @@
+//! Parsing of the Celestia flags into the configuration the host runs with.
+
+use clap::Parser;
+use hana_host::celestia::CelestiaCfg;
+use std::num::NonZeroU64;
+
+#[test]
+fn rejects_a_zero_scan_window() {
+    assert!(CelestiaCfg::try_parse_from(["hana-host", "--scan-window", "0"]).is_err());
+}
+
+#[test]
+fn scan_window_overrides_the_chain_default() {
+    let cfg = CelestiaCfg::try_parse_from(["hana-host", "--scan-window", "250"]).unwrap();
+
+    assert_eq!(cfg.proof_config(None).scan.window, NonZeroU64::new(250));
+}
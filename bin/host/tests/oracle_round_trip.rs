@@ -0,0 +1,756 @@
+//! Round trip of a Celestia blob from the Celestia node, through the host's
+//! [CelestiaChainHintHandler] and the preimage channels, to the client's [OracleCelestiaProvider],
+//! which must verify it and yield the blob.
+//!
+//! The Celestia, L1 and beacon nodes are mocked by an in-process HTTP server, serving a blob in a
+//! square of width 1 along with proofs built here: the share and row proofs against the data
+//! root, the data root tuple proof against a Blobstream data commitment, and the storage proof of
+//! the data commitment in a trie holding only it. Nothing is read from the environment or the
+//! network.
+
+use alloy_primitives::{address, b256, hex, keccak256, Address, Bytes, B256, U256};
+use celestia_types::{
+    hash::Hash, nmt::Namespace, test_utils::ExtendedHeaderGenerator, AppVersion, Blob, Commitment,
+    DataAvailabilityHeader, ExtendedHeader,
+};
+use hana_blobstream::blobstream::{
+    calculate_mapping_slot, encode_data_root_tuple, SP1BlobstreamDataCommitmentStored,
+    DATA_COMMITMENTS_SLOT,
+};
+use hana_celestia::CelestiaProvider;
+use hana_host::celestia::{
+    celestia_client, CelestiaChainHintHandler, CelestiaChainHost, CelestiaChainProviders,
+    OnlineCelestiaProvider,
+};
+use hana_oracle::{
+    hint::{celestia_preimage_key, CelestiaHint, HintWrapper},
+    provider::OracleCelestiaProvider,
+};
+use hana_proofs::{blobstream_inclusion::ProofConfig, contract::Sp1Blobstream};
+use kona_host::{
+    eth::http_provider, single::SingleChainProviders, HintHandler, KeyValueStore,
+    MemoryKeyValueStore, OfflineHostBackend, OnlineHostBackend, PreimageServer,
+    SharedKeyValueStore,
+};
+use kona_preimage::{
+    BidirectionalChannel, HintReader, HintWriter, NativeChannel, OracleReader, OracleServer,
+};
+use kona_proof::{CachingOracle, Hint};
+use kona_providers_alloy::{OnlineBeaconClient, OnlineBlobProvider};
+use op_alloy_network::Optimism;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::{sync::Arc, time::Duration};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+    sync::RwLock,
+    task::AbortHandle,
+};
+
+/// Size in bytes of a Celestia share
+const SHARE_SIZE: usize = 512;
+
+/// The namespace of the parity shares extending the data square
+const PARITY_NAMESPACE: [u8; 29] = [0xff; 29];
+
+/// The blob posted by the rollup, fitting in a single share
+const BLOB: &[u8] = b"a rollup batch posted to Celestia";
+
+/// The Blobstream contract the data commitment is stored in
+const BLOBSTREAM: Address = address!("0x7Cf3876F681Dbb6EdA8f6FfC45D66B996Df08fAe");
+
+/// The proof nonce the data commitment is stored at
+const PROOF_NONCE: u64 = 1;
+
+/// The L1 block the data commitment event was emitted in, which the storage proof is taken at
+const L1_BLOCK: u64 = 100;
+
+/// The hash of [L1_BLOCK]
+const L1_BLOCK_HASH: B256 =
+    b256!("0x2222222222222222222222222222222222222222222222222222222222222222");
+
+/// How long a round trip may take, so a host retrying a failing hint fails the test instead of
+/// hanging it
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The client's oracle, talking to the host over in-process channels
+type ClientOracle = CachingOracle<OracleReader<NativeChannel>, HintWriter<NativeChannel>>;
+
+/// Encodes `bytes` as standard padded base64, the encoding of byte fields in celestia-node JSON.
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (i, byte)| {
+            group | ((*byte as u32) << (16 - 8 * i))
+        });
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[((group >> (18 - 6 * i)) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+fn sha256(parts: &[&[u8]]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher.finalize().into()
+}
+
+/// Hash of a leaf of a Merkle tree built like Tendermint's, as the data root over the row and
+/// column roots, or the data commitment over the data root tuples
+fn merkle_leaf(leaf: &[u8]) -> [u8; 32] {
+    sha256(&[&[0], leaf])
+}
+
+/// Hash of an inner node of a Merkle tree built like Tendermint's
+fn merkle_inner(left: &[u8], right: &[u8]) -> [u8; 32] {
+    sha256(&[&[1], left, right])
+}
+
+/// Namespaced hash of a leaf of a namespaced Merkle tree
+fn nmt_leaf(namespace: &[u8], data: &[u8]) -> Vec<u8> {
+    [namespace, namespace, &sha256(&[&[0], namespace, data])].concat()
+}
+
+/// Namespaced hash of the root of a namespaced Merkle tree over two leaves, the right one a
+/// parity share whose namespace is left out of the range
+fn nmt_root(left: &[u8], right: &[u8]) -> Vec<u8> {
+    [&left[..58], &sha256(&[&[1], left, right])].concat()
+}
+
+/// RLP encoding of `bytes` as a string
+fn rlp_string(bytes: &[u8]) -> Vec<u8> {
+    match bytes {
+        [byte] if *byte < 0x80 => vec![*byte],
+        _ => [&[0x80 + bytes.len() as u8][..], bytes].concat(),
+    }
+}
+
+/// RLP encoding of the storage trie leaf holding `value` at the full path of `key`, which is the
+/// root of a trie holding only it
+fn storage_leaf(key: B256, value: U256) -> Bytes {
+    // The path carries the leaf flag for an even number of nibbles, and the value is the RLP
+    // encoding of the storage value, without leading zeros
+    let path = rlp_string(&[&[0x20][..], key.as_slice()].concat());
+    let value = rlp_string(&rlp_string(&value.to_be_bytes_trimmed_vec()));
+
+    let payload = [path, value].concat();
+    let mut node = match payload.len() {
+        len @ ..=55 => vec![0xc0 + len as u8],
+        len => vec![0xf8, len as u8],
+    };
+    node.extend(payload);
+    node.into()
+}
+
+fn namespace() -> Namespace {
+    Namespace::new_v0(b"hana").unwrap()
+}
+
+/// The share version 0 share holding `blob`, which must fit in one share
+fn share(blob: &[u8]) -> Vec<u8> {
+    let mut share = namespace().as_bytes().to_vec();
+    share.push(1);
+    share.extend((blob.len() as u32).to_be_bytes());
+    share.extend(blob);
+    share.resize(SHARE_SIZE, 0);
+    share
+}
+
+/// Returns the selector of the contract function with the given signature.
+fn selector(signature: &str) -> [u8; 4] {
+    keccak256(signature)[..4].try_into().unwrap()
+}
+
+/// The blob at the first share of a block, with everything the mocked nodes serve to prove it
+#[derive(Clone)]
+struct Fixture {
+    blob: Blob,
+    header: ExtendedHeader,
+    share_proof: Value,
+    data_root_tuple_proof: Value,
+    event: SP1BlobstreamDataCommitmentStored,
+    storage_root: B256,
+    storage_node: Bytes,
+}
+
+impl Fixture {
+    fn new() -> Self {
+        let mut blob = Blob::new(namespace(), BLOB.to_vec(), AppVersion::latest()).unwrap();
+        blob.index = Some(0);
+
+        // The original square has a width of 1, the blob's share, and is extended with parity
+        // shares. The column roots are the same as the row roots.
+        let share_leaf = nmt_leaf(namespace().as_bytes(), &share(BLOB));
+        let parity_leaf = nmt_leaf(&PARITY_NAMESPACE, &[0; SHARE_SIZE]);
+        let data_row = nmt_root(&share_leaf, &parity_leaf);
+        let parity_row = nmt_root(&parity_leaf, &parity_leaf);
+
+        let dah: DataAvailabilityHeader = serde_json::from_value(json!({
+            "row_roots": [base64(&data_row), base64(&parity_row)],
+            "column_roots": [base64(&data_row), base64(&parity_row)],
+        }))
+        .expect("data availability header");
+        let roots = [&data_row, &parity_row].map(|root| merkle_leaf(root));
+        let half = merkle_inner(&roots[0], &roots[1]);
+        let data_root = merkle_inner(&half, &half);
+        assert_eq!(
+            dah.hash(),
+            Hash::Sha256(data_root),
+            "data root of the square"
+        );
+
+        let header = ExtendedHeaderGenerator::new().next_with_dah(dah);
+        let height = header.height().value();
+
+        let share_proof = json!({
+            "data": [base64(&share(BLOB))],
+            "share_proofs": [{
+                "start": 0,
+                "end": 1,
+                "nodes": [base64(&parity_leaf)],
+            }],
+            "namespace_id": base64(&namespace().as_bytes()[1..]),
+            "row_proof": {
+                "row_roots": [hex::encode_upper(&data_row)],
+                "proofs": [{
+                    "total": 4,
+                    "index": 0,
+                    "leaf_hash": base64(&roots[0]),
+                    "aunts": [base64(&roots[1]), base64(&half)],
+                }],
+                "start_row": 0,
+                "end_row": 0,
+            },
+            "namespace_version": 0,
+        });
+
+        // The data commitment covers the blob's height and the next one
+        let tuples = [
+            encode_data_root_tuple(height, &Hash::Sha256(data_root)).unwrap(),
+            encode_data_root_tuple(height + 1, &Hash::Sha256([0x11; 32])).unwrap(),
+        ]
+        .map(|tuple| merkle_leaf(&tuple));
+        let data_commitment = B256::from(merkle_inner(&tuples[0], &tuples[1]));
+        let data_root_tuple_proof = json!({
+            "total": 2,
+            "index": 0,
+            "leaf_hash": base64(&tuples[0]),
+            "aunts": [base64(&tuples[1])],
+        });
+
+        let slot = calculate_mapping_slot(DATA_COMMITMENTS_SLOT, U256::from(PROOF_NONCE));
+        let storage_node = storage_leaf(keccak256(slot), data_commitment.into());
+
+        Self {
+            blob,
+            header,
+            share_proof,
+            data_root_tuple_proof,
+            event: SP1BlobstreamDataCommitmentStored {
+                proof_nonce: U256::from(PROOF_NONCE),
+                start_block: height,
+                end_block: height + 2,
+                data_commitment,
+                l1_block_number: Some(L1_BLOCK),
+                l1_block_hash: Some(L1_BLOCK_HASH),
+            },
+            storage_root: keccak256(&storage_node),
+            storage_node,
+        }
+    }
+
+    fn height(&self) -> u64 {
+        self.header.height().value()
+    }
+
+    fn commitment(&self) -> Commitment {
+        self.blob.commitment
+    }
+}
+
+/// The Celestia, L1 and beacon nodes, answering with the proofs of a [Fixture]
+struct MockNode {
+    fixture: Fixture,
+    /// The answer to `blob.Get`, or the message of the error answered instead
+    blob: Result<Value, &'static str>,
+    /// The blobs in the namespace, answered to `blob.GetAll`
+    all_blobs: Vec<Blob>,
+}
+
+impl MockNode {
+    /// Answers the JSON-RPC call of `method`, or errors with a JSON-RPC error code and message.
+    fn call(&self, method: &str, params: &Value) -> Result<Value, (i64, String)> {
+        let fixture = &self.fixture;
+        match method {
+            "header.LocalHead" | "header.GetByHeight" => {
+                Ok(serde_json::to_value(&fixture.header).unwrap())
+            }
+            "blob.Get" => self
+                .blob
+                .clone()
+                .map_err(|message| (1, message.to_string())),
+            // celestia-node answers null rather than an empty list
+            "blob.GetAll" if self.all_blobs.is_empty() => Ok(Value::Null),
+            "blob.GetAll" => Ok(serde_json::to_value(&self.all_blobs).unwrap()),
+            "share.GetRange" => Ok(json!({
+                "Shares": [base64(&share(BLOB))],
+                "Proof": fixture.share_proof,
+            })),
+            "blobstream.GetDataRootTupleInclusionProof" => {
+                Ok(fixture.data_root_tuple_proof.clone())
+            }
+            "eth_call" => self.contract_call(&params[0]),
+            "eth_getProof" => Ok(json!({
+                "address": BLOBSTREAM,
+                "balance": "0x0",
+                "codeHash": B256::ZERO,
+                "nonce": "0x0",
+                "storageHash": fixture.storage_root,
+                "accountProof": [],
+                "storageProof": [{
+                    "key": params[1][0],
+                    "value": U256::from_be_bytes(fixture.event.data_commitment.0),
+                    "proof": [fixture.storage_node],
+                }],
+            })),
+            _ => Err((-32601, format!("method {method} not found"))),
+        }
+    }
+
+    /// Answers a call to the Blobstream contract, which stored the fixture's data commitment as
+    /// its latest.
+    fn contract_call(&self, call: &Value) -> Result<Value, (i64, String)> {
+        let input = call
+            .get("input")
+            .or_else(|| call.get("data"))
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let input = hex::decode(input).map_err(|err| (-32602, err.to_string()))?;
+
+        let word: B256 = match input.get(..4) {
+            Some(s) if *s == selector("frozen()") => B256::ZERO,
+            Some(s) if *s == selector("state_proofNonce()") => U256::from(PROOF_NONCE + 1).into(),
+            Some(s) if *s == selector("DATA_COMMITMENT_MAX()") => U256::from(1000).into(),
+            Some(s) if *s == selector("state_dataCommitments(uint256)") => {
+                self.fixture.event.data_commitment
+            }
+            _ => return Err((3, "execution reverted".to_string())),
+        };
+        Ok(json!(word))
+    }
+
+    /// Answers a JSON-RPC request.
+    fn rpc(&self, body: &[u8]) -> Value {
+        let request: Value = serde_json::from_slice(body).expect("JSON-RPC request");
+        let method = request["method"].as_str().unwrap_or_default();
+
+        match self.call(method, &request["params"]) {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": request["id"], "result": result }),
+            Err((code, message)) => json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "error": { "code": code, "message": message },
+            }),
+        }
+    }
+
+    /// Answers a query of the beacon API at `path`, the ones the blob provider starts with.
+    fn beacon(&self, path: &str) -> Option<Value> {
+        match path {
+            "/eth/v1/beacon/genesis" => Some(json!({
+                "data": {
+                    "genesis_time": "1606824023",
+                    "genesis_validators_root": B256::ZERO,
+                    "genesis_fork_version": "0x00000000",
+                },
+            })),
+            "/eth/v1/config/spec" => Some(json!({ "data": { "SECONDS_PER_SLOT": "12" } })),
+            _ => None,
+        }
+    }
+}
+
+/// Serves the mocked nodes over HTTP on a local port, returning their URL.
+async fn serve_node(node: MockNode) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("mock node listener");
+    let url = format!("http://{}", listener.local_addr().unwrap());
+
+    let node = Arc::new(node);
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            tokio::spawn(serve_connection(stream, node.clone()));
+        }
+    });
+
+    url
+}
+
+/// Answers the HTTP requests of a connection until the client closes it: the JSON-RPC calls of
+/// the Celestia and L1 clients, and the beacon API queries of the blob provider.
+async fn serve_connection(stream: TcpStream, node: Arc<MockNode>) {
+    let (read, mut write) = stream.into_split();
+    let mut read = BufReader::new(read);
+
+    loop {
+        let mut request_line = String::new();
+        if read.read_line(&mut request_line).await.unwrap_or(0) == 0 {
+            return;
+        }
+
+        let mut content_length = 0;
+        loop {
+            let mut line = String::new();
+            if read.read_line(&mut line).await.unwrap_or(0) == 0 {
+                return;
+            }
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse().expect("content length");
+                }
+            }
+        }
+        let mut body = vec![0; content_length];
+        if read.read_exact(&mut body).await.is_err() {
+            return;
+        }
+
+        let (status, response) = match request_line.split_whitespace().collect::<Vec<_>>()[..] {
+            ["GET", path, ..] => match node.beacon(path) {
+                Some(response) => ("200 OK", response),
+                None => ("404 Not Found", json!({})),
+            },
+            _ => ("200 OK", node.rpc(&body)),
+        };
+        let response = response.to_string();
+        let head = format!(
+            "HTTP/1.1 {status}\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n",
+            response.len()
+        );
+        if write.write_all(head.as_bytes()).await.is_err()
+            || write.write_all(response.as_bytes()).await.is_err()
+        {
+            return;
+        }
+    }
+}
+
+/// Builds the host's providers against the mocked nodes at `url`.
+async fn providers(url: &str, fixture: &Fixture) -> CelestiaChainProviders {
+    // The data commitment event is pinned rather than scanned for, so the L1 node only answers
+    // the contract calls and the storage proof
+    let proof_config = ProofConfig {
+        rpc_timeout: Duration::from_secs(5),
+        pinned_commitments: vec![fixture.event.clone()],
+        ..ProofConfig::default()
+    };
+    let celestia = OnlineCelestiaProvider::new(
+        celestia_client(url, None, &[])
+            .await
+            .expect("celestia client"),
+        namespace(),
+        Sp1Blobstream::new(BLOBSTREAM),
+        proof_config,
+    );
+
+    CelestiaChainProviders::new(
+        SingleChainProviders {
+            l1: http_provider(url),
+            blobs: OnlineBlobProvider::init(OnlineBeaconClient::new_http(url.to_string())).await,
+            l2: http_provider::<Optimism>(url),
+        },
+        celestia,
+    )
+}
+
+/// Returns the client's provider for the rollup, talking to a host over the given channels.
+fn client_provider(
+    hint: NativeChannel,
+    preimage: NativeChannel,
+) -> OracleCelestiaProvider<ClientOracle> {
+    let oracle = CachingOracle::new(16, OracleReader::new(preimage), HintWriter::new(hint));
+    OracleCelestiaProvider::new(Arc::new(oracle), namespace())
+}
+
+/// Serves the preimages of the hints the client sends with the [CelestiaChainHintHandler], against
+/// the mocked nodes at `url` and storing them in `kv`. Returns the client's provider, and the
+/// handle of the host's task.
+async fn serve_host(
+    url: &str,
+    fixture: &Fixture,
+    kv: SharedKeyValueStore,
+) -> (OracleCelestiaProvider<ClientOracle>, AbortHandle) {
+    let hint = BidirectionalChannel::new().expect("hint channel");
+    let preimage = BidirectionalChannel::new().expect("preimage channel");
+    let backend = OnlineHostBackend::new(
+        CelestiaChainHost::default(),
+        kv,
+        providers(url, fixture).await,
+        CelestiaChainHintHandler,
+    );
+    let server = tokio::spawn(
+        PreimageServer::new(
+            OracleServer::new(preimage.host),
+            HintReader::new(hint.host),
+            Arc::new(backend),
+        )
+        .start(),
+    )
+    .abort_handle();
+
+    (client_provider(hint.client, preimage.client), server)
+}
+
+/// Serves the hint for the blob of a fixture with the [CelestiaChainHintHandler], against a
+/// Celestia node answering `blob.Get` with `blob`. Returns the outcome, and whether a payload
+/// was stored for the hint.
+async fn fetch_blob_hint(
+    blob: impl FnOnce(&Fixture) -> Result<Value, &'static str>,
+) -> (anyhow::Result<()>, bool) {
+    let fixture = Fixture::new();
+    let hint = CelestiaHint::Commitment {
+        height: fixture.height(),
+        commitment: fixture.commitment(),
+    };
+    let url = serve_node(MockNode {
+        blob: blob(&fixture),
+        all_blobs: Vec::new(),
+        fixture: fixture.clone(),
+    })
+    .await;
+
+    let kv: SharedKeyValueStore = Arc::new(RwLock::new(MemoryKeyValueStore::new()));
+    let result = CelestiaChainHintHandler::fetch_hint(
+        Hint {
+            ty: HintWrapper::CelestiaDA,
+            data: hint.encode().into(),
+        },
+        &CelestiaChainHost::default(),
+        &providers(&url, &fixture).await,
+        kv.clone(),
+    )
+    .await;
+    let stored = kv.read().await.get(hint.preimage_key().into()).is_some();
+
+    (result, stored)
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn blob_round_trips_through_the_hint_handler() {
+    let fixture = Fixture::new();
+    let (height, commitment) = (fixture.height(), fixture.commitment());
+    let url = serve_node(MockNode {
+        blob: Ok(serde_json::to_value(&fixture.blob).unwrap()),
+        all_blobs: Vec::new(),
+        fixture: fixture.clone(),
+    })
+    .await;
+
+    let kv: SharedKeyValueStore = Arc::new(RwLock::new(MemoryKeyValueStore::new()));
+    let (provider, server) = serve_host(&url, &fixture, kv.clone()).await;
+    let blob = tokio::time::timeout(TIMEOUT, provider.blob_get(height, commitment))
+        .await
+        .expect("round trip timed out")
+        .expect("blob failed to verify on the client");
+
+    assert_eq!(blob, Bytes::from_static(BLOB));
+    assert!(
+        kv.read()
+            .await
+            .get(celestia_preimage_key(height, commitment).into())
+            .is_some(),
+        "payload not stored"
+    );
+
+    server.abort();
+}
+
+/// Every blob of a commitment is fetched from the node with its share index, leaving out the
+/// other blobs of the namespace.
+#[tokio::test(flavor = "multi_thread")]
+async fn online_provider_fetches_every_blob_of_a_commitment() {
+    let fixture = Fixture::new();
+    let mut repeated = fixture.blob.clone();
+    repeated.index = Some(2);
+    let mut other = Blob::new(
+        namespace(),
+        b"another rollup batch".to_vec(),
+        AppVersion::latest(),
+    )
+    .unwrap();
+    other.index = Some(1);
+    let url = serve_node(MockNode {
+        blob: Err("blob: not found"),
+        all_blobs: vec![repeated, other, fixture.blob.clone()],
+        fixture: fixture.clone(),
+    })
+    .await;
+
+    let provider = providers(&url, &fixture).await.celestia;
+    let blobs = CelestiaProvider::blobs_get(&provider, fixture.height(), fixture.commitment())
+        .await
+        .expect("blobs of the commitment");
+
+    assert_eq!(
+        blobs,
+        vec![(2, Bytes::from_static(BLOB)), (0, Bytes::from_static(BLOB))]
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn online_provider_reports_commitments_without_blobs_as_not_found() {
+    let fixture = Fixture::new();
+    let mut other = Blob::new(
+        namespace(),
+        b"another rollup batch".to_vec(),
+        AppVersion::latest(),
+    )
+    .unwrap();
+    other.index = Some(1);
+    let url = serve_node(MockNode {
+        blob: Err("blob: not found"),
+        all_blobs: vec![other],
+        fixture: fixture.clone(),
+    })
+    .await;
+
+    let provider = providers(&url, &fixture).await.celestia;
+    let err = provider
+        .blobs_get(fixture.height(), fixture.commitment())
+        .await
+        .expect_err("blobs of another commitment");
+    assert!(err.is_not_found(), "{err}");
+}
+
+/// Blobs without the share index they start at cannot be ordered, nor proven to start there.
+#[tokio::test(flavor = "multi_thread")]
+async fn online_provider_rejects_blobs_without_an_index() {
+    let fixture = Fixture::new();
+    let mut blob = fixture.blob.clone();
+    blob.index = None;
+    let url = serve_node(MockNode {
+        blob: Err("blob: not found"),
+        all_blobs: vec![blob],
+        fixture: fixture.clone(),
+    })
+    .await;
+
+    let provider = providers(&url, &fixture).await.celestia;
+    let err = provider
+        .blobs_get(fixture.height(), fixture.commitment())
+        .await
+        .expect_err("blob without an index");
+    assert!(err.is_verification_failure(), "{err}");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn blobs_of_a_commitment_round_trip_through_the_hint_handler() {
+    let fixture = Fixture::new();
+    let (height, commitment) = (fixture.height(), fixture.commitment());
+    let url = serve_node(MockNode {
+        blob: Err("blob: not found"),
+        all_blobs: vec![fixture.blob.clone()],
+        fixture: fixture.clone(),
+    })
+    .await;
+
+    let kv: SharedKeyValueStore = Arc::new(RwLock::new(MemoryKeyValueStore::new()));
+    let (provider, server) = serve_host(&url, &fixture, kv.clone()).await;
+    let blobs = tokio::time::timeout(TIMEOUT, provider.blobs_get(height, commitment))
+        .await
+        .expect("round trip timed out")
+        .expect("blobs failed to verify on the client");
+
+    assert_eq!(blobs, vec![(0, Bytes::from_static(BLOB))]);
+    let hint = CelestiaHint::Blobs { height, commitment };
+    assert!(
+        kv.read().await.get(hint.preimage_key().into()).is_some(),
+        "payload not stored"
+    );
+
+    server.abort();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn node_failures_fail_the_hint() {
+    let (result, stored) = fetch_blob_hint(|_| Err("header: syncing in progress")).await;
+
+    let err = result.expect_err("hint served");
+    assert!(!format!("{err:#}").contains("namespace absence"), "{err:#}");
+    assert!(!stored);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn tampered_blobs_fail_the_hint() {
+    let (result, stored) = fetch_blob_hint(|fixture| {
+        let mut blob = serde_json::to_value(&fixture.blob).unwrap();
+        blob["data"] = json!(base64(b"another rollup batch"));
+        Ok(blob)
+    })
+    .await;
+
+    let err = result.expect_err("hint served");
+    assert!(!format!("{err:#}").contains("namespace absence"), "{err:#}");
+    assert!(!stored);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn missing_blobs_fall_back_to_the_namespace_absence_proof() {
+    // The mocked node serves no namespace data, so proving the absence fails in turn
+    let (result, stored) = fetch_blob_hint(|_| Err("blob: not found")).await;
+
+    let err = result.expect_err("hint served");
+    assert!(
+        format!("{err:#}").contains("namespace absence could not be proven"),
+        "{err:#}"
+    );
+    assert!(!stored);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn malformed_payload_is_rejected() {
+    let height = 1u64;
+    let commitment = Commitment::new([0x11; 32]);
+
+    let mut kv = MemoryKeyValueStore::new();
+    kv.set(
+        celestia_preimage_key(height, commitment).into(),
+        b"not a celestia payload".to_vec(),
+    )
+    .unwrap();
+
+    let hint = BidirectionalChannel::new().expect("hint channel");
+    let preimage = BidirectionalChannel::new().expect("preimage channel");
+    let server = tokio::spawn(
+        PreimageServer::new(
+            OracleServer::new(preimage.host),
+            HintReader::new(hint.host),
+            Arc::new(OfflineHostBackend::new(Arc::new(RwLock::new(kv)))),
+        )
+        .start(),
+    )
+    .abort_handle();
+
+    let provider = client_provider(hint.client, preimage.client);
+    let result = tokio::time::timeout(TIMEOUT, provider.blob_get(height, commitment))
+        .await
+        .expect("round trip timed out");
+    assert!(result.is_err(), "malformed payload was accepted");
+
+    server.abort();
+}
@@ -0,0 +1,90 @@
+//! Classification of the errors of the online Celestia provider, which decides whether a hint
+//! falls back to proving the namespace's absence or fails.
+
+use celestia_types::{nmt::Namespace, Commitment};
+use hana_host::celestia::CelestiaProviderError;
+use hana_proofs::blobstream_inclusion::RpcTimeout;
+use jsonrpsee::{core::ClientError, types::ErrorObject};
+use std::time::Duration;
+
+/// An error answered by the node to a call, with the given message
+fn call_error(message: &str) -> CelestiaProviderError {
+    CelestiaProviderError::Rpc(ClientError::Call(ErrorObject::owned(
+        1, message, None::<()>,
+    )))
+}
+
+#[test]
+fn blobs_the_node_does_not_hold_are_not_found() {
+    assert!(call_error("blob: not found").is_not_found());
+    assert!(call_error("getting blob: blob: not found").is_not_found());
+    assert!(CelestiaProviderError::NotFoundAtIndex {
+        height: 1,
+        index: 4
+    }
+    .is_not_found());
+    assert!(CelestiaProviderError::NotFound {
+        height: 1,
+        commitment: Commitment::new([1; 32]),
+    }
+    .is_not_found());
+}
+
+#[test]
+fn failed_calls_are_not_a_missing_blob() {
+    let errors = [
+        call_error("header: syncing in progress"),
+        call_error("context deadline exceeded"),
+        CelestiaProviderError::Rpc(ClientError::RequestTimeout),
+        CelestiaProviderError::RpcTimeout(RpcTimeout {
+            call: "blob.Get",
+            timeout: Duration::from_secs(30),
+        }),
+    ];
+
+    for err in errors {
+        assert!(!err.is_not_found(), "{err}");
+        assert!(err.is_temporary(), "{err}");
+    }
+}
+
+#[test]
+fn blobs_failing_verification_are_not_a_missing_blob() {
+    let namespace = Namespace::new_v0(&[1]).unwrap();
+    let other = Namespace::new_v0(&[2]).unwrap();
+
+    let errors = [
+        CelestiaProviderError::CommitmentMismatch {
+            height: 1,
+            expected: Commitment::new([1; 32]),
+            returned: Commitment::new([2; 32]),
+        },
+        CelestiaProviderError::BlobDataMismatch {
+            height: 1,
+            commitment: Commitment::new([1; 32]),
+        },
+        CelestiaProviderError::NamespaceMismatch {
+            height: 1,
+            expected: namespace,
+            returned: other,
+        },
+        CelestiaProviderError::MissingIndex {
+            height: 1,
+            commitment: Commitment::new([1; 32]),
+        },
+        CelestiaProviderError::UnsupportedAppVersion {
+            height: 1,
+            version: 99,
+        },
+        CelestiaProviderError::BlobTooLarge {
+            height: 1,
+            len: 2,
+            max: 1,
+        },
+    ];
+
+    for err in errors {
+        assert!(!err.is_not_found(), "{err}");
+        assert!(!err.is_temporary(), "{err}");
+    }
+}
@@ -0,0 +1,20 @@
+//! Compile-time checks that the providers shared between the host's tasks, and the client's
+//! oracle provider, are `Send + Sync`, so a field breaking it fails the build here rather than
+//! at a `tokio::spawn` far away.
+
+use hana_host::celestia::{CelestiaChainProviders, OnlineCelestiaProvider};
+use hana_oracle::provider::OracleCelestiaProvider;
+use kona_preimage::{HintWriter, NativeChannel, OracleReader};
+use kona_proof::CachingOracle;
+
+/// The client's oracle in native mode
+type ClientOracle = CachingOracle<OracleReader<NativeChannel>, HintWriter<NativeChannel>>;
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn providers_are_send_and_sync() {
+    assert_send_sync::<OnlineCelestiaProvider>();
+    assert_send_sync::<CelestiaChainProviders>();
+    assert_send_sync::<OracleCelestiaProvider<ClientOracle>>();
+}
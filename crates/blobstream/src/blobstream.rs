@@ -2,14 +2,16 @@ use std::boxed::Box;
 
 use alloc::vec::Vec;
 use alloy_primitives::{keccak256, Bytes, FixedBytes, B256, U256};
-use alloy_sol_types::sol;
-use alloy_trie::{
-    proof::{verify_proof, ProofVerificationError},
-    Nibbles,
-};
-use celestia_types::{hash::Hash, MerkleProof, ShareProof};
+use alloy_sol_types::{sol, SolValue};
+use alloy_trie::{proof::verify_proof, Nibbles};
+use celestia_proto::celestia::core::v1::proof::ShareProof as RawShareProof;
+use celestia_types::{hash::Hash, nmt::Namespace, AppVersion, Commitment, MerkleProof, ShareProof};
+use core::ops::Range;
+use hana_celestia::{verify_blob_commitment, verify_blob_shares};
 use serde::{Deserialize, Serialize};
 
+use crate::error::{BlobstreamError, DataRootTupleError, InvalidShareRange};
+
 /////// Contract ///////
 
 sol! {
@@ -36,6 +38,15 @@ sol! {
     }
 }
 
+sol! {
+    /// The leaf of a Blobstream data commitment, as defined by the contract
+    #[derive(Debug, PartialEq, Eq)]
+    struct DataRootTuple {
+        uint256 height;
+        bytes32 dataRoot;
+    }
+}
+
 /// Represents the stored data commitment event from Blobstream
 #[derive(Debug, Clone)]
 pub struct SP1BlobstreamDataCommitmentStored {
@@ -43,6 +54,10 @@ pub struct SP1BlobstreamDataCommitmentStored {
     pub start_block: u64,
     pub end_block: u64,
     pub data_commitment: B256,
+    /// The number of the L1 block the event was emitted in, if known
+    pub l1_block_number: Option<u64>,
+    /// The hash of the L1 block the event was emitted in, if known
+    pub l1_block_hash: Option<B256>,
 }
 
 impl std::fmt::Display for SP1BlobstreamDataCommitmentStored {
@@ -52,10 +67,15 @@ impl std::fmt::Display for SP1BlobstreamDataCommitmentStored {
     }
 }
 
+/// Storage slot of the `state_dataCommitments` mapping in the deployed SP1Blobstream contracts.
+///
+/// A redeployed or forked contract may place the mapping elsewhere. The slot can be read from
+/// the contract's storage layout, e.g. with
+/// `forge inspect SP1Blobstream storage-layout`, as the `slot` of `state_dataCommitments`.
 pub const DATA_COMMITMENTS_SLOT: u32 = 254;
 
 /// A structure containing a Celestia Blob and its corresponding proofs
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct BlobstreamProof {
     /// The data root to verify the proof against
     pub data_root: Hash,
@@ -67,14 +87,17 @@ pub struct BlobstreamProof {
     pub share_proof: ShareProof,
     /// The proof_nonce in blobstream
     pub proof_nonce: U256,
-    /// The storage root to verify against
+    /// The storage root to verify against, zero when the storage proof was skipped
     pub storage_root: B256,
-    /// The storage proof for the state_dataCommitments mapping slot in Blobstream
-    pub storage_proof: Vec<Bytes>,
+    /// The storage proof for the state_dataCommitments mapping slot in Blobstream, if the host
+    /// did not skip it
+    pub storage_proof: Option<Vec<Bytes>>,
+    /// The hash of the L1 block the storage proof was taken at, for auditing
+    pub storage_proof_block: Option<B256>,
 }
 
 impl BlobstreamProof {
-    /// Create a new OraclePayload instance
+    /// Create a new BlobstreamProof instance
     pub fn new(
         data_root: Hash,
         data_commitment: FixedBytes<32>,
@@ -82,7 +105,7 @@ impl BlobstreamProof {
         share_proof: ShareProof,
         proof_nonce: U256,
         storage_root: B256,
-        storage_proof: Vec<Bytes>,
+        storage_proof: Option<Vec<Bytes>>,
     ) -> Self {
         Self {
             data_root,
@@ -92,9 +115,16 @@ impl BlobstreamProof {
             proof_nonce,
             storage_root,
             storage_proof,
+            storage_proof_block: None,
         }
     }
 
+    /// Records the hash of the L1 block the storage proof was taken at.
+    pub fn with_storage_proof_block(mut self, storage_proof_block: Option<B256>) -> Self {
+        self.storage_proof_block = storage_proof_block;
+        self
+    }
+
     /// Serialize the struct to bytes using serde with a binary format
     pub fn to_bytes(&self) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
         let bytes = bincode::serialize(self)?;
@@ -106,36 +136,362 @@ impl BlobstreamProof {
         let deserialized = bincode::deserialize(bytes)?;
         Ok(deserialized)
     }
+
+    /// Returns the part of the proof committing the data root to Blobstream.
+    pub fn inclusion_proof(&self) -> DataRootInclusionProof {
+        DataRootInclusionProof {
+            data_commitment: self.data_commitment,
+            data_root_tuple_proof: self.data_root_tuple_proof.clone(),
+            proof_nonce: self.proof_nonce,
+            storage_root: self.storage_root,
+            storage_proof: self.storage_proof.clone(),
+            storage_proof_block: self.storage_proof_block,
+        }
+    }
+
+    /// Verify the proof for the `blob` in `namespace` at `height` with the given `commitment`:
+    /// the commitment recomputed from the blob under the share layout of `app_version`, the
+    /// proven shares against the blob, the share proof against the data root, the data root
+    /// tuple against the data commitment, and the data commitment against the storage proof of
+    /// the `state_dataCommitments` mapping at `data_commitments_slot`.
+    pub fn verify(
+        &self,
+        height: u64,
+        blob: &[u8],
+        namespace: Namespace,
+        commitment: &Commitment,
+        app_version: AppVersion,
+        data_commitments_slot: u32,
+    ) -> Result<(), BlobstreamError> {
+        self.verify_without_storage_proof(height, blob, namespace, commitment, app_version)?;
+
+        self.inclusion_proof()
+            .verify_storage_proof(data_commitments_slot)
+    }
+
+    /// Verify the proof like [BlobstreamProof::verify], but trust the data commitment instead of
+    /// checking it against the storage proof.
+    pub fn verify_without_storage_proof(
+        &self,
+        height: u64,
+        blob: &[u8],
+        namespace: Namespace,
+        commitment: &Commitment,
+        app_version: AppVersion,
+    ) -> Result<(), BlobstreamError> {
+        if !verify_blob_commitment(blob, namespace, commitment, app_version) {
+            return Err(BlobstreamError::BlobCommitment {
+                commitment: B256::from(*commitment.hash()),
+            });
+        }
+        self.verify_blob_shares(blob, namespace)?;
+        verify_share_proof(&self.share_proof, self.data_root)?;
+
+        self.inclusion_proof()
+            .verify_without_storage_proof(height, &self.data_root)
+    }
+
+    /// Verify the proof for the `blob` in `namespace` whose first share is at `index` in the
+    /// extended data square of the block at `height`, for blobs asked for without a commitment.
+    ///
+    /// In place of the commitment, the proven shares are checked against the blob, and the shares
+    /// they are proven at against the shares spanned by the blob from `index`. The share proof,
+    /// data root tuple and storage proof are then checked like [BlobstreamProof::verify].
+    pub fn verify_at_index(
+        &self,
+        height: u64,
+        blob: &[u8],
+        namespace: Namespace,
+        index: u64,
+        data_commitments_slot: u32,
+    ) -> Result<(), BlobstreamError> {
+        self.verify_at_index_without_storage_proof(height, blob, namespace, index)?;
+
+        self.inclusion_proof()
+            .verify_storage_proof(data_commitments_slot)
+    }
+
+    /// Verify the proof like [BlobstreamProof::verify_at_index], but trust the data commitment
+    /// instead of checking it against the storage proof.
+    pub fn verify_at_index_without_storage_proof(
+        &self,
+        height: u64,
+        blob: &[u8],
+        namespace: Namespace,
+        index: u64,
+    ) -> Result<(), BlobstreamError> {
+        self.verify_blob_shares(blob, namespace)?;
+        self.verify_share_range(index)?;
+        verify_share_proof(&self.share_proof, self.data_root)?;
+
+        self.inclusion_proof()
+            .verify_without_storage_proof(height, &self.data_root)
+    }
+
+    /// Checks that the shares of the share proof are proven at the shares of the original data
+    /// square spanned by a blob of as many shares whose first share is at `index`.
+    ///
+    /// The positions are only bound to the data root once the share proof is verified, e.g. by
+    /// [BlobstreamProof::verify].
+    pub fn verify_share_range(&self, index: u64) -> Result<(), BlobstreamError> {
+        let shares_len = self.share_proof.shares().len() as u64;
+        let error = || BlobstreamError::ShareRange { index, shares_len };
+
+        let (eds_size, proven) = proven_share_range(&self.share_proof).ok_or_else(error)?;
+        if proven != ods_share_range(index, eds_size, shares_len)? {
+            return Err(error());
+        }
+        Ok(())
+    }
+
+    /// Checks that the shares of the share proof are the shares `blob` is laid out into in
+    /// `namespace`, so the proof is about the blob rather than any other data.
+    fn verify_blob_shares(&self, blob: &[u8], namespace: Namespace) -> Result<(), BlobstreamError> {
+        let shares = self.share_proof.shares();
+        if !verify_blob_shares(blob, namespace, shares) {
+            return Err(BlobstreamError::BlobShares {
+                blob_len: blob.len(),
+                shares: shares.len(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Serialize the struct to JSON, with the byte fields hex encoded and the Celestia proofs in
+    /// the format served by celestia-node
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> Result<alloc::string::String, Box<dyn std::error::Error>> {
+        let json = serde_json::to_string(self)?;
+        Ok(json)
+    }
+
+    /// Deserialize from JSON back into the struct
+    #[cfg(feature = "json")]
+    pub fn from_json(json: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let deserialized = serde_json::from_str(json)?;
+        Ok(deserialized)
+    }
 }
 
-pub fn encode_data_root_tuple(height: u64, data_root: &Hash) -> Vec<u8> {
-    // Create the result vector with 64 bytes capacity
-    let mut result = Vec::with_capacity(64);
+/// A proof that a Celestia data root was committed to by Blobstream
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DataRootInclusionProof {
+    /// The data commitment from Blobstream to verify against
+    pub data_commitment: FixedBytes<32>,
+    /// The Data Root Tuple Inclusion proof
+    pub data_root_tuple_proof: MerkleProof,
+    /// The proof_nonce in blobstream
+    pub proof_nonce: U256,
+    /// The storage root to verify against, zero when the storage proof was skipped
+    pub storage_root: B256,
+    /// The storage proof for the state_dataCommitments mapping slot in Blobstream, if the host
+    /// did not skip it
+    pub storage_proof: Option<Vec<Bytes>>,
+    /// The hash of the L1 block the storage proof was taken at, for auditing
+    pub storage_proof_block: Option<B256>,
+}
+
+impl DataRootInclusionProof {
+    /// Verify that the `data_root` of the block at `height` is included in the data commitment,
+    /// and that the data commitment is stored in the `state_dataCommitments` mapping at
+    /// `data_commitments_slot`.
+    pub fn verify(
+        &self,
+        height: u64,
+        data_root: &Hash,
+        data_commitments_slot: u32,
+    ) -> Result<(), BlobstreamError> {
+        self.verify_without_storage_proof(height, data_root)?;
+        self.verify_storage_proof(data_commitments_slot)
+    }
 
-    // Pad the height to 32 bytes (convert to big-endian and pad with zeros)
-    let height_bytes = height.to_be_bytes();
+    /// Verify that the data commitment is stored in the `state_dataCommitments` mapping at
+    /// `data_commitments_slot`, erroring if the proof carries no storage proof.
+    pub fn verify_storage_proof(&self, data_commitments_slot: u32) -> Result<(), BlobstreamError> {
+        let storage_proof =
+            self.storage_proof
+                .clone()
+                .ok_or(BlobstreamError::MissingStorageProof {
+                    proof_nonce: self.proof_nonce,
+                    data_commitment: self.data_commitment,
+                })?;
 
-    // Add leading zeros (24 bytes of padding)
-    result.extend_from_slice(&[0u8; 24]);
+        verify_data_commitment_storage(
+            self.storage_root,
+            storage_proof,
+            data_commitments_slot,
+            self.proof_nonce,
+            self.data_commitment,
+        )
+    }
+
+    /// Verify that the `data_root` of the block at `height` is included in the data commitment,
+    /// trusting the data commitment instead of checking it against the storage proof.
+    pub fn verify_without_storage_proof(
+        &self,
+        height: u64,
+        data_root: &Hash,
+    ) -> Result<(), BlobstreamError> {
+        verify_data_root_tuple(
+            &self.data_root_tuple_proof,
+            height,
+            data_root,
+            self.data_commitment,
+        )
+    }
 
-    // Add the 8-byte height
-    result.extend_from_slice(&height_bytes);
+    /// Returns whether the proof carries a storage proof for the data commitment.
+    pub fn has_storage_proof(&self) -> bool {
+        self.storage_proof.is_some()
+    }
+}
 
-    // Add the 32-byte data root
-    result.extend_from_slice(data_root.as_bytes());
+/// ABI encodes the [DataRootTuple] of a Celestia block, the leaf its data root is committed to
+/// with in a Blobstream data commitment.
+///
+/// The encoding is `abi.encode(DataRootTuple(height, dataRoot))`, 64 bytes: the height as a
+/// big-endian `uint256`, so 24 zero bytes followed by the 8 byte height, then the 32 byte data
+/// root. Errors if the data root is not a 32 byte hash.
+pub fn encode_data_root_tuple(
+    height: u64,
+    data_root: &Hash,
+) -> Result<Vec<u8>, DataRootTupleError> {
+    let data_root = B256::try_from(data_root.as_bytes())
+        .map_err(|_| DataRootTupleError::InvalidDataRoot(*data_root))?;
 
-    result
+    Ok(DataRootTuple {
+        height: U256::from(height),
+        dataRoot: data_root,
+    }
+    .abi_encode())
 }
 
-/// Verify a storage proof for the state_dataCommitments mapping
+/// Returns the range of share indices in the original data square spanned by a blob whose first
+/// share is at `index` in an extended data square of width `eds_size`.
+///
+/// Errors instead of panicking or wrapping if the square is empty or of odd width, if `index` is
+/// outside the original data square, or if the range would end past it.
+pub fn ods_share_range(
+    index: u64,
+    eds_size: u64,
+    shares_len: u64,
+) -> Result<Range<u64>, InvalidShareRange> {
+    let error = InvalidShareRange {
+        index,
+        eds_size,
+        shares_len,
+    };
+
+    if eds_size == 0 || eds_size % 2 != 0 {
+        return Err(error);
+    }
+    let ods_size = eds_size / 2;
+
+    let row = index / eds_size;
+    let column = index % eds_size;
+    if row >= ods_size || column >= ods_size {
+        return Err(error);
+    }
+
+    // Bounded by `index`, as `row * ods_size + column <= row * eds_size + column`
+    let start = row * ods_size + column;
+    let end = start.checked_add(shares_len).ok_or(error)?;
+    if ods_size
+        .checked_mul(ods_size)
+        .is_some_and(|shares| end > shares)
+    {
+        return Err(error);
+    }
+
+    Ok(start..end)
+}
+
+/// Returns the width of the extended data square a share proof is for, and the range of share
+/// indices in the original data square it proves, or `None` if it does not prove a contiguous
+/// range of shares of a square whose width is a power of two.
+///
+/// The data root commits to the row roots followed by the column roots, so the row proofs have
+/// twice the width of the square as their total. Every row but the first must be proven from its
+/// first share, and every row but the last up to the end of the original data square.
+fn proven_share_range(share_proof: &ShareProof) -> Option<(u64, Range<u64>)> {
+    let raw = RawShareProof::from(share_proof.clone());
+    let row_proof = raw.row_proof?;
+    if row_proof.proofs.len() != raw.share_proofs.len() {
+        return None;
+    }
+
+    let total = u64::try_from(row_proof.proofs.first()?.total).ok()?;
+    let eds_size = total / 2;
+    if total % 2 != 0 || !eds_size.is_power_of_two() {
+        return None;
+    }
+    let ods_size = eds_size / 2;
+
+    let mut proven: Option<Range<u64>> = None;
+    for (row, shares) in row_proof.proofs.iter().zip(&raw.share_proofs) {
+        let index = u64::try_from(row.index).ok()?;
+        let start = u64::try_from(shares.start).ok()?;
+        let end = u64::try_from(shares.end).ok()?;
+        if u64::try_from(row.total).ok()? != total
+            || index >= ods_size
+            || start >= end
+            || end > ods_size
+        {
+            return None;
+        }
+
+        let row_start = index.checked_mul(ods_size)?;
+        let row_end = row_start.checked_add(end)?;
+        proven = Some(match proven {
+            None => row_start + start..row_end,
+            Some(proven) if start == 0 && proven.end == row_start => proven.start..row_end,
+            Some(_) => return None,
+        });
+    }
+
+    proven.map(|proven| (eds_size, proven))
+}
+
+/// Verify a share proof against the data root of its block
+pub fn verify_share_proof(
+    share_proof: &ShareProof,
+    data_root: Hash,
+) -> Result<(), BlobstreamError> {
+    share_proof
+        .verify(data_root)
+        .map_err(|reason| BlobstreamError::ShareProof { data_root, reason })
+}
+
+/// Verify that the data root tuple of the given height is included in a Blobstream data commitment
+pub fn verify_data_root_tuple(
+    data_root_tuple_proof: &MerkleProof,
+    height: u64,
+    data_root: &Hash,
+    data_commitment: B256,
+) -> Result<(), BlobstreamError> {
+    let error = |reason| BlobstreamError::DataRootTuple {
+        height,
+        data_commitment,
+        reason,
+    };
+
+    let tuple = encode_data_root_tuple(height, data_root).map_err(error)?;
+    data_root_tuple_proof
+        .verify(tuple, *data_commitment)
+        .map_err(|reason| error(reason.into()))
+}
+
+/// Verify a storage proof for the state_dataCommitments mapping, which lives at
+/// `data_commitments_slot` (usually [DATA_COMMITMENTS_SLOT])
 pub fn verify_data_commitment_storage(
     root: B256,
     storage_proof: Vec<Bytes>,
+    data_commitments_slot: u32,
     commitment_nonce: U256,
     expected_commitment: B256,
-) -> Result<(), ProofVerificationError> {
+) -> Result<(), BlobstreamError> {
     // Calculate the storage slot for state_dataCommitments[nonce]
-    let slot = calculate_mapping_slot(DATA_COMMITMENTS_SLOT, commitment_nonce);
+    let slot = calculate_mapping_slot(data_commitments_slot, commitment_nonce);
 
     let nibbles = Nibbles::unpack(keccak256(slot));
 
@@ -145,10 +501,14 @@ pub fn verify_data_commitment_storage(
     expected_with_prefix.push(0xa0); // Add the RLP prefix
     expected_with_prefix.extend_from_slice(expected_commitment.as_slice());
 
-    match verify_proof(root, nibbles, Some(expected_with_prefix), &storage_proof) {
-        Ok(_) => Ok(()),
-        Err(err) => return Err(err),
-    }
+    verify_proof(root, nibbles, Some(expected_with_prefix), &storage_proof).map_err(|reason| {
+        BlobstreamError::StorageProof {
+            proof_nonce: commitment_nonce,
+            data_commitment: expected_commitment,
+            storage_root: root,
+            reason,
+        }
+    })
 }
 
 /// Calculate the storage slot for a mapping with a uint256 key
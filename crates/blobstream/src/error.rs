@@ -0,0 +1,106 @@
+//! Errors returned while verifying Blobstream proofs
+
+use alloy_primitives::{B256, U256};
+use alloy_trie::proof::ProofVerificationError;
+use celestia_types::hash::Hash;
+
+/// A Blobstream proof that failed to verify
+#[derive(Debug, thiserror::Error)]
+pub enum BlobstreamError {
+    /// The blob does not match the commitment it was asked for
+    #[error("blob does not match its commitment {commitment}")]
+    BlobCommitment {
+        /// The commitment the blob was asked for
+        commitment: B256,
+    },
+    /// The shares proven by the share proof are not the shares the blob is laid out into
+    #[error("blob of {blob_len} bytes does not match the {shares} shares proven for it")]
+    BlobShares {
+        /// The size of the blob in bytes
+        blob_len: usize,
+        /// The number of shares proven
+        shares: usize,
+    },
+    /// The share index the blob was asked at does not lie in the original data square
+    #[error(transparent)]
+    InvalidShareRange(#[from] InvalidShareRange),
+    /// The share proof does not prove the shares spanned by the blob at the share index it was
+    /// asked at
+    #[error(
+        "share proof does not prove the {shares_len} shares of the blob at share index {index}"
+    )]
+    ShareRange {
+        /// The index of the blob's first share in the extended data square
+        index: u64,
+        /// The number of shares proven
+        shares_len: u64,
+    },
+    /// The share proof does not verify against the block's data root
+    #[error("failed to verify share proof against data root {data_root}: {reason}")]
+    ShareProof {
+        /// The data root the shares were proven against
+        data_root: Hash,
+        /// Why the proof failed
+        reason: celestia_types::Error,
+    },
+    /// The data root tuple is not included in the Blobstream data commitment
+    #[error(
+        "failed to verify data root tuple of height {height} against data commitment {data_commitment}: {reason}"
+    )]
+    DataRootTuple {
+        /// The Celestia height of the data root tuple
+        height: u64,
+        /// The data commitment the tuple was proven against
+        data_commitment: B256,
+        /// Why the proof failed
+        reason: DataRootTupleError,
+    },
+    /// The data commitment is not stored in the Blobstream contract
+    #[error(
+        "failed to verify data commitment {data_commitment} at proof nonce {proof_nonce} against Blobstream storage root {storage_root}: {reason}"
+    )]
+    StorageProof {
+        /// The proof nonce the data commitment is stored at
+        proof_nonce: U256,
+        /// The data commitment expected in storage
+        data_commitment: B256,
+        /// The storage root of the Blobstream contract
+        storage_root: B256,
+        /// Why the proof failed
+        reason: ProofVerificationError,
+    },
+    /// The proof carries no storage proof for the data commitment
+    #[error("no storage proof for data commitment {data_commitment} at proof nonce {proof_nonce}")]
+    MissingStorageProof {
+        /// The proof nonce the data commitment is stored at
+        proof_nonce: U256,
+        /// The data commitment left unproven
+        data_commitment: B256,
+    },
+}
+
+/// Why a data root tuple failed to verify
+#[derive(Debug, thiserror::Error)]
+pub enum DataRootTupleError {
+    /// The data root is not a 32 byte hash, so has no tuple encoding
+    #[error("data root {0:?} is not a 32 byte hash")]
+    InvalidDataRoot(Hash),
+    /// The merkle proof of the tuple does not verify
+    #[error(transparent)]
+    Proof(#[from] celestia_types::Error),
+}
+
+/// A blob whose first share index and share count do not span a range of the original data
+/// square of its block, as returned by a faulty or malicious Celestia node
+#[derive(Debug, Clone, Copy, thiserror::Error)]
+#[error(
+    "Celestia blob at share index {index} spanning {shares_len} shares lies outside the original data square of an extended square of width {eds_size}"
+)]
+pub struct InvalidShareRange {
+    /// The index of the blob's first share in the extended data square
+    pub index: u64,
+    /// The width of the extended data square
+    pub eds_size: u64,
+    /// The number of shares spanned by the blob
+    pub shares_len: u64,
+}
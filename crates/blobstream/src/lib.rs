@@ -8,3 +8,5 @@ extern crate alloc;
 extern crate std;
 
 pub mod blobstream;
+
+pub mod error;
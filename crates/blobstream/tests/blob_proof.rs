@@ -0,0 +1,312 @@
+//! Checks binding a [BlobstreamProof] to the blob it is served with, which run before any of its
+//! proofs: a blob must match its commitment and the shares the proof is for, and when asked for
+//! at a share index, the shares must be proven where the blob lies.
+
+use alloy_primitives::{hex, B256, U256};
+use celestia_types::{
+    hash::Hash, nmt::Namespace, AppVersion, Blob, Commitment, MerkleProof, ShareProof,
+};
+use hana_blobstream::{
+    blobstream::BlobstreamProof,
+    error::{BlobstreamError, InvalidShareRange},
+};
+use serde_json::json;
+
+/// Size in bytes of a Celestia share
+const SHARE_SIZE: usize = 512;
+
+/// The blob the proofs are served with, fitting in a single share
+const BLOB: &[u8] = b"a rollup batch posted to Celestia";
+
+/// Encodes `bytes` as standard padded base64, the encoding of byte fields in celestia-node JSON.
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (i, byte)| {
+            group | ((*byte as u32) << (16 - 8 * i))
+        });
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[((group >> (18 - 6 * i)) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+fn namespace() -> Namespace {
+    Namespace::new_v0(&[0xaa; 10]).unwrap()
+}
+
+fn commitment(blob: &[u8]) -> Commitment {
+    Blob::new(namespace(), blob.to_vec(), AppVersion::latest())
+        .unwrap()
+        .commitment
+}
+
+/// Lays `blob` out into share version 0 sparse shares of the test namespace.
+fn shares(blob: &[u8]) -> Vec<Vec<u8>> {
+    let mut shares = Vec::new();
+    let mut data = blob;
+    loop {
+        let mut share = namespace().as_bytes().to_vec();
+        if shares.is_empty() {
+            share.push(1);
+            share.extend((blob.len() as u32).to_be_bytes());
+        } else {
+            share.push(0);
+        }
+
+        let (chunk, rest) = data.split_at((SHARE_SIZE - share.len()).min(data.len()));
+        share.extend(chunk);
+        share.resize(SHARE_SIZE, 0);
+        shares.push(share);
+
+        data = rest;
+        if data.is_empty() {
+            return shares;
+        }
+    }
+}
+
+/// The share version 0 share holding `blob`, which must fit in one share
+fn share(blob: &[u8]) -> Vec<u8> {
+    let shares = shares(blob);
+    assert_eq!(shares.len(), 1, "blob spans several shares");
+    shares.into_iter().next().unwrap()
+}
+
+/// A namespaced hash covering only the test namespace, with `seed` as its digest
+fn namespaced_hash(seed: u8) -> Vec<u8> {
+    let mut hash = namespace().as_bytes().to_vec();
+    hash.extend(namespace().as_bytes());
+    hash.extend([seed; 32]);
+    hash
+}
+
+/// A proof of `shares` at the start of the first row of a square of width 2, whose NMT and row
+/// proofs do not verify
+fn blobstream_proof(shares: &[Vec<u8>]) -> BlobstreamProof {
+    blobstream_proof_at(shares, 2, &[(0, 0, shares.len())])
+}
+
+/// A proof of `shares` in an extended data square of width `eds_size`, proven in each of `rows`
+/// from a start to an end share of the row, whose NMT and row proofs do not verify
+fn blobstream_proof_at(
+    shares: &[Vec<u8>],
+    eds_size: u64,
+    rows: &[(u64, usize, usize)],
+) -> BlobstreamProof {
+    let share_proof: ShareProof = serde_json::from_value(json!({
+        "data": shares.iter().map(|share| base64(share)).collect::<Vec<_>>(),
+        "share_proofs": rows
+            .iter()
+            .map(|(_, start, end)| json!({
+                "start": start,
+                "end": end,
+                "nodes": [base64(&namespaced_hash(0x77))],
+            }))
+            .collect::<Vec<_>>(),
+        "namespace_id": base64(&namespace().as_bytes()[1..]),
+        "row_proof": {
+            "row_roots": rows
+                .iter()
+                .map(|_| hex::encode_upper(namespaced_hash(0x99)))
+                .collect::<Vec<_>>(),
+            "proofs": rows
+                .iter()
+                .map(|(row, _, _)| json!({
+                    "total": 2 * eds_size,
+                    "index": row,
+                    "leaf_hash": base64(&[0xaa; 32]),
+                    "aunts": [base64(&[0xbb; 32])],
+                }))
+                .collect::<Vec<_>>(),
+            "start_row": rows.first().unwrap().0,
+            "end_row": rows.last().unwrap().0,
+        },
+        "namespace_version": 0,
+    }))
+    .expect("share proof");
+    let data_root_tuple_proof: MerkleProof = serde_json::from_value(json!({
+        "total": 2,
+        "index": 0,
+        "leaf_hash": base64(&[0x22; 32]),
+        "aunts": [base64(&[0x33; 32])],
+    }))
+    .expect("data root tuple proof");
+
+    BlobstreamProof::new(
+        Hash::Sha256([0xee; 32]),
+        B256::repeat_byte(0xef),
+        data_root_tuple_proof,
+        share_proof,
+        U256::from(1),
+        B256::ZERO,
+        None,
+    )
+}
+
+fn verify(proof: &BlobstreamProof, blob: &[u8], commitment: &Commitment) -> BlobstreamError {
+    proof
+        .verify_without_storage_proof(1, blob, namespace(), commitment, AppVersion::latest())
+        .expect_err("proof verified")
+}
+
+#[test]
+fn rejects_a_blob_not_matching_its_commitment() {
+    let proof = blobstream_proof(&[share(BLOB)]);
+
+    let expected = B256::from(*commitment(BLOB).hash());
+
+    let err = verify(&proof, b"another rollup batch", &commitment(BLOB));
+    assert!(
+        matches!(err, BlobstreamError::BlobCommitment { commitment } if commitment == expected),
+        "{err}"
+    );
+}
+
+#[test]
+fn rejects_a_blob_not_matching_the_proven_shares() {
+    let other = b"another rollup batch";
+    let proof = blobstream_proof(&[share(other)]);
+
+    let err = verify(&proof, BLOB, &commitment(BLOB));
+    assert!(
+        matches!(
+            err,
+            BlobstreamError::BlobShares {
+                blob_len,
+                shares: 1
+            } if blob_len == BLOB.len()
+        ),
+        "{err}"
+    );
+}
+
+#[test]
+fn rejects_extra_proven_shares() {
+    let proof = blobstream_proof(&[share(BLOB), share(BLOB)]);
+
+    let err = verify(&proof, BLOB, &commitment(BLOB));
+    assert!(
+        matches!(err, BlobstreamError::BlobShares { shares: 2, .. }),
+        "{err}"
+    );
+}
+
+#[test]
+fn checks_the_share_proof_of_a_matching_blob() {
+    let proof = blobstream_proof(&[share(BLOB)]);
+
+    let err = verify(&proof, BLOB, &commitment(BLOB));
+    assert!(matches!(err, BlobstreamError::ShareProof { .. }), "{err}");
+}
+
+fn verify_at_index(proof: &BlobstreamProof, blob: &[u8], index: u64) -> BlobstreamError {
+    proof
+        .verify_at_index_without_storage_proof(1, blob, namespace(), index)
+        .expect_err("proof verified")
+}
+
+/// A 600 byte blob, spanning two shares
+fn two_share_blob() -> Vec<u8> {
+    (0..600).map(|i| (i % 251) as u8).collect()
+}
+
+#[test]
+fn checks_the_share_proof_of_a_blob_proven_at_its_index() {
+    // Share 9 of an extended square of width 8 is share 5 of its original square of width 4
+    let proof = blobstream_proof_at(&[share(BLOB)], 8, &[(1, 1, 2)]);
+
+    let err = verify_at_index(&proof, BLOB, 9);
+    assert!(matches!(err, BlobstreamError::ShareProof { .. }), "{err}");
+}
+
+#[test]
+fn checks_the_share_proof_of_a_blob_proven_across_rows() {
+    let blob = two_share_blob();
+    let proof = blobstream_proof_at(&shares(&blob), 8, &[(0, 3, 4), (1, 0, 1)]);
+
+    let err = verify_at_index(&proof, &blob, 3);
+    assert!(matches!(err, BlobstreamError::ShareProof { .. }), "{err}");
+}
+
+#[test]
+fn rejects_shares_proven_at_another_index() {
+    let proof = blobstream_proof_at(&[share(BLOB)], 8, &[(1, 1, 2)]);
+
+    for index in [0, 8, 10, 17] {
+        let err = verify_at_index(&proof, BLOB, index);
+        assert!(
+            matches!(
+                err,
+                BlobstreamError::ShareRange {
+                    index: i,
+                    shares_len: 1
+                } if i == index
+            ),
+            "index {index}: {err}"
+        );
+    }
+}
+
+#[test]
+fn rejects_an_index_outside_the_original_square() {
+    let proof = blobstream_proof_at(&[share(BLOB)], 8, &[(1, 1, 2)]);
+
+    let err = verify_at_index(&proof, BLOB, 13);
+    assert!(
+        matches!(
+            err,
+            BlobstreamError::InvalidShareRange(InvalidShareRange {
+                index: 13,
+                eds_size: 8,
+                shares_len: 1,
+            })
+        ),
+        "{err}"
+    );
+}
+
+#[test]
+fn rejects_rows_not_proven_contiguously() {
+    let blob = two_share_blob();
+
+    for rows in [
+        // A gap at the end of the first row
+        [(0, 2, 3), (1, 0, 1)],
+        // A gap at the start of the second row
+        [(0, 3, 4), (1, 1, 2)],
+        // A row skipped
+        [(0, 3, 4), (2, 0, 1)],
+    ] {
+        let proof = blobstream_proof_at(&shares(&blob), 8, &rows);
+
+        let err = verify_at_index(&proof, &blob, 3);
+        assert!(
+            matches!(err, BlobstreamError::ShareRange { index: 3, .. }),
+            "rows {rows:?}: {err}"
+        );
+    }
+}
+
+#[test]
+fn rejects_a_square_width_not_a_power_of_two() {
+    let proof = blobstream_proof_at(&[share(BLOB)], 6, &[(0, 0, 1)]);
+
+    let err = verify_at_index(&proof, BLOB, 0);
+    assert!(matches!(err, BlobstreamError::ShareRange { .. }), "{err}");
+}
+
+#[test]
+fn rejects_a_blob_not_matching_the_shares_proven_at_its_index() {
+    let proof = blobstream_proof_at(&[share(b"another rollup batch")], 8, &[(0, 0, 1)]);
+
+    let err = verify_at_index(&proof, BLOB, 0);
+    assert!(matches!(err, BlobstreamError::BlobShares { .. }), "{err}");
+}
@@ -0,0 +1,87 @@
+//! Cross-checks of [encode_data_root_tuple] against the layout the Blobstream contract hashes.
+
+use alloy_primitives::{B256, U256};
+use alloy_sol_types::SolValue;
+use celestia_types::{hash::Hash, MerkleProof};
+use hana_blobstream::{
+    blobstream::{encode_data_root_tuple, verify_data_root_tuple},
+    error::{BlobstreamError, DataRootTupleError},
+};
+
+/// Heights covering the edges of the 8 byte height
+const HEIGHTS: [u64; 5] = [0, 1, 4_206_669, u32::MAX as u64 + 1, u64::MAX];
+
+/// Data roots with distinct leading and trailing bytes
+fn data_roots() -> [[u8; 32]; 3] {
+    let mut ascending = [0u8; 32];
+    for (i, byte) in ascending.iter_mut().enumerate() {
+        *byte = i as u8;
+    }
+    [[0u8; 32], [0xff; 32], ascending]
+}
+
+/// The documented layout: 24 zero bytes, the big-endian height, then the data root.
+fn reference_layout(height: u64, data_root: [u8; 32]) -> Vec<u8> {
+    let mut encoded = vec![0u8; 24];
+    encoded.extend_from_slice(&height.to_be_bytes());
+    encoded.extend_from_slice(&data_root);
+    encoded
+}
+
+#[test]
+fn matches_abi_encoding_of_the_tuple() {
+    for height in HEIGHTS {
+        for data_root in data_roots() {
+            let expected = (U256::from(height), B256::from(data_root)).abi_encode();
+
+            assert_eq!(
+                encode_data_root_tuple(height, &Hash::Sha256(data_root)).unwrap(),
+                expected,
+                "height {height}, data root {}",
+                B256::from(data_root)
+            );
+        }
+    }
+}
+
+#[test]
+fn matches_documented_layout() {
+    for height in HEIGHTS {
+        for data_root in data_roots() {
+            let encoded = encode_data_root_tuple(height, &Hash::Sha256(data_root)).unwrap();
+
+            assert_eq!(encoded.len(), 64);
+            assert_eq!(encoded, reference_layout(height, data_root));
+        }
+    }
+}
+
+#[test]
+fn rejects_data_root_that_is_not_a_sha256_hash() {
+    assert!(matches!(
+        encode_data_root_tuple(1, &Hash::None),
+        Err(DataRootTupleError::InvalidDataRoot(Hash::None))
+    ));
+}
+
+#[test]
+fn verification_reports_a_bad_data_root_as_a_data_root_tuple_error() {
+    let proof: MerkleProof = serde_json::from_value(serde_json::json!({
+        "total": 1,
+        "index": 0,
+        "leaf_hash": "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=",
+        "aunts": [],
+    }))
+    .expect("data root tuple proof");
+
+    let err = verify_data_root_tuple(&proof, 7, &Hash::None, B256::ZERO).unwrap_err();
+
+    assert!(matches!(
+        err,
+        BlobstreamError::DataRootTuple {
+            height: 7,
+            reason: DataRootTupleError::InvalidDataRoot(_),
+            ..
+        }
+    ));
+}
@@ -0,0 +1,103 @@
+//! Properties of [ods_share_range], which maps the index of a blob's first share in the extended
+//! data square to the shares it spans in the original data square.
+
+use hana_blobstream::blobstream::ods_share_range;
+use proptest::prelude::*;
+
+/// A square of width `2^k` and a blob starting inside its original data square, with a length
+/// that keeps it inside, as `(eds_size, row, column, shares_len)`.
+fn blob_in_square() -> impl Strategy<Value = (u64, u64, u64, u64)> {
+    (1u32..=8)
+        .prop_flat_map(|k| {
+            let eds_size = 1u64 << k;
+            let ods_size = eds_size / 2;
+            (Just(eds_size), 0..ods_size, 0..ods_size)
+        })
+        .prop_flat_map(|(eds_size, row, column)| {
+            let ods_size = eds_size / 2;
+            let start = row * ods_size + column;
+            (
+                Just(eds_size),
+                Just(row),
+                Just(column),
+                0..=ods_size * ods_size - start,
+            )
+        })
+}
+
+/// Square widths around the valid ones and the edges of `u64`
+fn eds_size() -> impl Strategy<Value = u64> {
+    prop_oneof![
+        0u64..=260,
+        (1u32..64).prop_map(|k| 1u64 << k),
+        any::<u64>(),
+        Just(u64::MAX),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn blobs_inside_the_square_span_their_shares(
+        (eds_size, row, column, shares_len) in blob_in_square(),
+    ) {
+        let ods_size = eds_size / 2;
+        let start = row * ods_size + column;
+
+        prop_assert_eq!(
+            ods_share_range(row * eds_size + column, eds_size, shares_len).ok(),
+            Some(start..start + shares_len)
+        );
+    }
+
+    #[test]
+    fn blobs_starting_in_the_parity_columns_are_rejected(
+        (eds_size, row, column, _) in blob_in_square(),
+    ) {
+        let ods_size = eds_size / 2;
+        let index = row * eds_size + ods_size + column;
+
+        prop_assert!(ods_share_range(index, eds_size, 1).is_err());
+    }
+
+    #[test]
+    fn blobs_starting_in_the_parity_rows_are_rejected(
+        (eds_size, row, column, _) in blob_in_square(),
+    ) {
+        let ods_size = eds_size / 2;
+        let index = (ods_size + row) * eds_size + column;
+
+        prop_assert!(ods_share_range(index, eds_size, 1).is_err());
+    }
+
+    #[test]
+    fn blobs_ending_past_the_square_are_rejected(
+        (eds_size, row, column, _) in blob_in_square(),
+        overflow in 1u64..=u64::MAX,
+    ) {
+        let ods_size = eds_size / 2;
+        let start = row * ods_size + column;
+        let shares_len = (ods_size * ods_size - start).saturating_add(overflow);
+
+        prop_assert!(ods_share_range(row * eds_size + column, eds_size, shares_len).is_err());
+    }
+
+    /// Any input either errors or yields a range of `shares_len` shares inside the original data
+    /// square, without panicking on overflow.
+    #[test]
+    fn ranges_stay_inside_the_original_data_square(
+        index in any::<u64>(),
+        eds_size in eds_size(),
+        shares_len in any::<u64>(),
+    ) {
+        if let Ok(range) = ods_share_range(index, eds_size, shares_len) {
+            prop_assert!(eds_size != 0 && eds_size % 2 == 0);
+            let ods_size = eds_size / 2;
+
+            prop_assert_eq!(range.start, index / eds_size * ods_size + index % eds_size);
+            prop_assert_eq!(range.end - range.start, shares_len);
+            if let Some(shares) = ods_size.checked_mul(ods_size) {
+                prop_assert!(range.end <= shares);
+            }
+        }
+    }
+}
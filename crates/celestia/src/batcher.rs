@@ -0,0 +1,45 @@
+//! Extraction of the Celestia pointers posted by the batcher, without fetching their blobs
+
+use crate::pointer::CelestiaPointer;
+
+use alloy_primitives::Address;
+
+/// A transaction posted to L1, reduced to the fields identifying batcher data
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatcherTx<'a> {
+    /// The sender of the transaction
+    pub from: Address,
+    /// The recipient of the transaction, `None` for contract creations
+    pub to: Option<Address>,
+    /// The calldata of the transaction
+    pub input: &'a [u8],
+}
+
+/// Returns the Celestia pointers posted to `batch_inbox` among `txs`, in order, by `batcher` if
+/// it is known.
+///
+/// Batcher data that is not a Celestia pointer tagged with `da_layer_byte` is skipped, like the
+/// [CelestiaDADataSource](crate::CelestiaDADataSource) passes it through without a fetch.
+pub fn batcher_pointers<'a>(
+    txs: impl IntoIterator<Item = BatcherTx<'a>> + 'a,
+    batch_inbox: Address,
+    batcher: Option<Address>,
+    da_layer_byte: u8,
+) -> impl Iterator<Item = CelestiaPointer> + 'a {
+    txs.into_iter()
+        .filter(move |tx| {
+            tx.to == Some(batch_inbox) && !batcher.is_some_and(|batcher| tx.from != batcher)
+        })
+        .filter_map(move |tx| {
+            match CelestiaPointer::decode_with_da_layer_byte(tx.input, da_layer_byte) {
+                Ok(pointer) => Some(pointer),
+                Err(err) => {
+                    debug!(
+                        target: "celestia-batcher",
+                        "Skipping batcher data that is not a Celestia pointer: {}", err
+                    );
+                    None
+                }
+            }
+        })
+}
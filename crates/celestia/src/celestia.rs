@@ -1,12 +1,13 @@
 //! [CelestiaDADataSource] an implementation of the [DataAvailabilityProvider] trait.
 
+use crate::decoder::{BlobDecoder, IdentityDecoder};
+use crate::pointer::{CelestiaPointer, CelestiaPointerError, CELESTIA_DA_LAYER_BYTE};
 use crate::source::CelestiaDASource;
 use crate::traits::CelestiaProvider;
 
 use alloc::{boxed::Box, fmt::Debug};
 use alloy_primitives::{Address, Bytes};
 use async_trait::async_trait;
-use celestia_types::Commitment;
 use kona_derive::{
     errors::{PipelineError, PipelineErrorKind},
     sources::EthereumDataSource,
@@ -15,43 +16,81 @@ use kona_derive::{
 };
 use kona_protocol::BlockInfo;
 /// A factory for creating a Celestia data source provider.
+///
+/// The [EthereumDataSource] yields the batcher data of an L1 block one transaction at a time, so
+/// each item is routed on its own: a Celestia pointer is replaced by the blobs it references,
+/// and any other data is passed through. A block mixing both yields them in transaction order,
+/// with all the blobs of a pointer before the data of the next transaction.
 #[derive(Debug, Clone)]
-pub struct CelestiaDADataSource<C, B, A>
+pub struct CelestiaDADataSource<C, B, A, D = IdentityDecoder>
 where
     C: ChainProvider + Send + Clone,
     B: BlobProvider + Send + Clone,
     A: CelestiaProvider + Send + Clone,
+    D: BlobDecoder + Send + Clone,
 {
     /// The blob source.
     pub ethereum_source: EthereumDataSource<C, B>,
     /// The celestia source.
-    pub celestia_source: CelestiaDASource<A>,
+    pub celestia_source: CelestiaDASource<A, D>,
+    /// The DA layer byte identifying Celestia pointers in the batcher data
+    da_layer_byte: u8,
+    /// The lowest Celestia height a pointer may reference
+    min_height: u64,
+    /// The highest Celestia height a pointer may reference
+    max_height: u64,
 }
 
-impl<C, B, A> CelestiaDADataSource<C, B, A>
+impl<C, B, A, D> CelestiaDADataSource<C, B, A, D>
 where
     C: ChainProvider + Send + Clone + Debug,
     B: BlobProvider + Send + Clone + Debug,
     A: CelestiaProvider + Send + Clone + Debug,
+    D: BlobDecoder + Send + Clone + Debug,
 {
     /// Creates a [CelestiaDADataSource] from the given sources.
     pub const fn new(
         ethereum_source: EthereumDataSource<C, B>,
-        celestia_source: CelestiaDASource<A>,
+        celestia_source: CelestiaDASource<A, D>,
     ) -> Self {
         Self {
             ethereum_source,
             celestia_source,
+            da_layer_byte: CELESTIA_DA_LAYER_BYTE,
+            min_height: 0,
+            max_height: u64::MAX,
         }
     }
+
+    /// Sets the DA layer byte identifying Celestia pointers, for rollups tagging their pointers
+    /// with another byte than the [CELESTIA_DA_LAYER_BYTE]. Batcher data tagged with any other
+    /// byte is passed through to the pipeline as is.
+    pub const fn with_da_layer_byte(mut self, da_layer_byte: u8) -> Self {
+        self.da_layer_byte = da_layer_byte;
+        self
+    }
+
+    /// Returns the DA layer byte identifying Celestia pointers.
+    pub const fn da_layer_byte(&self) -> u8 {
+        self.da_layer_byte
+    }
+
+    /// Bounds the Celestia heights pointers may reference to `min_height..=max_height`. Pointers
+    /// outside the bounds are skipped like malformed pointers, without fetching the blob.
+    pub const fn with_height_bounds(mut self, min_height: u64, max_height: u64) -> Self {
+        self.min_height = min_height;
+        self.max_height = max_height;
+        self
+    }
 }
 
 #[async_trait]
-impl<C, B, A> DataAvailabilityProvider for CelestiaDADataSource<C, B, A>
+impl<C, B, A, D> DataAvailabilityProvider for CelestiaDADataSource<C, B, A, D>
 where
     C: ChainProvider + Send + Sync + Clone + Debug,
     B: BlobProvider + Send + Sync + Clone + Debug,
     A: CelestiaProvider + Send + Sync + Clone + Debug,
+    D: BlobDecoder + Send + Sync + Clone + Debug,
 {
     type Item = Bytes;
 
@@ -60,26 +99,45 @@ where
         block_ref: &BlockInfo,
         batcher_address: Address,
     ) -> PipelineResult<Self::Item> {
-        // Feth Blob pointer from the Ethereum Data Source
+        // Return the remaining blobs of the current pointer before moving to the next one
+        if let Some(blob) = self.celestia_source.next_pending() {
+            return Ok(blob);
+        }
+
+        // Fetch the next batcher transaction's data from the Ethereum data source, which yields
+        // one transaction per call rather than every frame of the block at once
         let pointer_data = self
             .ethereum_source
             .next(block_ref, batcher_address)
             .await?;
 
-        if pointer_data[2] != 0x0c {
-            // check if there's more appropirate error, since we just fetched a celestia batch that does not correspond to celestia
-            return Err(PipelineErrorKind::Temporary(PipelineError::EndOfSource));
-        }
-
-        let height_bytes = &pointer_data[3..11];
-        let height = u64::from_le_bytes(height_bytes.try_into().unwrap());
-        let hash_array: [u8; 32] = pointer_data[11..43]
-            .try_into()
-            .expect("Slice must be 32 bytes");
-        let commitment = Commitment::new(hash_array);
+        let pointer = match CelestiaPointer::decode_with_da_layer_byte(
+            &pointer_data,
+            self.da_layer_byte,
+        )
+        .and_then(|pointer| {
+            pointer.check_height(self.min_height, self.max_height)?;
+            Ok(pointer)
+        }) {
+            Ok(pointer) => pointer,
+            // Data not tagged as a Celestia pointer, e.g. frames posted straight to Ethereum by a
+            // rollup migrating between DA layers, is handed to the pipeline as is
+            Err(
+                err @ (CelestiaPointerError::TooShort { .. }
+                | CelestiaPointerError::NotAltDa { .. }
+                | CelestiaPointerError::NotCelestia { .. }),
+            ) => {
+                debug!(target: "celestia-source", "Passing through batcher data that is not a Celestia pointer: {}", err);
+                return Ok(pointer_data);
+            }
+            Err(err) => {
+                warn!(target: "celestia-source", "Skipping malformed Celestia pointer: {}", err);
+                return Err(PipelineErrorKind::Temporary(PipelineError::EndOfSource));
+            }
+        };
 
-        info!("Fetching blob at height: {:?}", height);
-        let blob = self.celestia_source.next(height, commitment).await?;
+        debug!(target: "celestia-source", "Fetching blob at height: {:?}", pointer.height());
+        let blob = self.celestia_source.next(pointer).await?;
         Ok(blob)
     }
 
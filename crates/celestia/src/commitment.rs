@@ -0,0 +1,26 @@
+//! Recomputation of the share commitment of a blob, without any Blobstream proof
+
+use celestia_types::{nmt::Namespace, AppVersion, Blob, Commitment};
+
+/// Returns whether `commitment` is the share commitment of `blob` in `namespace`, recomputing it
+/// from the blob's shares laid out under the rules of `app_version`.
+///
+/// This only binds the blob to the commitment, not to any Celestia block: it says nothing of the
+/// blob's inclusion, which takes the Blobstream proof.
+pub fn verify_blob_commitment(
+    blob: &[u8],
+    namespace: Namespace,
+    commitment: &Commitment,
+    app_version: AppVersion,
+) -> bool {
+    match Blob::new(namespace, blob.to_vec(), app_version) {
+        Ok(blob) => blob.commitment == *commitment,
+        Err(err) => {
+            debug!(
+                target: "celestia-commitment",
+                "Failed computing the commitment of the blob: {}", err
+            );
+            false
+        }
+    }
+}
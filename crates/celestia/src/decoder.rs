@@ -0,0 +1,101 @@
+//! Decoding of the blob data fetched from Celestia into the rollup's frame data
+
+use alloc::{format, string::String, vec::Vec};
+use alloy_primitives::Bytes;
+use kona_derive::errors::BlobProviderError;
+
+/// Size in bytes of a Celestia share
+pub(crate) const SHARE_SIZE: usize = 512;
+
+/// Size in bytes of a Celestia namespace
+pub(crate) const NAMESPACE_SIZE: usize = 29;
+
+/// Size in bytes of a share's info byte
+pub(crate) const SHARE_INFO_BYTES: usize = 1;
+
+/// Size in bytes of the sequence length carried by the first share of a blob
+pub(crate) const SEQUENCE_LEN_BYTES: usize = 4;
+
+/// Size in bytes of the signer carried by the first share of a share version 1 blob
+const SIGNER_SIZE: usize = 20;
+
+/// Turns the data of a fetched Celestia blob into the data handed to the derivation pipeline
+pub trait BlobDecoder {
+    /// Decodes the blob data.
+    fn decode(&self, blob: Bytes) -> Result<Bytes, BlobProviderError>;
+}
+
+/// A [BlobDecoder] returning the blob data unchanged
+#[derive(Debug, Clone, Copy, Default)]
+pub struct IdentityDecoder;
+
+impl BlobDecoder for IdentityDecoder {
+    fn decode(&self, blob: Bytes) -> Result<Bytes, BlobProviderError> {
+        Ok(blob)
+    }
+}
+
+/// A [BlobDecoder] for blobs posted as raw Celestia sparse shares, stripping each share's
+/// namespace and info byte, and the sequence length and signer of the first share.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SparseSharesDecoder;
+
+impl BlobDecoder for SparseSharesDecoder {
+    fn decode(&self, blob: Bytes) -> Result<Bytes, BlobProviderError> {
+        if blob.is_empty() || blob.len() % SHARE_SIZE != 0 {
+            return Err(decoding_error(format!(
+                "blob of {} bytes is not a sequence of {SHARE_SIZE} byte shares",
+                blob.len()
+            )));
+        }
+
+        let mut shares = blob.chunks_exact(SHARE_SIZE);
+        let first = shares.next().expect("blob holds at least one share");
+
+        let info = first[NAMESPACE_SIZE];
+        let share_version = info >> 1;
+        let is_sequence_start = info & 1 == 1;
+        if !is_sequence_start {
+            return Err(decoding_error(format!(
+                "first share does not start a sequence, info byte {info:#04x}"
+            )));
+        }
+
+        let len_offset = NAMESPACE_SIZE + SHARE_INFO_BYTES;
+        let sequence_len = u32::from_be_bytes(
+            first[len_offset..len_offset + SEQUENCE_LEN_BYTES]
+                .try_into()
+                .expect("slice is 4 bytes"),
+        ) as usize;
+
+        let data_offset = match share_version {
+            0 => len_offset + SEQUENCE_LEN_BYTES,
+            1 => len_offset + SEQUENCE_LEN_BYTES + SIGNER_SIZE,
+            version => {
+                return Err(decoding_error(format!(
+                    "unsupported share version {version}"
+                )))
+            }
+        };
+
+        let mut data = Vec::with_capacity(sequence_len);
+        data.extend_from_slice(&first[data_offset..]);
+        for share in shares {
+            data.extend_from_slice(&share[NAMESPACE_SIZE + SHARE_INFO_BYTES..]);
+        }
+
+        if data.len() < sequence_len {
+            return Err(decoding_error(format!(
+                "shares hold {} bytes, less than the sequence length {sequence_len}",
+                data.len()
+            )));
+        }
+        data.truncate(sequence_len);
+
+        Ok(data.into())
+    }
+}
+
+fn decoding_error(message: String) -> BlobProviderError {
+    BlobProviderError::Backend(format!("failed to decode Celestia blob: {message}"))
+}
@@ -0,0 +1,102 @@
+//! A [CelestiaProvider] falling back to a secondary provider when the primary one fails
+
+use crate::traits::CelestiaProvider;
+
+use alloc::{boxed::Box, vec::Vec};
+use alloy_primitives::Bytes;
+use async_trait::async_trait;
+use celestia_types::{nmt::Namespace, Commitment};
+
+/// A [CelestiaProvider] sending each request to a primary provider, and to a secondary one if
+/// the primary fails with an error classified as temporary by [CelestiaProvider::is_temporary].
+///
+/// Other errors, e.g. a blob failing verification, are returned as is: the secondary would
+/// serve the same data.
+#[derive(Debug, Clone)]
+pub struct FallbackCelestiaProvider<P, S> {
+    primary: P,
+    secondary: S,
+}
+
+impl<P, S> FallbackCelestiaProvider<P, S> {
+    /// Creates a provider trying `primary` first and falling back to `secondary`.
+    pub const fn new(primary: P, secondary: S) -> Self {
+        Self { primary, secondary }
+    }
+
+    /// Returns the primary provider.
+    pub const fn primary(&self) -> &P {
+        &self.primary
+    }
+
+    /// Returns the secondary provider.
+    pub const fn secondary(&self) -> &S {
+        &self.secondary
+    }
+}
+
+#[async_trait]
+impl<P, S> CelestiaProvider for FallbackCelestiaProvider<P, S>
+where
+    P: CelestiaProvider + Send + Sync,
+    S: CelestiaProvider<Error = P::Error> + Send + Sync,
+{
+    type Error = P::Error;
+
+    /// An error is temporary if either provider classifies it so, as it may come from either.
+    fn is_temporary(error: &Self::Error) -> bool {
+        P::is_temporary(error) || S::is_temporary(error)
+    }
+
+    fn namespace(&self) -> Option<Namespace> {
+        self.primary
+            .namespace()
+            .or_else(|| self.secondary.namespace())
+    }
+
+    async fn blob_get(&self, height: u64, commitment: Commitment) -> Result<Bytes, Self::Error> {
+        match self.primary.blob_get(height, commitment).await {
+            Err(err) if P::is_temporary(&err) => {
+                warn!(
+                    target: "celestia-fallback",
+                    "Primary Celestia provider failed fetching the blob at height {}, falling back: {}",
+                    height, err
+                );
+                self.secondary.blob_get(height, commitment).await
+            }
+            result => result,
+        }
+    }
+
+    async fn blobs_get(
+        &self,
+        height: u64,
+        commitment: Commitment,
+    ) -> Result<Vec<(u64, Bytes)>, Self::Error> {
+        match self.primary.blobs_get(height, commitment).await {
+            Err(err) if P::is_temporary(&err) => {
+                warn!(
+                    target: "celestia-fallback",
+                    "Primary Celestia provider failed fetching the blobs at height {}, falling back: {}",
+                    height, err
+                );
+                self.secondary.blobs_get(height, commitment).await
+            }
+            result => result,
+        }
+    }
+
+    async fn blob_get_at_index(&self, height: u64, index: u64) -> Result<Bytes, Self::Error> {
+        match self.primary.blob_get_at_index(height, index).await {
+            Err(err) if P::is_temporary(&err) => {
+                warn!(
+                    target: "celestia-fallback",
+                    "Primary Celestia provider failed fetching the blob at height {} index {}, falling back: {}",
+                    height, index, err
+                );
+                self.secondary.blob_get_at_index(height, index).await
+            }
+            result => result,
+        }
+    }
+}
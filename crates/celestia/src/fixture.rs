@@ -0,0 +1,165 @@
+//! A [CelestiaProvider] serving blobs from canned fixtures
+
+use crate::traits::CelestiaProvider;
+
+use alloc::{boxed::Box, collections::BTreeMap, format, string::String};
+use alloy_primitives::{hex, Bytes};
+use async_trait::async_trait;
+use celestia_types::Commitment;
+use kona_derive::errors::{PipelineError, PipelineErrorKind};
+use std::{fs, io, path::Path};
+
+/// Extension of fixture files holding the raw blob data
+pub const RAW_FIXTURE_EXTENSION: &str = "bin";
+
+/// Extension of fixture files holding a serialized oracle payload
+pub const PAYLOAD_FIXTURE_EXTENSION: &str = "payload";
+
+/// Extracts the blob data from a serialized oracle payload fixture
+pub type PayloadDecoder = fn(&[u8]) -> Result<Bytes, String>;
+
+/// A [CelestiaProvider] serving blobs from an in-memory set of `(height, commitment)` and
+/// `(height, index)` fixtures
+#[derive(Debug, Clone, Default)]
+pub struct FixtureCelestiaProvider {
+    blobs: BTreeMap<(u64, [u8; 32]), Bytes>,
+    indexed_blobs: BTreeMap<(u64, u64), Bytes>,
+}
+
+/// What a fixture file is looked up by, parsed from its name
+enum FixtureKey {
+    Commitment(u64, Commitment),
+    Index(u64, u64),
+}
+
+impl FixtureCelestiaProvider {
+    /// Creates a provider without any fixtures.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the blob served for `commitment` at `height`.
+    pub fn insert(&mut self, height: u64, commitment: Commitment, blob: Bytes) {
+        self.blobs.insert((height, *commitment.hash()), blob);
+    }
+
+    /// Adds the blob served for the share `index` at `height`.
+    pub fn insert_at_index(&mut self, height: u64, index: u64, blob: Bytes) {
+        self.indexed_blobs.insert((height, index), blob);
+    }
+
+    /// Loads every fixture in `dir`.
+    ///
+    /// Fixture files are named `<height>-<commitment hex>.<extension>`, or
+    /// `<height>-index-<share index>.<extension>` for blobs looked up by index. Files with the
+    /// [RAW_FIXTURE_EXTENSION] hold the blob data as-is, files with the
+    /// [PAYLOAD_FIXTURE_EXTENSION] hold a serialized payload that `decode_payload` extracts the
+    /// blob from. Files with any other extension are ignored.
+    pub fn load_dir(
+        dir: impl AsRef<Path>,
+        decode_payload: Option<PayloadDecoder>,
+    ) -> io::Result<Self> {
+        let mut provider = Self::new();
+
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+
+            let Some(extension) = path.extension().and_then(|ext| ext.to_str()) else {
+                continue;
+            };
+            if extension != RAW_FIXTURE_EXTENSION && extension != PAYLOAD_FIXTURE_EXTENSION {
+                continue;
+            }
+
+            let stem = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .unwrap_or_default();
+            let key = parse_fixture_name(stem)
+                .ok_or_else(|| invalid_data(format!("invalid fixture name {}", path.display())))?;
+
+            let contents = fs::read(&path)?;
+            let blob = if extension == RAW_FIXTURE_EXTENSION {
+                Bytes::from(contents)
+            } else {
+                let decode_payload = decode_payload.ok_or_else(|| {
+                    invalid_data(format!("no payload decoder for {}", path.display()))
+                })?;
+                decode_payload(&contents).map_err(|err| {
+                    invalid_data(format!(
+                        "invalid payload fixture {}: {}",
+                        path.display(),
+                        err
+                    ))
+                })?
+            };
+
+            match key {
+                FixtureKey::Commitment(height, commitment) => {
+                    provider.insert(height, commitment, blob)
+                }
+                FixtureKey::Index(height, index) => provider.insert_at_index(height, index, blob),
+            }
+        }
+
+        Ok(provider)
+    }
+
+    /// Returns the number of fixtures held by the provider.
+    pub fn len(&self) -> usize {
+        self.blobs.len() + self.indexed_blobs.len()
+    }
+
+    /// Returns whether the provider holds no fixtures.
+    pub fn is_empty(&self) -> bool {
+        self.blobs.is_empty() && self.indexed_blobs.is_empty()
+    }
+}
+
+#[async_trait]
+impl CelestiaProvider for FixtureCelestiaProvider {
+    type Error = PipelineErrorKind;
+
+    async fn blob_get(&self, height: u64, commitment: Commitment) -> Result<Bytes, Self::Error> {
+        self.blobs
+            .get(&(height, *commitment.hash()))
+            .cloned()
+            .ok_or_else(|| {
+                PipelineError::Provider(format!(
+                    "no fixture for commitment {} at height {}",
+                    hex::encode(commitment.hash()),
+                    height
+                ))
+                .crit()
+            })
+    }
+
+    async fn blob_get_at_index(&self, height: u64, index: u64) -> Result<Bytes, Self::Error> {
+        self.indexed_blobs
+            .get(&(height, index))
+            .cloned()
+            .ok_or_else(|| {
+                PipelineError::Provider(format!(
+                    "no fixture for share index {} at height {}",
+                    index, height
+                ))
+                .crit()
+            })
+    }
+}
+
+fn parse_fixture_name(stem: &str) -> Option<FixtureKey> {
+    let (height, rest) = stem.split_once('-')?;
+    let height = height.parse().ok()?;
+
+    if let Some(index) = rest.strip_prefix("index-") {
+        return Some(FixtureKey::Index(height, index.parse().ok()?));
+    }
+    let commitment: [u8; 32] = hex::decode(rest).ok()?.try_into().ok()?;
+
+    Some(FixtureKey::Commitment(height, Commitment::new(commitment)))
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
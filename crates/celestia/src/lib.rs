@@ -5,14 +5,50 @@
 
 extern crate alloc;
 
+#[cfg(feature = "std")]
+extern crate std;
+
 #[macro_use]
 extern crate tracing;
 
 mod traits;
-pub use traits::CelestiaProvider;
+pub use traits::{CelestiaProvider, DEFAULT_MAX_BLOB_BYTES};
+
+mod fallback;
+pub use fallback::FallbackCelestiaProvider;
+
+mod decoder;
+pub use decoder::{BlobDecoder, IdentityDecoder, SparseSharesDecoder};
+
+mod pointer;
+pub use pointer::{
+    CelestiaPointer, CelestiaPointerError, ALTDA_DERIVATION_VERSION, CELESTIA_DA_LAYER_BYTE,
+    GENERIC_COMMITMENT_TYPE, INDEX_POINTER_VERSION,
+};
+
+mod commitment;
+pub use commitment::verify_blob_commitment;
+
+mod shares;
+pub use shares::verify_blob_shares;
+
+mod batcher;
+pub use batcher::{batcher_pointers, BatcherTx};
 
 mod source;
-pub use source::CelestiaDASource;
+pub use source::{CelestiaDASource, SourceState};
 
 mod celestia;
 pub use celestia::CelestiaDADataSource;
+
+#[cfg(feature = "test-utils")]
+mod mock;
+#[cfg(feature = "test-utils")]
+pub use mock::{MockCall, MockCelestiaProvider};
+
+#[cfg(all(feature = "test-utils", feature = "std"))]
+mod fixture;
+#[cfg(all(feature = "test-utils", feature = "std"))]
+pub use fixture::{
+    FixtureCelestiaProvider, PayloadDecoder, PAYLOAD_FIXTURE_EXTENSION, RAW_FIXTURE_EXTENSION,
+};
@@ -0,0 +1,96 @@
+//! A scripted [CelestiaProvider] for tests
+
+use crate::traits::CelestiaProvider;
+
+use alloc::{boxed::Box, collections::VecDeque, string::ToString, sync::Arc, vec::Vec};
+use alloy_primitives::Bytes;
+use async_trait::async_trait;
+use celestia_types::Commitment;
+use kona_derive::errors::{PipelineError, PipelineErrorKind};
+use spin::Mutex;
+
+/// A request received by a [MockCelestiaProvider]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MockCall {
+    /// A [CelestiaProvider::blob_get] call
+    BlobGet {
+        /// The requested Celestia height
+        height: u64,
+        /// The requested blob commitment
+        commitment: Commitment,
+    },
+    /// A [CelestiaProvider::blob_get_at_index] call
+    BlobGetAtIndex {
+        /// The requested Celestia height
+        height: u64,
+        /// The requested share index
+        index: u64,
+    },
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    results: VecDeque<Result<Bytes, PipelineErrorKind>>,
+    calls: Vec<MockCall>,
+}
+
+/// A [CelestiaProvider] answering each request with the next queued result and recording the
+/// requests it received.
+///
+/// Clones share their queue and call log, so a test can keep a handle to a provider it moved
+/// into a source.
+#[derive(Debug, Clone, Default)]
+pub struct MockCelestiaProvider {
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockCelestiaProvider {
+    /// Creates a provider without any queued results.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queues a blob to be returned. An empty blob stands for a proven namespace absence.
+    pub fn push_blob(&self, blob: impl Into<Bytes>) {
+        self.state.lock().results.push_back(Ok(blob.into()));
+    }
+
+    /// Queues an error to be returned.
+    pub fn push_error(&self, err: PipelineErrorKind) {
+        self.state.lock().results.push_back(Err(err));
+    }
+
+    /// Returns the requests received so far, oldest first.
+    pub fn calls(&self) -> Vec<MockCall> {
+        self.state.lock().calls.clone()
+    }
+
+    /// Returns the number of queued results not yet returned.
+    pub fn pending_results(&self) -> usize {
+        self.state.lock().results.len()
+    }
+
+    fn respond(&self, call: MockCall) -> Result<Bytes, PipelineErrorKind> {
+        let mut state = self.state.lock();
+        state.calls.push(call);
+        state.results.pop_front().unwrap_or_else(|| {
+            Err(
+                PipelineError::Provider("mock celestia provider has no queued result".to_string())
+                    .crit(),
+            )
+        })
+    }
+}
+
+#[async_trait]
+impl CelestiaProvider for MockCelestiaProvider {
+    type Error = PipelineErrorKind;
+
+    async fn blob_get(&self, height: u64, commitment: Commitment) -> Result<Bytes, Self::Error> {
+        self.respond(MockCall::BlobGet { height, commitment })
+    }
+
+    async fn blob_get_at_index(&self, height: u64, index: u64) -> Result<Bytes, Self::Error> {
+        self.respond(MockCall::BlobGetAtIndex { height, index })
+    }
+}
@@ -0,0 +1,231 @@
+//! Decoding of the Celestia DA pointers posted by the batcher.
+//!
+//! A pointer is posted as the calldata of a batcher transaction, following the OP alt-DA
+//! commitment layout:
+//!
+//! | bytes  | content                                                               |
+//! |--------|-----------------------------------------------------------------------|
+//! | 0      | derivation version, [ALTDA_DERIVATION_VERSION]                        |
+//! | 1      | commitment type, [GENERIC_COMMITMENT_TYPE]                            |
+//! | 2      | DA layer byte, [CELESTIA_DA_LAYER_BYTE] by default                    |
+//! | 3..11  | commitment pointer: Celestia height, 8 bytes little endian            |
+//! | 11..43 | commitment pointer: share commitment of the blob, 32 bytes            |
+//! | 3      | index pointer: version, [INDEX_POINTER_VERSION]                       |
+//! | 4..12  | index pointer: Celestia height, 8 bytes little endian                 |
+//! | 12..20 | index pointer: index of the blob's first share, 8 bytes little endian |
+//!
+//! The two pointer layouts are told apart by their length.
+
+use celestia_types::Commitment;
+
+/// The derivation version byte of batcher data holding an alt-DA commitment rather than frames.
+pub const ALTDA_DERIVATION_VERSION: u8 = 0x01;
+
+/// The commitment type byte of a generic alt-DA commitment, whose DA layer byte follows.
+pub const GENERIC_COMMITMENT_TYPE: u8 = 0x01;
+
+/// The DA layer byte identifying a Celestia pointer, unless a rollup configures another one.
+pub const CELESTIA_DA_LAYER_BYTE: u8 = 0x0c;
+
+/// The version byte identifying a pointer that references a blob by its share index.
+pub const INDEX_POINTER_VERSION: u8 = 0x01;
+
+/// The length of a pointer referencing a blob by height and commitment.
+const COMMITMENT_POINTER_LEN: usize = 43;
+
+/// The length of a pointer referencing a blob by height and share index.
+const INDEX_POINTER_LEN: usize = 20;
+
+/// The length of the derivation prefix shared by all pointers.
+const PREFIX_LEN: usize = 3;
+
+/// The length of the share commitment carried by a commitment pointer.
+const COMMITMENT_LEN: usize = 32;
+
+/// Errors returned when decoding a [CelestiaPointer]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum CelestiaPointerError {
+    /// The data is shorter than the derivation prefix
+    #[error("pointer data of {len} bytes is shorter than the {PREFIX_LEN} byte prefix")]
+    TooShort {
+        /// The length of the data
+        len: usize,
+    },
+    /// The derivation version byte does not mark an alt-DA commitment, e.g. frames posted
+    /// straight to Ethereum
+    #[error(
+        "derivation version {version:#04x} is not the alt-DA version {ALTDA_DERIVATION_VERSION:#04x}"
+    )]
+    NotAltDa {
+        /// The derivation version byte found in the prefix
+        version: u8,
+    },
+    /// The alt-DA commitment is of a type other than a generic commitment, so it cannot
+    /// reference Celestia data
+    #[error(
+        "unsupported alt-DA commitment type {commitment_type:#04x}, expected {GENERIC_COMMITMENT_TYPE:#04x}"
+    )]
+    UnsupportedCommitmentType {
+        /// The commitment type byte found in the prefix
+        commitment_type: u8,
+    },
+    /// The DA layer byte does not identify Celestia
+    #[error("DA layer byte {da_layer_byte:#04x} is not the Celestia byte {expected:#04x}")]
+    NotCelestia {
+        /// The DA layer byte found in the prefix
+        da_layer_byte: u8,
+        /// The DA layer byte identifying Celestia pointers
+        expected: u8,
+    },
+    /// An index pointer carries an unknown version
+    #[error("unsupported index pointer version {version:#04x}")]
+    UnsupportedVersion {
+        /// The version byte found in the pointer
+        version: u8,
+    },
+    /// The data length matches no pointer layout
+    #[error(
+        "Celestia pointer of {len} bytes, expected {COMMITMENT_POINTER_LEN} (commitment) or {INDEX_POINTER_LEN} (index)"
+    )]
+    InvalidLength {
+        /// The length of the data
+        len: usize,
+    },
+    /// The commitment field does not hold a 32 byte share commitment
+    #[error("Celestia commitment of {len} bytes, expected {COMMITMENT_LEN}")]
+    InvalidCommitmentLength {
+        /// The length of the commitment field
+        len: usize,
+    },
+    /// The pointer references a height outside the accepted bounds
+    #[error("pointer height {height} is outside the accepted range {min}..={max}")]
+    HeightOutOfRange {
+        /// The height referenced by the pointer
+        height: u64,
+        /// The lowest accepted height
+        min: u64,
+        /// The highest accepted height
+        max: u64,
+    },
+}
+
+/// A pointer to a blob on Celestia.
+///
+/// Both variants share the 3 byte derivation prefix: [ALTDA_DERIVATION_VERSION],
+/// [GENERIC_COMMITMENT_TYPE] and the DA layer byte, [CELESTIA_DA_LAYER_BYTE] by default:
+/// - [CelestiaPointer::Commitment] is `prefix ++ height (8 bytes LE) ++ commitment (32 bytes)`,
+/// - [CelestiaPointer::Index] is `prefix ++ version (1 byte) ++ height (8 bytes LE) ++ index
+///   (8 bytes LE)`, with the version set to [INDEX_POINTER_VERSION].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CelestiaPointer {
+    /// A blob identified by its height and share commitment.
+    Commitment {
+        /// The Celestia block height.
+        height: u64,
+        /// The blob's share commitment.
+        commitment: Commitment,
+    },
+    /// A blob identified by its height and the index of its first share.
+    Index {
+        /// The Celestia block height.
+        height: u64,
+        /// The index of the blob's first share in the extended data square.
+        index: u64,
+    },
+}
+
+impl CelestiaPointer {
+    /// Decodes a [CelestiaPointer] from the data fetched from the batcher inbox.
+    ///
+    /// Returns `None` if the data is not a Celestia pointer, see [CelestiaPointer::try_from] for
+    /// the reason.
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        Self::try_from(data).ok()
+    }
+
+    /// Decodes a [CelestiaPointer] whose prefix ends with `da_layer_byte` instead of the
+    /// [CELESTIA_DA_LAYER_BYTE].
+    ///
+    /// The derivation version and commitment type bytes are checked before the DA layer byte,
+    /// so a commitment of another type is never read as a Celestia pointer.
+    pub fn decode_with_da_layer_byte(
+        data: &[u8],
+        da_layer_byte: u8,
+    ) -> Result<Self, CelestiaPointerError> {
+        let len = data.len();
+        if len < PREFIX_LEN {
+            return Err(CelestiaPointerError::TooShort { len });
+        }
+        if data[0] != ALTDA_DERIVATION_VERSION {
+            return Err(CelestiaPointerError::NotAltDa { version: data[0] });
+        }
+        if data[1] != GENERIC_COMMITMENT_TYPE {
+            return Err(CelestiaPointerError::UnsupportedCommitmentType {
+                commitment_type: data[1],
+            });
+        }
+        if data[2] != da_layer_byte {
+            return Err(CelestiaPointerError::NotCelestia {
+                da_layer_byte: data[2],
+                expected: da_layer_byte,
+            });
+        }
+
+        match len {
+            COMMITMENT_POINTER_LEN => {
+                let height = read_u64_le(&data[3..11]);
+                let commitment = Self::commitment_from_bytes(&data[11..])?;
+                Ok(Self::Commitment { height, commitment })
+            }
+            INDEX_POINTER_LEN => {
+                let version = data[3];
+                if version != INDEX_POINTER_VERSION {
+                    return Err(CelestiaPointerError::UnsupportedVersion { version });
+                }
+                let height = read_u64_le(&data[4..12]);
+                let index = read_u64_le(&data[12..20]);
+                Ok(Self::Index { height, index })
+            }
+            _ => Err(CelestiaPointerError::InvalidLength { len }),
+        }
+    }
+
+    /// Builds the share commitment of a commitment pointer from its commitment field, rejecting
+    /// fields of any other length than 32 bytes.
+    pub fn commitment_from_bytes(bytes: &[u8]) -> Result<Commitment, CelestiaPointerError> {
+        let hash: [u8; COMMITMENT_LEN] = bytes
+            .try_into()
+            .map_err(|_| CelestiaPointerError::InvalidCommitmentLength { len: bytes.len() })?;
+        Ok(Commitment::new(hash))
+    }
+
+    /// Returns the Celestia block height the pointer references.
+    pub const fn height(&self) -> u64 {
+        match self {
+            Self::Commitment { height, .. } | Self::Index { height, .. } => *height,
+        }
+    }
+
+    /// Checks that the referenced height lies within `min..=max`, rejecting pointers decoded
+    /// from corrupted batcher data before any blob is fetched.
+    pub const fn check_height(&self, min: u64, max: u64) -> Result<(), CelestiaPointerError> {
+        let height = self.height();
+        if height < min || height > max {
+            return Err(CelestiaPointerError::HeightOutOfRange { height, min, max });
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<&[u8]> for CelestiaPointer {
+    type Error = CelestiaPointerError;
+
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        Self::decode_with_da_layer_byte(data, CELESTIA_DA_LAYER_BYTE)
+    }
+}
+
+/// Reads a little endian `u64` from an 8 byte slice.
+fn read_u64_le(bytes: &[u8]) -> u64 {
+    u64::from_le_bytes(bytes.try_into().expect("slice is 8 bytes"))
+}
@@ -0,0 +1,62 @@
+//! Comparison of a blob against the Celestia shares proven to hold it
+
+use celestia_types::nmt::Namespace;
+
+use crate::decoder::{NAMESPACE_SIZE, SEQUENCE_LEN_BYTES, SHARE_INFO_BYTES, SHARE_SIZE};
+
+/// Number of blob bytes held by the first share of a share version 0 blob
+const FIRST_SHARE_DATA: usize = SHARE_SIZE - NAMESPACE_SIZE - SHARE_INFO_BYTES - SEQUENCE_LEN_BYTES;
+
+/// Number of blob bytes held by each following share of a share version 0 blob
+const CONTINUATION_SHARE_DATA: usize = SHARE_SIZE - NAMESPACE_SIZE - SHARE_INFO_BYTES;
+
+/// Returns whether `shares` are exactly the share version 0 sparse shares `blob` is laid out into
+/// in `namespace`, the layout the blob commitment is computed over.
+///
+/// Each share must start with the namespace and an info byte of share version 0, flagging the
+/// start of the sequence on the first share only. The first share carries the big-endian length
+/// of the blob before its data, the last share is zero padded, and no share may be left over.
+/// Only bytes are compared, no hash is computed.
+pub fn verify_blob_shares<S: AsRef<[u8]>>(blob: &[u8], namespace: Namespace, shares: &[S]) -> bool {
+    let Ok(sequence_len) = u32::try_from(blob.len()) else {
+        return false;
+    };
+    let expected_shares = 1 + blob
+        .len()
+        .saturating_sub(FIRST_SHARE_DATA)
+        .div_ceil(CONTINUATION_SHARE_DATA);
+    if shares.len() != expected_shares {
+        return false;
+    }
+
+    let mut data = blob;
+    for (i, share) in shares.iter().enumerate() {
+        let share = share.as_ref();
+        let is_first = i == 0;
+
+        if share.len() != SHARE_SIZE
+            || share[..NAMESPACE_SIZE] != *namespace.as_bytes()
+            || share[NAMESPACE_SIZE] != u8::from(is_first)
+        {
+            return false;
+        }
+
+        let mut content = &share[NAMESPACE_SIZE + SHARE_INFO_BYTES..];
+        if is_first {
+            let (len, rest) = content.split_at(SEQUENCE_LEN_BYTES);
+            if len != sequence_len.to_be_bytes() {
+                return false;
+            }
+            content = rest;
+        }
+
+        let (chunk, rest) = data.split_at(content.len().min(data.len()));
+        let (held, padding) = content.split_at(chunk.len());
+        if held != chunk || padding.iter().any(|byte| *byte != 0) {
+            return false;
+        }
+        data = rest;
+    }
+
+    data.is_empty()
+}
@@ -1,94 +1,217 @@
 //! Celestia Data source
 
+use crate::decoder::{BlobDecoder, IdentityDecoder};
+use crate::pointer::CelestiaPointer;
 use crate::traits::CelestiaProvider;
 
-use alloc::vec::Vec;
+use alloc::{string::ToString, vec, vec::Vec};
 use alloy_primitives::Bytes;
-use celestia_types::Commitment;
 use kona_derive::{
-    errors::{BlobProviderError, PipelineError},
+    errors::{PipelineError, PipelineErrorKind},
     types::PipelineResult,
 };
+use tracing::Instrument;
+
+/// Loading state of a [CelestiaDASource]
+///
+/// The source stays open on the last pointer it loaded. Passing that pointer again continues
+/// with its pending blobs, while passing another pointer, e.g. one at a new height, drops them
+/// and loads the new pointer's blobs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SourceState {
+    /// Nothing is loaded, the next pointer is fetched from the provider.
+    #[default]
+    Closed,
+    /// A pointer was loaded and some of its blobs are still pending.
+    Loaded,
+    /// A pointer was loaded and all of its blobs, possibly none, were consumed. The source yields
+    /// EOF for that pointer until it is cleared or another pointer is passed.
+    Drained,
+}
 
 /// Data source for Celestia DA
 #[derive(Debug, Clone)]
-pub struct CelestiaDASource<C>
+pub struct CelestiaDASource<C, D = IdentityDecoder>
 where
     C: CelestiaProvider + Send,
+    D: BlobDecoder + Send,
 {
     /// Celestia connection
     pub celestia_fetcher: C,
+    /// Decoder applied to each fetched blob
+    decoder: D,
     /// Celestia Blobs
-    pub data: Vec<Bytes>,
-    /// Whether the source is open.
-    pub open: bool,
+    data: Vec<Bytes>,
+    /// The pointer the blobs were loaded from
+    pointer: Option<CelestiaPointer>,
+    /// The loading state of the source.
+    state: SourceState,
 }
 
 impl<C> CelestiaDASource<C>
 where
     C: CelestiaProvider + Send,
 {
-    /// Creates a new celestia source.
+    /// Creates a new celestia source returning the blob data unchanged.
     pub const fn new(celestia_fetcher: C) -> Self {
+        Self::with_decoder(celestia_fetcher, IdentityDecoder)
+    }
+}
+
+impl<C, D> CelestiaDASource<C, D>
+where
+    C: CelestiaProvider + Send + Sync,
+    D: BlobDecoder + Send,
+{
+    /// Creates a new celestia source decoding each fetched blob with `decoder`.
+    pub const fn with_decoder(celestia_fetcher: C, decoder: D) -> Self {
         Self {
             celestia_fetcher,
+            decoder,
             data: Vec::new(),
-            open: false,
+            pointer: None,
+            state: SourceState::Closed,
         }
     }
 
-    /// Fetches the next blob from the source.
-    pub async fn next(&mut self, height: u64, commitment: Commitment) -> PipelineResult<Bytes> {
-        self.load_blobs(height, commitment).await?;
+    /// Returns the loading state of the source.
+    pub const fn state(&self) -> SourceState {
+        self.state
+    }
+
+    /// Returns whether a pointer is loaded, i.e. the source is [SourceState::Loaded] or
+    /// [SourceState::Drained].
+    pub fn is_open(&self) -> bool {
+        self.state != SourceState::Closed
+    }
+
+    /// Returns the number of loaded blobs not yet returned by [Self::next].
+    pub fn pending_len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns the next pending blob of the loaded pointer, if any, without fetching anything.
+    pub fn next_pending(&mut self) -> Option<Bytes> {
+        self.next_data().ok()
+    }
+
+    /// Fetches the next blob of `pointer`, loading its blobs first unless it is the pointer the
+    /// source is open on.
+    pub async fn next(&mut self, pointer: CelestiaPointer) -> PipelineResult<Bytes> {
+        let span = debug_span!(target: "celestia-source", "next", height = pointer.height());
+
+        self.load_blobs(pointer).instrument(span.clone()).await?;
+
+        let _entered = span.enter();
         let next_data = match self.next_data() {
             Ok(d) => d,
             Err(e) => return e,
         };
 
-        // check decoding / encoding from lumina crates
-        Ok(Bytes::from(next_data))
+        Ok(next_data)
     }
 
-    /// Clears the source's data
+    /// Drops any pending blobs and closes the source, so the next pointer is fetched from the
+    /// provider.
     pub fn clear(&mut self) {
         self.data.clear();
-        self.open = false;
+        self.pointer = None;
+        self.state = SourceState::Closed;
     }
 
-    /// Loads blob data into the source if it is not open.
-    async fn load_blobs(
-        &mut self,
-        height: u64,
-        commitment: Commitment,
-    ) -> Result<(), BlobProviderError> {
-        if self.open {
-            return Ok(());
+    /// Loads the blobs of `pointer` into the source, unless it is already open on that pointer.
+    /// If it is open on another pointer, its pending blobs are dropped first.
+    ///
+    /// The blobs are queued in ascending order of their data, whatever order the provider returned
+    /// them in.
+    ///
+    /// No blobs means the provider proved the namespace holds no data at the pointer's height:
+    /// the source moves straight to [SourceState::Drained], so the pointer yields no frames. A
+    /// provider error leaves the source closed and is returned as classified by
+    /// [CelestiaProvider::is_temporary], so a temporary failure is retried with the pointer
+    /// fetched again.
+    async fn load_blobs(&mut self, pointer: CelestiaPointer) -> Result<(), PipelineErrorKind> {
+        if self.is_open() {
+            if self.pointer.as_ref() == Some(&pointer) {
+                return Ok(());
+            }
+            debug!(
+                target: "celestia-source",
+                "dropping {} pending blobs to load {:?}", self.data.len(), pointer
+            );
+            self.clear();
         }
 
-        info!(target: "celestia-source", "fetching blobs rom celestia fetcher");
-        let blob = self.celestia_fetcher.blob_get(height, commitment).await;
-        match blob {
-            Ok(blob) => {
-                self.open = true;
-                self.data.push(blob.clone());
-
-                info!(target: "celestia-source", "load_blobs {:?}", self.data);
-
-                Ok(())
+        debug!(
+            target: "celestia-source",
+            "fetching blobs for {:?} in namespace {:?}",
+            pointer,
+            self.celestia_fetcher.namespace()
+        );
+        let height = pointer.height();
+        let mut blobs = match pointer {
+            CelestiaPointer::Commitment { height, commitment } => {
+                self.celestia_fetcher.blobs_get(height, commitment).await
             }
-            Err(_) => {
-                self.open = true;
-                Ok(())
+            CelestiaPointer::Index { height, index } => self
+                .celestia_fetcher
+                .blob_get_at_index(height, index)
+                .await
+                .map(|blob| {
+                    if blob.is_empty() {
+                        Vec::new()
+                    } else {
+                        vec![(index, blob)]
+                    }
+                }),
+        }
+        .map_err(|err| {
+            if C::is_temporary(&err) {
+                PipelineError::Provider(err.to_string()).temp()
+            } else {
+                err.into()
             }
+        })?;
+
+        if blobs.is_empty() {
+            debug!(target: "celestia-source", "no blob data at height {}", height);
+        }
+
+        // Providers may return the blobs of a height in any order, e.g. the order their fetches
+        // completed in, so they are sorted by share index to be consumed in the order the
+        // sequencer laid them out in.
+        blobs.sort_by_key(|(index, _)| *index);
+
+        let mut data = Vec::with_capacity(blobs.len());
+        for (_, blob) in blobs {
+            debug!(target: "celestia-source", "loaded {} byte blob at height {}", blob.len(), height);
+            trace!(target: "celestia-source", "blob data {:?}", blob);
+
+            data.push(self.decoder.decode(blob)?);
         }
+
+        self.state = if data.is_empty() {
+            SourceState::Drained
+        } else {
+            SourceState::Loaded
+        };
+        self.data = data;
+        self.pointer = Some(pointer);
+
+        Ok(())
     }
 
     fn next_data(&mut self) -> Result<Bytes, PipelineResult<Bytes>> {
-        info!(target: "celestia-source", "celestia source data empty: {:?}", self.data.is_empty());
+        trace!(target: "celestia-source", "celestia source data empty: {:?}", self.data.is_empty());
 
         if self.data.is_empty() {
             return Err(Err(PipelineError::Eof.temp()));
         }
-        Ok(self.data.remove(0))
+
+        let next = self.data.remove(0);
+        if self.data.is_empty() {
+            self.state = SourceState::Drained;
+        }
+        Ok(next)
     }
 }
@@ -1,14 +1,60 @@
-use alloc::{boxed::Box, string::ToString};
+use alloc::{boxed::Box, string::ToString, vec, vec::Vec};
 use alloy_primitives::Bytes;
 use async_trait::async_trait;
-use celestia_types::Commitment;
+use celestia_types::{nmt::Namespace, Commitment};
 use core::fmt::Display;
 use kona_derive::errors::PipelineErrorKind;
 
+/// Default cap on the size of a blob accepted from a Celestia node: the data of a square of the
+/// maximum size of 128 by 128 shares of 512 bytes, which no blob can exceed
+pub const DEFAULT_MAX_BLOB_BYTES: usize = 128 * 128 * 512;
+
 /// Describes the functionality of the Celestia DA client needed to fetch a blob from calldata
 #[async_trait]
 pub trait CelestiaProvider {
     type Error: Display + ToString + Into<PipelineErrorKind>;
 
+    /// Returns whether retrying the request that failed with `error` may succeed, e.g. after a
+    /// network failure, as opposed to a blob that failed verification.
+    ///
+    /// Temporary errors are returned to the pipeline as [PipelineErrorKind::Temporary], others
+    /// through their [Into<PipelineErrorKind>] conversion. Defaults to `false`.
+    fn is_temporary(error: &Self::Error) -> bool {
+        let _ = error;
+        false
+    }
+
+    /// Returns the namespace the provider serves blobs from, e.g. for logging or to check the
+    /// namespace of served data against. Defaults to `None`, for providers serving any namespace.
+    fn namespace(&self) -> Option<Namespace> {
+        None
+    }
+
     async fn blob_get(&self, height: u64, commitment: Commitment) -> Result<Bytes, Self::Error>;
+
+    /// Fetches every blob with `commitment` at `height`, each along with the index of its first
+    /// share in the block, in any order: the source consumes them in share index order, the
+    /// order the sequencer laid them out in. No blobs means the namespace holds no data at the
+    /// height.
+    ///
+    /// Defaults to the single blob returned by [Self::blob_get] at index 0, for providers serving
+    /// one blob per commitment, as a single blob needs no ordering.
+    async fn blobs_get(
+        &self,
+        height: u64,
+        commitment: Commitment,
+    ) -> Result<Vec<(u64, Bytes)>, Self::Error> {
+        let blob = self.blob_get(height, commitment).await?;
+        Ok(if blob.is_empty() {
+            Vec::new()
+        } else {
+            vec![(0, blob)]
+        })
+    }
+
+    /// Fetches the blob whose first share is at `index` in the block at `height`.
+    ///
+    /// Providers that can only look blobs up by commitment must return an error rather than
+    /// panic, as index pointers are valid batcher data.
+    async fn blob_get_at_index(&self, height: u64, index: u64) -> Result<Bytes, Self::Error>;
 }
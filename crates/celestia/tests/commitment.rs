@@ -0,0 +1,88 @@
+//! Recomputation of share commitments against pairs computed independently from the share
+//! commitment rules: the NMT root of each subtree of shares, merkleized into the commitment.
+
+use alloy_primitives::hex;
+use celestia_types::{nmt::Namespace, AppVersion, Commitment};
+use hana_celestia::verify_blob_commitment;
+
+/// Commitment of `b"hana"`, which fits in a single share
+const SINGLE_SHARE_COMMITMENT: [u8; 32] =
+    hex!("012839df20fd65b391ddfe328d0c00cb32ecd32d98dba5d8a488a5d43635f90c");
+
+/// Commitment of [two_share_blob], spilling over a second share
+const TWO_SHARE_COMMITMENT: [u8; 32] =
+    hex!("dfd10e0fde7231bcada8d2fc029192326faeeb0450d31f87ad807cf0a8f748f6");
+
+fn namespace() -> Namespace {
+    Namespace::new_v0(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10]).unwrap()
+}
+
+/// A 600 byte blob, more than the 478 bytes of data the first share holds
+fn two_share_blob() -> Vec<u8> {
+    (0..600).map(|i| (i % 251) as u8).collect()
+}
+
+#[test]
+fn accepts_the_commitment_of_a_single_share_blob() {
+    assert!(verify_blob_commitment(
+        b"hana",
+        namespace(),
+        &Commitment::new(SINGLE_SHARE_COMMITMENT),
+        AppVersion::latest(),
+    ));
+}
+
+#[test]
+fn accepts_the_commitment_of_a_blob_spanning_shares() {
+    assert!(verify_blob_commitment(
+        &two_share_blob(),
+        namespace(),
+        &Commitment::new(TWO_SHARE_COMMITMENT),
+        AppVersion::latest(),
+    ));
+}
+
+#[test]
+fn accepts_the_commitment_under_the_first_app_version() {
+    assert!(verify_blob_commitment(
+        b"hana",
+        namespace(),
+        &Commitment::new(SINGLE_SHARE_COMMITMENT),
+        AppVersion::V1,
+    ));
+}
+
+#[test]
+fn rejects_a_tampered_blob() {
+    let mut blob = two_share_blob();
+    blob[500] ^= 1;
+
+    assert!(!verify_blob_commitment(
+        &blob,
+        namespace(),
+        &Commitment::new(TWO_SHARE_COMMITMENT),
+        AppVersion::latest(),
+    ));
+}
+
+#[test]
+fn rejects_a_truncated_blob() {
+    assert!(!verify_blob_commitment(
+        b"han",
+        namespace(),
+        &Commitment::new(SINGLE_SHARE_COMMITMENT),
+        AppVersion::latest(),
+    ));
+}
+
+#[test]
+fn rejects_another_namespace() {
+    let namespace = Namespace::new_v0(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 11]).unwrap();
+
+    assert!(!verify_blob_commitment(
+        b"hana",
+        namespace,
+        &Commitment::new(SINGLE_SHARE_COMMITMENT),
+        AppVersion::latest(),
+    ));
+}
@@ -0,0 +1,216 @@
+//! Routing of the batcher data of an L1 block through a [CelestiaDADataSource]: Celestia
+//! pointers are replaced by the blobs they reference, and any other data is passed through.
+
+use alloy_consensus::{Signed, TxEnvelope, TxLegacy};
+use alloy_primitives::{address, Address, Bytes, PrimitiveSignature, TxKind, B256};
+use async_trait::async_trait;
+use celestia_types::Commitment;
+use hana_celestia::{
+    CelestiaDADataSource, CelestiaDASource, CelestiaProvider, ALTDA_DERIVATION_VERSION,
+    CELESTIA_DA_LAYER_BYTE, GENERIC_COMMITMENT_TYPE,
+};
+use kona_derive::{
+    errors::{PipelineError, PipelineErrorKind},
+    sources::EthereumDataSource,
+    test_utils::{TestBlobProvider, TestChainProvider},
+    traits::DataAvailabilityProvider,
+};
+use kona_genesis::RollupConfig;
+use kona_protocol::BlockInfo;
+
+const BATCH_INBOX: Address = address!("ff00000000000000000000000000000000000042");
+
+/// A provider serving, for every commitment, the blobs named after it in reverse share order
+#[derive(Debug, Clone)]
+struct Blobs;
+
+#[async_trait]
+impl CelestiaProvider for Blobs {
+    type Error = PipelineErrorKind;
+
+    async fn blob_get(&self, _: u64, _: Commitment) -> Result<Bytes, Self::Error> {
+        unreachable!("the source fetches commitments through blobs_get")
+    }
+
+    async fn blob_get_at_index(&self, _: u64, _: u64) -> Result<Bytes, Self::Error> {
+        unreachable!("the tests only pass commitment pointers")
+    }
+
+    async fn blobs_get(
+        &self,
+        _: u64,
+        commitment: Commitment,
+    ) -> Result<Vec<(u64, Bytes)>, Self::Error> {
+        let name = commitment.hash()[0];
+        Ok(vec![
+            (2, Bytes::from(vec![name, 2])),
+            (1, Bytes::from(vec![name, 1])),
+        ])
+    }
+}
+
+/// A pointer to the blobs of commitment `[name; 32]`, tagged with `da_layer_byte`
+fn pointer(da_layer_byte: u8, name: u8) -> Vec<u8> {
+    let mut data = vec![
+        ALTDA_DERIVATION_VERSION,
+        GENERIC_COMMITMENT_TYPE,
+        da_layer_byte,
+    ];
+    data.extend_from_slice(&7u64.to_le_bytes());
+    data.extend_from_slice(&[name; 32]);
+    data
+}
+
+/// Frames posted straight to Ethereum, after the frames derivation version byte
+fn frames(byte: u8) -> Vec<u8> {
+    vec![0x00, byte, byte]
+}
+
+/// A transaction posting `data` to the batch inbox
+fn batcher_tx(data: Vec<u8>) -> TxEnvelope {
+    TxEnvelope::Legacy(Signed::new_unchecked(
+        TxLegacy {
+            to: TxKind::Call(BATCH_INBOX),
+            input: data.into(),
+            ..Default::default()
+        },
+        PrimitiveSignature::test_signature(),
+        B256::ZERO,
+    ))
+}
+
+/// A data source over an L1 block posting `data` in calldata, in order, along with the block and
+/// the address of the batcher that signed the transactions
+fn data_source(
+    data: Vec<Vec<u8>>,
+) -> (
+    CelestiaDADataSource<TestChainProvider, TestBlobProvider, Blobs>,
+    BlockInfo,
+    Address,
+) {
+    let block = BlockInfo {
+        number: 1,
+        hash: B256::repeat_byte(1),
+        ..Default::default()
+    };
+    let txs: Vec<_> = data.into_iter().map(batcher_tx).collect();
+    let batcher = txs[0].recover_signer().unwrap();
+
+    let mut chain_provider = TestChainProvider::default();
+    chain_provider.insert_block_with_transactions(block.number, block, txs);
+
+    // Without Ecotone, the batcher data is read from calldata
+    let cfg = RollupConfig {
+        batch_inbox_address: BATCH_INBOX,
+        ..Default::default()
+    };
+    let ethereum_source =
+        EthereumDataSource::new_from_parts(chain_provider, TestBlobProvider::default(), &cfg);
+
+    (
+        CelestiaDADataSource::new(ethereum_source, CelestiaDASource::new(Blobs)),
+        block,
+        batcher,
+    )
+}
+
+/// Reads every item of `block` from `source`, until the end of its data.
+async fn drain(
+    source: &mut CelestiaDADataSource<TestChainProvider, TestBlobProvider, Blobs>,
+    block: &BlockInfo,
+    batcher: Address,
+) -> Vec<Bytes> {
+    let mut items = Vec::new();
+    loop {
+        match source.next(block, batcher).await {
+            Ok(item) => items.push(item),
+            Err(err) => {
+                assert_eq!(err, PipelineError::Eof.temp());
+                return items;
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn routes_mixed_batcher_data_in_transaction_order() {
+    let (mut source, block, batcher) = data_source(vec![
+        frames(1),
+        pointer(CELESTIA_DA_LAYER_BYTE, 0xa),
+        frames(2),
+        pointer(CELESTIA_DA_LAYER_BYTE, 0xb),
+    ]);
+
+    assert_eq!(
+        drain(&mut source, &block, batcher).await,
+        vec![
+            Bytes::from(frames(1)),
+            Bytes::from(vec![0xa, 1]),
+            Bytes::from(vec![0xa, 2]),
+            Bytes::from(frames(2)),
+            Bytes::from(vec![0xb, 1]),
+            Bytes::from(vec![0xb, 2]),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn returns_the_pending_blobs_of_a_pointer_before_reading_more_data() {
+    let (mut source, block, batcher) =
+        data_source(vec![pointer(CELESTIA_DA_LAYER_BYTE, 0xa), frames(1)]);
+
+    assert_eq!(
+        source.next(&block, batcher).await,
+        Ok(Bytes::from(vec![0xa, 1]))
+    );
+    assert_eq!(source.celestia_source.pending_len(), 1);
+
+    assert_eq!(
+        source.next(&block, batcher).await,
+        Ok(Bytes::from(vec![0xa, 2]))
+    );
+    assert_eq!(source.celestia_source.pending_len(), 0);
+
+    assert_eq!(
+        source.next(&block, batcher).await,
+        Ok(Bytes::from(frames(1)))
+    );
+}
+
+/// A malformed pointer is skipped with a temporary error, after which the block's other data is
+/// still read.
+#[tokio::test]
+async fn skips_malformed_pointers() {
+    let mut truncated = pointer(CELESTIA_DA_LAYER_BYTE, 0xa);
+    truncated.pop();
+    let (mut source, block, batcher) = data_source(vec![truncated, frames(1)]);
+
+    assert_eq!(
+        source.next(&block, batcher).await,
+        Err(PipelineError::EndOfSource.temp())
+    );
+    assert_eq!(
+        source.next(&block, batcher).await,
+        Ok(Bytes::from(frames(1)))
+    );
+}
+
+/// With a configured DA layer byte, only pointers tagged with it are fetched from Celestia.
+#[tokio::test]
+async fn routes_pointers_by_the_configured_da_layer_byte() {
+    const CUSTOM_DA_LAYER_BYTE: u8 = 0xce;
+    let default = pointer(CELESTIA_DA_LAYER_BYTE, 0xa);
+    let (source, block, batcher) =
+        data_source(vec![default.clone(), pointer(CUSTOM_DA_LAYER_BYTE, 0xb)]);
+    let mut source = source.with_da_layer_byte(CUSTOM_DA_LAYER_BYTE);
+    assert_eq!(source.da_layer_byte(), CUSTOM_DA_LAYER_BYTE);
+
+    assert_eq!(
+        drain(&mut source, &block, batcher).await,
+        vec![
+            Bytes::from(default),
+            Bytes::from(vec![0xb, 1]),
+            Bytes::from(vec![0xb, 2]),
+        ]
+    );
+}
@@ -0,0 +1,158 @@
+//! A [FallbackCelestiaProvider] over two scripted providers: which requests reach the secondary,
+//! and which errors are returned as is.
+
+use alloy_primitives::Bytes;
+use async_trait::async_trait;
+use celestia_types::{nmt::Namespace, Commitment};
+use hana_celestia::{CelestiaProvider, FallbackCelestiaProvider, MockCall, MockCelestiaProvider};
+use kona_derive::errors::{PipelineError, PipelineErrorKind};
+
+/// A [MockCelestiaProvider] classifying its temporary pipeline errors as temporary, like an
+/// online provider classifies network failures
+#[derive(Debug, Clone, Default)]
+struct Scripted {
+    mock: MockCelestiaProvider,
+    namespace: Option<Namespace>,
+}
+
+#[async_trait]
+impl CelestiaProvider for Scripted {
+    type Error = PipelineErrorKind;
+
+    fn is_temporary(error: &Self::Error) -> bool {
+        matches!(error, PipelineErrorKind::Temporary(_))
+    }
+
+    fn namespace(&self) -> Option<Namespace> {
+        self.namespace
+    }
+
+    async fn blob_get(&self, height: u64, commitment: Commitment) -> Result<Bytes, Self::Error> {
+        self.mock.blob_get(height, commitment).await
+    }
+
+    async fn blob_get_at_index(&self, height: u64, index: u64) -> Result<Bytes, Self::Error> {
+        self.mock.blob_get_at_index(height, index).await
+    }
+}
+
+fn providers() -> (
+    Scripted,
+    Scripted,
+    FallbackCelestiaProvider<Scripted, Scripted>,
+) {
+    let primary = Scripted::default();
+    let secondary = Scripted::default();
+    let fallback = FallbackCelestiaProvider::new(primary.clone(), secondary.clone());
+    (primary, secondary, fallback)
+}
+
+fn timeout() -> PipelineErrorKind {
+    PipelineError::Provider("request timed out".into()).temp()
+}
+
+/// An error the primary would return for a blob failing verification
+fn invalid() -> PipelineErrorKind {
+    PipelineError::Provider("blob failed verification".into()).crit()
+}
+
+fn commitment() -> Commitment {
+    Commitment::new([1; 32])
+}
+
+#[tokio::test]
+async fn falls_back_on_temporary_errors() {
+    let (primary, secondary, fallback) = providers();
+    primary.mock.push_error(timeout());
+    secondary.mock.push_blob(Bytes::from_static(b"a"));
+
+    assert_eq!(
+        fallback.blob_get(1, commitment()).await,
+        Ok(Bytes::from_static(b"a"))
+    );
+
+    let call = MockCall::BlobGet {
+        height: 1,
+        commitment: commitment(),
+    };
+    assert_eq!(primary.mock.calls(), vec![call.clone()]);
+    assert_eq!(secondary.mock.calls(), vec![call]);
+}
+
+#[tokio::test]
+async fn falls_back_for_every_kind_of_request() {
+    let (primary, secondary, fallback) = providers();
+    primary.mock.push_error(timeout());
+    primary.mock.push_error(timeout());
+    secondary.mock.push_blob(Bytes::from_static(b"a"));
+    secondary.mock.push_blob(Bytes::from_static(b"b"));
+
+    assert_eq!(
+        fallback.blobs_get(1, commitment()).await,
+        Ok(vec![(0, Bytes::from_static(b"a"))])
+    );
+    assert_eq!(
+        fallback.blob_get_at_index(2, 5).await,
+        Ok(Bytes::from_static(b"b"))
+    );
+    assert_eq!(primary.mock.calls(), secondary.mock.calls());
+}
+
+#[tokio::test]
+async fn does_not_fall_back_once_the_primary_answers() {
+    let (primary, secondary, fallback) = providers();
+    primary.mock.push_blob(Bytes::from_static(b"a"));
+    primary.mock.push_blob(Bytes::new());
+
+    assert_eq!(
+        fallback.blob_get(1, commitment()).await,
+        Ok(Bytes::from_static(b"a"))
+    );
+    // A proven namespace absence is an answer too
+    assert_eq!(fallback.blob_get_at_index(1, 5).await, Ok(Bytes::new()));
+    assert!(secondary.mock.calls().is_empty());
+}
+
+/// Errors other than temporary ones would be the same from the secondary.
+#[tokio::test]
+async fn returns_other_errors_of_the_primary() {
+    let (primary, secondary, fallback) = providers();
+    primary.mock.push_error(invalid());
+    secondary.mock.push_blob(Bytes::from_static(b"a"));
+
+    assert_eq!(fallback.blob_get(1, commitment()).await, Err(invalid()));
+    assert!(secondary.mock.calls().is_empty());
+    assert_eq!(secondary.mock.pending_results(), 1);
+}
+
+#[tokio::test]
+async fn returns_the_error_of_the_secondary_when_both_fail() {
+    let (primary, secondary, fallback) = providers();
+    primary.mock.push_error(timeout());
+    secondary
+        .mock
+        .push_error(PipelineError::Provider("connection refused".into()).temp());
+
+    let err = fallback.blob_get(1, commitment()).await.unwrap_err();
+    assert_eq!(
+        err,
+        PipelineError::Provider("connection refused".into()).temp()
+    );
+    assert!(FallbackCelestiaProvider::<Scripted, Scripted>::is_temporary(&err));
+}
+
+#[test]
+fn serves_the_namespace_of_the_primary_first() {
+    let namespace = Namespace::new_v0(b"hana").unwrap();
+    let other = Namespace::new_v0(b"other").unwrap();
+    let scripted = |namespace| Scripted {
+        namespace,
+        ..Default::default()
+    };
+
+    let fallback = FallbackCelestiaProvider::new(scripted(Some(namespace)), scripted(Some(other)));
+    assert_eq!(fallback.namespace(), Some(namespace));
+
+    let fallback = FallbackCelestiaProvider::new(scripted(None), scripted(Some(other)));
+    assert_eq!(fallback.namespace(), Some(other));
+}
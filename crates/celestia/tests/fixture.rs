@@ -0,0 +1,55 @@
+//! The [FixtureCelestiaProvider], serving canned blobs by commitment or by share index.
+
+use alloy_primitives::Bytes;
+use celestia_types::Commitment;
+use hana_celestia::{CelestiaProvider, FixtureCelestiaProvider};
+use kona_derive::errors::PipelineErrorKind;
+
+fn commitment() -> Commitment {
+    Commitment::new([1; 32])
+}
+
+#[tokio::test]
+async fn serves_blobs_by_commitment_and_by_index() {
+    let mut provider = FixtureCelestiaProvider::new();
+    provider.insert(1, commitment(), Bytes::from_static(b"a"));
+    provider.insert_at_index(1, 5, Bytes::from_static(b"b"));
+    assert_eq!(provider.len(), 2);
+
+    assert_eq!(
+        provider.blob_get(1, commitment()).await,
+        Ok(Bytes::from_static(b"a"))
+    );
+    assert_eq!(
+        provider.blob_get_at_index(1, 5).await,
+        Ok(Bytes::from_static(b"b"))
+    );
+}
+
+/// Index pointers are valid batcher data, so a missing index fixture fails the request instead
+/// of panicking.
+#[tokio::test]
+async fn fails_critically_without_a_fixture() {
+    let mut provider = FixtureCelestiaProvider::new();
+    provider.insert(1, commitment(), Bytes::from_static(b"a"));
+
+    let err = provider.blob_get_at_index(1, 0).await.unwrap_err();
+    assert!(matches!(err, PipelineErrorKind::Critical(_)), "{err:?}");
+
+    let err = provider.blob_get(2, commitment()).await.unwrap_err();
+    assert!(matches!(err, PipelineErrorKind::Critical(_)), "{err:?}");
+}
+
+#[test]
+fn loads_commitment_and_index_fixtures_from_a_directory() {
+    let dir = std::env::temp_dir().join(format!("hana-fixtures-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join(format!("1-{}.bin", "01".repeat(32))), b"a").unwrap();
+    std::fs::write(dir.join("1-index-5.bin"), b"b").unwrap();
+    std::fs::write(dir.join("README"), b"ignored").unwrap();
+
+    let provider = FixtureCelestiaProvider::load_dir(&dir, None);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(provider.unwrap().len(), 2);
+}
@@ -0,0 +1,93 @@
+//! The scripted [MockCelestiaProvider]: its queue of results and its log of requests.
+
+use alloy_primitives::Bytes;
+use celestia_types::Commitment;
+use hana_celestia::{CelestiaProvider, MockCall, MockCelestiaProvider};
+use kona_derive::errors::{PipelineError, PipelineErrorKind};
+
+#[tokio::test]
+async fn answers_with_the_queued_results_in_order() {
+    let provider = MockCelestiaProvider::new();
+    provider.push_blob(Bytes::from_static(b"a"));
+    provider.push_error(PipelineError::Eof.temp());
+    provider.push_blob(Bytes::from_static(b"b"));
+    assert_eq!(provider.pending_results(), 3);
+
+    let commitment = Commitment::new([1; 32]);
+    assert_eq!(
+        provider.blob_get(1, commitment).await,
+        Ok(Bytes::from_static(b"a"))
+    );
+    assert_eq!(
+        provider.blob_get_at_index(2, 5).await,
+        Err(PipelineError::Eof.temp())
+    );
+    assert_eq!(
+        provider.blob_get(3, commitment).await,
+        Ok(Bytes::from_static(b"b"))
+    );
+    assert_eq!(provider.pending_results(), 0);
+
+    assert_eq!(
+        provider.calls(),
+        vec![
+            MockCall::BlobGet {
+                height: 1,
+                commitment,
+            },
+            MockCall::BlobGetAtIndex {
+                height: 2,
+                index: 5,
+            },
+            MockCall::BlobGet {
+                height: 3,
+                commitment,
+            },
+        ]
+    );
+}
+
+#[tokio::test]
+async fn fails_critically_without_a_queued_result() {
+    let provider = MockCelestiaProvider::new();
+
+    let err = provider.blob_get_at_index(1, 0).await.unwrap_err();
+    assert!(matches!(err, PipelineErrorKind::Critical(_)), "{err:?}");
+    assert_eq!(
+        provider.calls(),
+        vec![MockCall::BlobGetAtIndex {
+            height: 1,
+            index: 0,
+        }]
+    );
+}
+
+#[tokio::test]
+async fn clones_share_the_queue_and_the_call_log() {
+    let provider = MockCelestiaProvider::new();
+    let clone = provider.clone();
+    provider.push_blob(Bytes::from_static(b"a"));
+
+    assert_eq!(
+        clone.blob_get(1, Commitment::new([1; 32])).await,
+        Ok(Bytes::from_static(b"a"))
+    );
+    assert_eq!(provider.pending_results(), 0);
+    assert_eq!(provider.calls(), clone.calls());
+}
+
+/// An empty blob stands for a proven namespace absence, which fetching every blob of a
+/// commitment reports as no blobs.
+#[tokio::test]
+async fn empty_blobs_are_no_blobs() {
+    let provider = MockCelestiaProvider::new();
+    provider.push_blob(Bytes::new());
+    provider.push_blob(Bytes::from_static(b"a"));
+
+    let commitment = Commitment::new([1; 32]);
+    assert_eq!(provider.blobs_get(1, commitment).await, Ok(vec![]));
+    assert_eq!(
+        provider.blobs_get(1, commitment).await,
+        Ok(vec![(0, Bytes::from_static(b"a"))])
+    );
+}
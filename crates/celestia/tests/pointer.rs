@@ -0,0 +1,152 @@
+//! Decoding of Celestia pointers from batcher data laid out by hand after the alt-DA commitment
+//! layout.
+
+use celestia_types::Commitment;
+use hana_celestia::{
+    CelestiaPointer, CelestiaPointerError, ALTDA_DERIVATION_VERSION, CELESTIA_DA_LAYER_BYTE,
+    GENERIC_COMMITMENT_TYPE, INDEX_POINTER_VERSION,
+};
+
+const HEIGHT: u64 = 4_206_669;
+
+/// The derivation prefix ending with `da_layer_byte`
+fn prefix(da_layer_byte: u8) -> Vec<u8> {
+    vec![
+        ALTDA_DERIVATION_VERSION,
+        GENERIC_COMMITMENT_TYPE,
+        da_layer_byte,
+    ]
+}
+
+/// A pointer to the blob of `commitment` at [HEIGHT]
+fn commitment_pointer(da_layer_byte: u8, commitment: &[u8]) -> Vec<u8> {
+    let mut data = prefix(da_layer_byte);
+    data.extend_from_slice(&HEIGHT.to_le_bytes());
+    data.extend_from_slice(commitment);
+    data
+}
+
+/// A pointer to the blob starting at share `index` at [HEIGHT]
+fn index_pointer(da_layer_byte: u8, index: u64) -> Vec<u8> {
+    let mut data = prefix(da_layer_byte);
+    data.push(INDEX_POINTER_VERSION);
+    data.extend_from_slice(&HEIGHT.to_le_bytes());
+    data.extend_from_slice(&index.to_le_bytes());
+    data
+}
+
+#[test]
+fn decodes_both_pointer_layouts() {
+    assert_eq!(
+        CelestiaPointer::decode(&commitment_pointer(CELESTIA_DA_LAYER_BYTE, &[7; 32])),
+        Some(CelestiaPointer::Commitment {
+            height: HEIGHT,
+            commitment: Commitment::new([7; 32]),
+        })
+    );
+    assert_eq!(
+        CelestiaPointer::decode(&index_pointer(CELESTIA_DA_LAYER_BYTE, 5)),
+        Some(CelestiaPointer::Index {
+            height: HEIGHT,
+            index: 5,
+        })
+    );
+}
+
+#[test]
+fn rejects_commitment_fields_of_the_wrong_length() {
+    for len in [0, 1, 31, 33, 64] {
+        assert_eq!(
+            CelestiaPointer::commitment_from_bytes(&vec![7; len]),
+            Err(CelestiaPointerError::InvalidCommitmentLength { len }),
+        );
+    }
+
+    assert_eq!(
+        CelestiaPointer::commitment_from_bytes(&[7; 32]),
+        Ok(Commitment::new([7; 32]))
+    );
+}
+
+/// A commitment field one byte short or long changes the pointer's length, which then matches
+/// neither layout.
+#[test]
+fn rejects_commitment_pointers_of_the_wrong_length() {
+    for commitment in [&[7; 31][..], &[7; 33][..]] {
+        let data = commitment_pointer(CELESTIA_DA_LAYER_BYTE, commitment);
+
+        assert_eq!(
+            CelestiaPointer::try_from(data.as_slice()),
+            Err(CelestiaPointerError::InvalidLength { len: data.len() }),
+        );
+    }
+}
+
+/// A DA layer byte configured by a rollup in place of the [CELESTIA_DA_LAYER_BYTE]
+const CUSTOM_DA_LAYER_BYTE: u8 = 0xce;
+
+#[test]
+fn decodes_pointers_tagged_with_a_configured_da_layer_byte() {
+    assert_eq!(
+        CelestiaPointer::decode_with_da_layer_byte(
+            &commitment_pointer(CUSTOM_DA_LAYER_BYTE, &[7; 32]),
+            CUSTOM_DA_LAYER_BYTE,
+        ),
+        Ok(CelestiaPointer::Commitment {
+            height: HEIGHT,
+            commitment: Commitment::new([7; 32]),
+        })
+    );
+    assert_eq!(
+        CelestiaPointer::decode_with_da_layer_byte(
+            &index_pointer(CUSTOM_DA_LAYER_BYTE, 5),
+            CUSTOM_DA_LAYER_BYTE,
+        ),
+        Ok(CelestiaPointer::Index {
+            height: HEIGHT,
+            index: 5,
+        })
+    );
+}
+
+#[test]
+fn rejects_pointers_tagged_with_another_da_layer_byte() {
+    let custom = index_pointer(CUSTOM_DA_LAYER_BYTE, 5);
+    assert_eq!(CelestiaPointer::decode(&custom), None);
+    assert_eq!(
+        CelestiaPointer::try_from(custom.as_slice()),
+        Err(CelestiaPointerError::NotCelestia {
+            da_layer_byte: CUSTOM_DA_LAYER_BYTE,
+            expected: CELESTIA_DA_LAYER_BYTE,
+        })
+    );
+
+    let default = index_pointer(CELESTIA_DA_LAYER_BYTE, 5);
+    assert_eq!(
+        CelestiaPointer::decode_with_da_layer_byte(&default, CUSTOM_DA_LAYER_BYTE),
+        Err(CelestiaPointerError::NotCelestia {
+            da_layer_byte: CELESTIA_DA_LAYER_BYTE,
+            expected: CUSTOM_DA_LAYER_BYTE,
+        })
+    );
+}
+
+/// The DA layer byte is only read once the prefix marks a generic alt-DA commitment.
+#[test]
+fn checks_the_prefix_before_the_da_layer_byte() {
+    let mut frames = index_pointer(CUSTOM_DA_LAYER_BYTE, 5);
+    frames[0] = 0x00;
+    assert_eq!(
+        CelestiaPointer::decode_with_da_layer_byte(&frames, CUSTOM_DA_LAYER_BYTE),
+        Err(CelestiaPointerError::NotAltDa { version: 0x00 })
+    );
+
+    let mut keccak = index_pointer(CUSTOM_DA_LAYER_BYTE, 5);
+    keccak[1] = 0x00;
+    assert_eq!(
+        CelestiaPointer::decode_with_da_layer_byte(&keccak, CUSTOM_DA_LAYER_BYTE),
+        Err(CelestiaPointerError::UnsupportedCommitmentType {
+            commitment_type: 0x00
+        })
+    );
+}
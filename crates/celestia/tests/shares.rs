@@ -0,0 +1,130 @@
+//! Comparison of blobs against the shares proven for them, laid out by hand following the share
+//! version 0 sparse share format.
+
+use celestia_types::nmt::Namespace;
+use hana_celestia::verify_blob_shares;
+
+/// Size in bytes of a Celestia share
+const SHARE_SIZE: usize = 512;
+
+fn namespace() -> Namespace {
+    Namespace::new_v0(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10]).unwrap()
+}
+
+/// A 1000 byte blob, spanning three shares
+fn blob() -> Vec<u8> {
+    (0..1000).map(|i| (i % 251) as u8).collect()
+}
+
+/// Lays `blob` out into share version 0 sparse shares of `namespace`.
+fn shares(blob: &[u8], namespace: Namespace) -> Vec<Vec<u8>> {
+    let mut shares = Vec::new();
+    let mut data = blob;
+    loop {
+        let mut share = namespace.as_bytes().to_vec();
+        if shares.is_empty() {
+            share.push(1);
+            share.extend((blob.len() as u32).to_be_bytes());
+        } else {
+            share.push(0);
+        }
+
+        let (chunk, rest) = data.split_at((SHARE_SIZE - share.len()).min(data.len()));
+        share.extend(chunk);
+        share.resize(SHARE_SIZE, 0);
+        shares.push(share);
+
+        data = rest;
+        if data.is_empty() {
+            return shares;
+        }
+    }
+}
+
+#[test]
+fn accepts_the_shares_of_the_blob() {
+    for blob in [b"hana".to_vec(), vec![0x42; 478], vec![0x42; 479], blob()] {
+        let shares = shares(&blob, namespace());
+
+        assert!(
+            verify_blob_shares(&blob, namespace(), &shares),
+            "blob of {} bytes",
+            blob.len()
+        );
+    }
+}
+
+#[test]
+fn splits_the_blob_at_the_share_boundaries() {
+    assert_eq!(shares(&[0x42; 478], namespace()).len(), 1);
+    assert_eq!(shares(&[0x42; 479], namespace()).len(), 2);
+    assert_eq!(shares(&blob(), namespace()).len(), 3);
+}
+
+#[test]
+fn rejects_a_tampered_share() {
+    let blob = blob();
+    let mut shares = shares(&blob, namespace());
+    shares[1][100] ^= 1;
+
+    assert!(!verify_blob_shares(&blob, namespace(), &shares));
+}
+
+#[test]
+fn rejects_a_tampered_blob() {
+    let mut blob = blob();
+    let shares = shares(&blob, namespace());
+    blob[700] ^= 1;
+
+    assert!(!verify_blob_shares(&blob, namespace(), &shares));
+}
+
+#[test]
+fn rejects_missing_or_extra_shares() {
+    let blob = blob();
+    let mut shares = shares(&blob, namespace());
+
+    assert!(!verify_blob_shares(&blob, namespace(), &shares[..2]));
+
+    shares.push(shares[2].clone());
+    assert!(!verify_blob_shares(&blob, namespace(), &shares));
+}
+
+#[test]
+fn rejects_another_namespace() {
+    let blob = blob();
+    let other = Namespace::new_v0(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 11]).unwrap();
+
+    assert!(!verify_blob_shares(
+        &blob,
+        namespace(),
+        &shares(&blob, other)
+    ));
+}
+
+#[test]
+fn rejects_a_wrong_sequence_length() {
+    let blob = blob();
+    let mut shares = shares(&blob, namespace());
+    shares[0][33] ^= 1;
+
+    assert!(!verify_blob_shares(&blob, namespace(), &shares));
+}
+
+#[test]
+fn rejects_a_sequence_start_on_a_continuation_share() {
+    let blob = blob();
+    let mut shares = shares(&blob, namespace());
+    shares[1][29] = 1;
+
+    assert!(!verify_blob_shares(&blob, namespace(), &shares));
+}
+
+#[test]
+fn rejects_data_in_the_padding() {
+    let blob = b"hana".to_vec();
+    let mut shares = shares(&blob, namespace());
+    shares[0][SHARE_SIZE - 1] = 1;
+
+    assert!(!verify_blob_shares(&blob, namespace(), &shares));
+}
@@ -0,0 +1,234 @@
+//! The loading states a [CelestiaDASource] moves through as it is passed pointers, driven by
+//! providers scripted by the tests.
+
+use alloy_primitives::Bytes;
+use async_trait::async_trait;
+use celestia_types::Commitment;
+use hana_celestia::{
+    CelestiaDASource, CelestiaPointer, CelestiaProvider, MockCall, MockCelestiaProvider,
+    SourceState,
+};
+use kona_derive::errors::{PipelineError, PipelineErrorKind};
+
+/// A pointer to the blob with commitment `[byte; 32]` at `height`
+fn pointer(height: u64, byte: u8) -> CelestiaPointer {
+    CelestiaPointer::Commitment {
+        height,
+        commitment: Commitment::new([byte; 32]),
+    }
+}
+
+/// A provider serving the same blobs, in the given order and with their share index, for every
+/// commitment
+#[derive(Debug, Clone)]
+struct Blobs(Vec<(u64, Bytes)>);
+
+impl Blobs {
+    /// Blobs laid out in the given order
+    fn new(blobs: &[&'static [u8]]) -> Self {
+        Self(
+            blobs
+                .iter()
+                .zip(0..)
+                .map(|(&blob, index)| (index, Bytes::from_static(blob)))
+                .collect(),
+        )
+    }
+
+    /// Blobs laid out at the given share indices
+    fn at(blobs: &[(u64, &'static [u8])]) -> Self {
+        Self(
+            blobs
+                .iter()
+                .map(|&(index, blob)| (index, Bytes::from_static(blob)))
+                .collect(),
+        )
+    }
+}
+
+#[async_trait]
+impl CelestiaProvider for Blobs {
+    type Error = PipelineErrorKind;
+
+    async fn blob_get(&self, _: u64, _: Commitment) -> Result<Bytes, Self::Error> {
+        unreachable!("the source fetches commitments through blobs_get")
+    }
+
+    async fn blob_get_at_index(&self, _: u64, _: u64) -> Result<Bytes, Self::Error> {
+        unreachable!("the tests only pass commitment pointers")
+    }
+
+    async fn blobs_get(&self, _: u64, _: Commitment) -> Result<Vec<(u64, Bytes)>, Self::Error> {
+        Ok(self.0.clone())
+    }
+}
+
+#[test]
+fn starts_closed() {
+    let mut source = CelestiaDASource::new(MockCelestiaProvider::new());
+
+    assert_eq!(source.state(), SourceState::Closed);
+    assert!(!source.is_open());
+    assert_eq!(source.pending_len(), 0);
+    assert_eq!(source.next_pending(), None);
+}
+
+#[tokio::test]
+async fn moves_from_loaded_to_drained_as_blobs_are_consumed() {
+    let mut source = CelestiaDASource::new(Blobs::new(&[b"a", b"b"]));
+
+    assert_eq!(
+        source.next(pointer(1, 1)).await,
+        Ok(Bytes::from_static(b"a"))
+    );
+    assert_eq!(source.state(), SourceState::Loaded);
+    assert!(source.is_open());
+    assert_eq!(source.pending_len(), 1);
+
+    assert_eq!(
+        source.next(pointer(1, 1)).await,
+        Ok(Bytes::from_static(b"b"))
+    );
+    assert_eq!(source.state(), SourceState::Drained);
+    assert!(source.is_open());
+    assert_eq!(source.pending_len(), 0);
+}
+
+#[tokio::test]
+async fn yields_eof_for_a_drained_pointer_until_cleared() {
+    let provider = MockCelestiaProvider::new();
+    let mut source = CelestiaDASource::new(provider.clone());
+    provider.push_blob(Bytes::from_static(b"a"));
+    provider.push_blob(Bytes::from_static(b"b"));
+
+    assert_eq!(
+        source.next(pointer(1, 1)).await,
+        Ok(Bytes::from_static(b"a"))
+    );
+    assert_eq!(source.state(), SourceState::Drained);
+
+    // The drained pointer is not fetched again
+    assert_eq!(
+        source.next(pointer(1, 1)).await,
+        Err(PipelineError::Eof.temp())
+    );
+    assert_eq!(provider.calls().len(), 1);
+
+    source.clear();
+    assert_eq!(source.state(), SourceState::Closed);
+    assert!(!source.is_open());
+
+    assert_eq!(
+        source.next(pointer(1, 1)).await,
+        Ok(Bytes::from_static(b"b"))
+    );
+    assert_eq!(provider.calls().len(), 2);
+}
+
+#[tokio::test]
+async fn pointers_without_blobs_are_drained_at_once() {
+    let provider = MockCelestiaProvider::new();
+    let mut source = CelestiaDASource::new(provider.clone());
+    provider.push_blob(Bytes::new());
+
+    assert_eq!(
+        source.next(pointer(1, 1)).await,
+        Err(PipelineError::Eof.temp())
+    );
+    assert_eq!(source.state(), SourceState::Drained);
+    assert_eq!(
+        provider.calls(),
+        vec![MockCall::BlobGet {
+            height: 1,
+            commitment: Commitment::new([1; 32]),
+        }]
+    );
+}
+
+#[tokio::test]
+async fn another_pointer_drops_the_pending_blobs() {
+    let mut source = CelestiaDASource::new(Blobs::new(&[b"a", b"b", b"c"]));
+
+    assert_eq!(
+        source.next(pointer(1, 1)).await,
+        Ok(Bytes::from_static(b"a"))
+    );
+    assert_eq!(source.pending_len(), 2);
+
+    assert_eq!(
+        source.next(pointer(2, 1)).await,
+        Ok(Bytes::from_static(b"a"))
+    );
+    assert_eq!(source.state(), SourceState::Loaded);
+    assert_eq!(source.pending_len(), 2);
+}
+
+#[tokio::test]
+async fn pending_blobs_are_returned_without_fetching() {
+    let mut source = CelestiaDASource::new(Blobs::new(&[b"a", b"b", b"c"]));
+    source.next(pointer(1, 1)).await.unwrap();
+
+    assert_eq!(source.next_pending(), Some(Bytes::from_static(b"b")));
+    assert_eq!(source.next_pending(), Some(Bytes::from_static(b"c")));
+    assert_eq!(source.state(), SourceState::Drained);
+    assert_eq!(source.next_pending(), None);
+}
+
+#[tokio::test]
+async fn provider_errors_leave_the_source_closed() {
+    let provider = MockCelestiaProvider::new();
+    let mut source = CelestiaDASource::new(provider.clone());
+    provider.push_error(PipelineError::Provider("node unreachable".into()).temp());
+    provider.push_blob(Bytes::from_static(b"a"));
+
+    assert_eq!(
+        source.next(pointer(1, 1)).await,
+        Err(PipelineError::Provider("node unreachable".into()).temp())
+    );
+    assert_eq!(source.state(), SourceState::Closed);
+
+    // The pointer is fetched again on retry
+    assert_eq!(
+        source.next(pointer(1, 1)).await,
+        Ok(Bytes::from_static(b"a"))
+    );
+    assert_eq!(provider.calls().len(), 2);
+}
+
+/// Drains the source of the blobs of `pointer`.
+async fn drain(source: &mut CelestiaDASource<Blobs>, pointer: CelestiaPointer) -> Vec<Bytes> {
+    let mut blobs = vec![source.next(pointer.clone()).await.unwrap()];
+    while let Some(blob) = source.next_pending() {
+        blobs.push(blob);
+    }
+    blobs
+}
+
+/// Blobs are consumed in the order of their first share, not of their data.
+#[tokio::test]
+async fn consumes_blobs_in_share_index_order_whatever_order_they_were_fetched_in() {
+    let blobs: [(u64, &'static [u8]); 3] = [(4, b"\x02"), (9, b"\x01\xff"), (12, b"\x01")];
+    let permutations = [
+        [0, 1, 2],
+        [0, 2, 1],
+        [1, 0, 2],
+        [1, 2, 0],
+        [2, 0, 1],
+        [2, 1, 0],
+    ];
+
+    for permutation in permutations {
+        let fetched: Vec<_> = permutation.iter().map(|&i| blobs[i]).collect();
+        let mut source = CelestiaDASource::new(Blobs::at(&fetched));
+
+        assert_eq!(
+            drain(&mut source, pointer(1, 1)).await,
+            vec![
+                Bytes::from_static(b"\x02"),
+                Bytes::from_static(b"\x01\xff"),
+                Bytes::from_static(b"\x01"),
+            ],
+            "blobs fetched in order {permutation:?}"
+        );
+    }
+}
@@ -0,0 +1,112 @@
+//! Benchmarks of the verification of the Celestia payloads served by the host, which the client
+//! runs for every blob inside the zkVM, as a whole and broken out by step.
+//!
+//! The payloads are read from `fixtures/`, see `tests/support` for their naming and encodings.
+
+use celestia_types::AppVersion;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use hana_blobstream::blobstream::{
+    verify_data_root_tuple, verify_share_proof, DATA_COMMITMENTS_SLOT,
+};
+use hana_oracle::{
+    payload::{CelestiaPayload, OraclePayload},
+    verify::{verify_namespace_absence, verify_oracle_payload, verify_oracle_payload_at_index},
+};
+
+#[path = "../tests/support/mod.rs"]
+mod support;
+
+use support::{blob_namespace, fixtures, Fixture, FixtureHint};
+
+/// Benchmarks the full verification of a blob payload, then each of its steps.
+fn bench_blob(c: &mut Criterion, fixture: &Fixture, payload: &OraclePayload) {
+    let height = fixture.height;
+    let proof = &payload.proof;
+    let inclusion_proof = proof.inclusion_proof();
+    let skip_storage_proof = !inclusion_proof.has_storage_proof();
+    let namespace = blob_namespace(payload);
+
+    let mut group = c.benchmark_group(format!("verify_oracle_payload/{}", fixture.name));
+
+    group.bench_function("full", |b| match fixture.hint {
+        FixtureHint::Commitment(commitment) => b.iter(|| {
+            verify_oracle_payload(
+                black_box(payload),
+                height,
+                namespace,
+                &commitment,
+                AppVersion::latest(),
+                DATA_COMMITMENTS_SLOT,
+                skip_storage_proof,
+            )
+            .unwrap()
+        }),
+        FixtureHint::Index(index) => b.iter(|| {
+            verify_oracle_payload_at_index(
+                black_box(payload),
+                height,
+                namespace,
+                index,
+                DATA_COMMITMENTS_SLOT,
+                skip_storage_proof,
+            )
+            .unwrap()
+        }),
+    });
+
+    group.bench_function("share_proof", |b| {
+        b.iter(|| verify_share_proof(black_box(&proof.share_proof), proof.data_root).unwrap())
+    });
+
+    group.bench_function("data_root_tuple", |b| {
+        b.iter(|| {
+            verify_data_root_tuple(
+                black_box(&proof.data_root_tuple_proof),
+                height,
+                &proof.data_root,
+                proof.data_commitment,
+            )
+            .unwrap()
+        })
+    });
+
+    if !skip_storage_proof {
+        group.bench_function("storage_proof", |b| {
+            b.iter(|| {
+                black_box(&inclusion_proof)
+                    .verify_storage_proof(DATA_COMMITMENTS_SLOT)
+                    .unwrap()
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_verification(c: &mut Criterion) {
+    for fixture in &fixtures() {
+        match &fixture.payload {
+            CelestiaPayload::Blob(payload) => bench_blob(c, fixture, payload),
+            CelestiaPayload::Blobs(_) => {
+                panic!("fixture {} is not named after a blobs hint", fixture.name)
+            }
+            CelestiaPayload::NamespaceAbsent(payload) => {
+                let skip_storage_proof = !payload.inclusion_proof.has_storage_proof();
+                c.bench_function(&format!("verify_namespace_absence/{}", fixture.name), |b| {
+                    b.iter(|| {
+                        verify_namespace_absence(
+                            black_box(payload),
+                            fixture.height,
+                            DATA_COMMITMENTS_SLOT,
+                            skip_storage_proof,
+                        )
+                        .unwrap()
+                    })
+                });
+            }
+        }
+    }
+}
+
+criterion_group!(benches, bench_verification);
+criterion_main!(benches);
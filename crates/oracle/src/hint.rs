@@ -1,12 +1,18 @@
 use core::{fmt, str::FromStr};
 
-use alloc::string::String;
+use alloc::{string::String, vec::Vec};
+use alloy_primitives::keccak256;
+use celestia_types::Commitment;
+use hana_celestia::CelestiaPointer;
+use kona_preimage::{PreimageKey, PreimageKeyType};
 use kona_proof::{errors::HintParsingError, HintType};
 // Add your HintWrapper
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum HintWrapper {
     Standard(HintType),
     CelestiaDA,
+    CelestiaDAIndex,
+    CelestiaDABlobs,
 }
 
 impl FromStr for HintWrapper {
@@ -21,6 +27,8 @@ impl FromStr for HintWrapper {
         // Check for our custom types
         match s {
             "celestia-da" => Ok(HintWrapper::CelestiaDA),
+            "celestia-da-index" => Ok(HintWrapper::CelestiaDAIndex),
+            "celestia-da-blobs" => Ok(HintWrapper::CelestiaDABlobs),
             _ => Err(HintParsingError(String::from("unknown hint"))),
         }
     }
@@ -32,6 +40,199 @@ impl fmt::Display for HintWrapper {
         match self {
             HintWrapper::Standard(hint) => write!(f, "{hint}"),
             HintWrapper::CelestiaDA => write!(f, "celestia-da"),
+            HintWrapper::CelestiaDAIndex => write!(f, "celestia-da-index"),
+            HintWrapper::CelestiaDABlobs => write!(f, "celestia-da-blobs"),
         }
     }
 }
+
+/// Size in bytes of the data of a `celestia-da` or `celestia-da-blobs` hint: the height and the
+/// commitment
+const CELESTIA_DA_HINT_LEN: usize = 40;
+
+/// Size in bytes of the data of a `celestia-da-index` hint: the height and the share index
+const CELESTIA_DA_INDEX_HINT_LEN: usize = 16;
+
+/// Errors returned when decoding the data of a Celestia hint
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum CelestiaHintError {
+    /// The hint is not a Celestia hint
+    #[error("{0} is not a Celestia hint")]
+    NotCelestia(HintWrapper),
+    /// The hint data ends before one of its fields
+    #[error(
+        "{hint} hint data of {actual} bytes is missing its {field}, expected {expected} bytes"
+    )]
+    MissingField {
+        /// The hint type
+        hint: HintWrapper,
+        /// The field that could not be read
+        field: &'static str,
+        /// The expected length of the hint data
+        expected: usize,
+        /// The length of the hint data
+        actual: usize,
+    },
+    /// The hint data holds bytes past its last field
+    #[error("{hint} hint data of {actual} bytes has trailing bytes, expected {expected} bytes")]
+    TrailingBytes {
+        /// The hint type
+        hint: HintWrapper,
+        /// The expected length of the hint data
+        expected: usize,
+        /// The length of the hint data
+        actual: usize,
+    },
+}
+
+/// The data of a Celestia hint, with the height encoded as little-endian
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CelestiaHint {
+    /// A `celestia-da` hint, for the blob with the given commitment
+    Commitment {
+        /// The Celestia height of the blob
+        height: u64,
+        /// The commitment of the blob
+        commitment: Commitment,
+    },
+    /// A `celestia-da-index` hint, for the blob whose first share is at the given index
+    Index {
+        /// The Celestia height of the blob
+        height: u64,
+        /// The index of the blob's first share in the block
+        index: u64,
+    },
+    /// A `celestia-da-blobs` hint, for every blob with the given commitment
+    Blobs {
+        /// The Celestia height of the blobs
+        height: u64,
+        /// The commitment of the blobs
+        commitment: Commitment,
+    },
+}
+
+impl CelestiaHint {
+    /// Decodes the data of a hint of the given type.
+    pub fn decode(hint: HintWrapper, data: &[u8]) -> Result<Self, CelestiaHintError> {
+        let expected = match hint {
+            HintWrapper::CelestiaDA | HintWrapper::CelestiaDABlobs => CELESTIA_DA_HINT_LEN,
+            HintWrapper::CelestiaDAIndex => CELESTIA_DA_INDEX_HINT_LEN,
+            HintWrapper::Standard(_) => return Err(CelestiaHintError::NotCelestia(hint)),
+        };
+
+        let field = |name: &'static str, range: core::ops::Range<usize>| {
+            data.get(range).ok_or(CelestiaHintError::MissingField {
+                hint,
+                field: name,
+                expected,
+                actual: data.len(),
+            })
+        };
+
+        let height = u64::from_le_bytes(field("height", 0..8)?.try_into().expect("8 bytes"));
+        let commitment = || {
+            field("commitment", 8..40)
+                .map(|commitment| Commitment::new(commitment.try_into().expect("32 bytes")))
+        };
+        let decoded = match hint {
+            HintWrapper::CelestiaDA => Self::Commitment {
+                height,
+                commitment: commitment()?,
+            },
+            HintWrapper::CelestiaDABlobs => Self::Blobs {
+                height,
+                commitment: commitment()?,
+            },
+            _ => Self::Index {
+                height,
+                index: u64::from_le_bytes(field("index", 8..16)?.try_into().expect("8 bytes")),
+            },
+        };
+
+        if data.len() > expected {
+            return Err(CelestiaHintError::TrailingBytes {
+                hint,
+                expected,
+                actual: data.len(),
+            });
+        }
+
+        Ok(decoded)
+    }
+
+    /// Returns the hint type.
+    pub const fn hint_type(&self) -> HintWrapper {
+        match self {
+            Self::Commitment { .. } => HintWrapper::CelestiaDA,
+            Self::Index { .. } => HintWrapper::CelestiaDAIndex,
+            Self::Blobs { .. } => HintWrapper::CelestiaDABlobs,
+        }
+    }
+
+    /// Returns the Celestia height of the blob.
+    pub const fn height(&self) -> u64 {
+        match self {
+            Self::Commitment { height, .. }
+            | Self::Index { height, .. }
+            | Self::Blobs { height, .. } => *height,
+        }
+    }
+
+    /// Encodes the hint data.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut encoded = Vec::with_capacity(CELESTIA_DA_HINT_LEN);
+        encoded.extend_from_slice(&self.height().to_le_bytes());
+
+        match self {
+            Self::Commitment { commitment, .. } | Self::Blobs { commitment, .. } => {
+                encoded.extend_from_slice(commitment.hash())
+            }
+            Self::Index { index, .. } => encoded.extend_from_slice(&index.to_le_bytes()),
+        }
+
+        encoded
+    }
+
+    /// Returns the key of the preimage the host stores the hint's payload behind.
+    ///
+    /// A `celestia-da-blobs` hint carries the same data as the `celestia-da` hint for the same
+    /// commitment, so its data is prefixed with [CELESTIA_DA_BLOBS_KEY_PREFIX] before hashing.
+    pub fn preimage_key(&self) -> PreimageKey {
+        match self {
+            Self::Blobs { .. } => {
+                hint_preimage_key(&[CELESTIA_DA_BLOBS_KEY_PREFIX, &self.encode()[..]].concat())
+            }
+            _ => hint_preimage_key(&self.encode()),
+        }
+    }
+}
+
+/// The hint the client sends for the blobs of a pointer: every blob with a commitment, or the
+/// blob at an index.
+impl From<CelestiaPointer> for CelestiaHint {
+    fn from(pointer: CelestiaPointer) -> Self {
+        match pointer {
+            CelestiaPointer::Commitment { height, commitment } => {
+                Self::Blobs { height, commitment }
+            }
+            CelestiaPointer::Index { height, index } => Self::Index { height, index },
+        }
+    }
+}
+
+/// Prefix of the hashed data of a `celestia-da-blobs` hint, keeping its payload apart from the
+/// payload of the `celestia-da` hint with the same data
+pub const CELESTIA_DA_BLOBS_KEY_PREFIX: &[u8] = b"celestia-da-blobs";
+
+/// Returns the key of the preimage the host stores the payload of a Celestia hint behind: the
+/// keccak256 hash of the hint data, as a global generic key. See [CelestiaHint::preimage_key] for
+/// the key of a `celestia-da-blobs` hint.
+pub fn hint_preimage_key(hint_data: &[u8]) -> PreimageKey {
+    PreimageKey::new(*keccak256(hint_data), PreimageKeyType::GlobalGeneric)
+}
+
+/// Returns the key of the preimage the host stores the payload of the blob with `commitment` at
+/// `height` behind.
+pub fn celestia_preimage_key(height: u64, commitment: Commitment) -> PreimageKey {
+    CelestiaHint::Commitment { height, commitment }.preimage_key()
+}
@@ -12,3 +12,7 @@ pub mod pipeline;
 pub mod provider;
 
 pub mod payload;
+
+pub mod profiling;
+
+pub mod verify;
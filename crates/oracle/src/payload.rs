@@ -1,27 +1,108 @@
-use alloc::{boxed::Box, vec::Vec};
-use alloy_primitives::{Bytes, FixedBytes, B256, U256};
-use celestia_types::{hash::Hash, MerkleProof, ShareProof};
+use alloc::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use alloy_primitives::{keccak256, Bytes, FixedBytes, B256, U256};
+use celestia_types::{
+    hash::Hash, nmt::Namespace, row_namespace_data::NamespaceData, DataAvailabilityHeader,
+    MerkleProof, ShareProof,
+};
+use hana_blobstream::blobstream::{BlobstreamProof, DataRootInclusionProof};
 use serde::{Deserialize, Serialize};
 
+/// Version of the binary encoding of [CelestiaPayload], written as its first byte. Bumped on any
+/// change to the encoding of the payloads or their proofs, which bincode gives no way to detect.
+///
+/// Starts at 2, so that unversioned payloads, whose first byte is the variant index 0 or 1, are
+/// rejected.
+pub const PAYLOAD_FORMAT_VERSION: u8 = 2;
+
+/// A serialized [CelestiaPayload] in a format version other than [PAYLOAD_FORMAT_VERSION]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("unsupported Celestia payload format version {found:?}, expected {PAYLOAD_FORMAT_VERSION}")]
+pub struct UnsupportedPayloadVersion {
+    /// The version byte of the payload, `None` if it is empty
+    pub found: Option<u8>,
+}
+
+/// The preimage served by the host for a Celestia DA hint
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CelestiaPayload {
+    /// The requested blob along with the proofs of its inclusion
+    Blob(OraclePayload),
+    /// The proof that the rollup's namespace holds no data at the requested height
+    NamespaceAbsent(NamespaceAbsencePayload),
+    /// Every blob with the requested commitment, along with the proofs of its inclusion at its
+    /// share index
+    Blobs(Vec<IndexedOraclePayload>),
+}
+
+impl CelestiaPayload {
+    /// Serialize the enum to bytes using serde with a binary format, prefixed with the
+    /// [PAYLOAD_FORMAT_VERSION]
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Box<dyn core::error::Error>> {
+        let mut bytes = Vec::from([PAYLOAD_FORMAT_VERSION]);
+        bincode::serialize_into(&mut bytes, self)?;
+        Ok(bytes)
+    }
+
+    /// Deserialize from bytes back into the enum, rejecting any format version but
+    /// [PAYLOAD_FORMAT_VERSION]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Box<dyn core::error::Error>> {
+        match bytes.split_first() {
+            Some((&PAYLOAD_FORMAT_VERSION, payload)) => Ok(bincode::deserialize(payload)?),
+            version => Err(Box::new(UnsupportedPayloadVersion {
+                found: version.map(|(version, _)| *version),
+            })),
+        }
+    }
+
+    /// Serialize the enum to JSON, with the byte fields hex encoded and the Celestia proofs in
+    /// the format served by celestia-node
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> Result<String, Box<dyn core::error::Error>> {
+        let json = serde_json::to_string(self)?;
+        Ok(json)
+    }
+
+    /// Deserialize from JSON back into the enum
+    #[cfg(feature = "json")]
+    pub fn from_json(json: &str) -> Result<Self, Box<dyn core::error::Error>> {
+        let deserialized = serde_json::from_str(json)?;
+        Ok(deserialized)
+    }
+
+    /// Returns the keccak256 hash of the payload's binary encoding, identifying its content.
+    pub fn content_hash(&self) -> B256 {
+        keccak256(self.to_bytes().expect("Celestia payloads always serialize"))
+    }
+
+    /// Extracts the blob data from a serialized [CelestiaPayload]. A namespace absence payload
+    /// yields an empty blob, and a payload of several blobs an error.
+    ///
+    /// Matches the payload decoder expected by `hana_celestia::FixtureCelestiaProvider`.
+    pub fn blob_from_bytes(bytes: &[u8]) -> Result<Bytes, String> {
+        match Self::from_bytes(bytes).map_err(|err| err.to_string())? {
+            Self::Blob(payload) => Ok(payload.blob),
+            Self::NamespaceAbsent(_) => Ok(Bytes::new()),
+            Self::Blobs(mut payloads) if payloads.len() == 1 => Ok(payloads.remove(0).payload.blob),
+            Self::Blobs(payloads) => Err(format!("payload holds {} blobs", payloads.len())),
+        }
+    }
+}
+
 /// A structure containing a Celestia Blob and its corresponding proofs
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// The binary encoding is the blob followed by the encoding of its [BlobstreamProof]. It carries
+/// no format version, which only the enclosing [CelestiaPayload] does.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct OraclePayload {
     /// The Celestia blob data
     pub blob: Bytes,
-    /// The data root to verify the proof against
-    pub data_root: Hash,
-    /// The data commitment from Blobstream to verify against
-    pub data_commitment: FixedBytes<32>,
-    /// The Data Root Tuple Inclusion proof
-    pub data_root_tuple_proof: MerkleProof,
-    /// The proof for the blob's inclusion
-    pub share_proof: ShareProof,
-    /// The proof_nonce in blobstream
-    pub proof_nonce: U256,
-    /// The storage root to verify against
-    pub storage_root: B256,
-    /// The storage proof for the state_dataCommitments mapping slot in Blobstream
-    pub storage_proof: Vec<Bytes>,
+    /// The proofs of the blob's inclusion in a block committed to by Blobstream
+    pub proof: BlobstreamProof,
 }
 
 impl OraclePayload {
@@ -34,18 +115,35 @@ impl OraclePayload {
         share_proof: ShareProof,
         proof_nonce: U256,
         storage_root: B256,
-        storage_proof: Vec<Bytes>,
+        storage_proof: Option<Vec<Bytes>>,
     ) -> Self {
-        Self {
+        Self::from_proof(
             blob,
-            data_root,
-            data_commitment,
-            data_root_tuple_proof,
-            share_proof,
-            proof_nonce,
-            storage_root,
-            storage_proof,
-        }
+            BlobstreamProof::new(
+                data_root,
+                data_commitment,
+                data_root_tuple_proof,
+                share_proof,
+                proof_nonce,
+                storage_root,
+                storage_proof,
+            ),
+        )
+    }
+
+    /// Create a new OraclePayload from the blob data and its [BlobstreamProof]
+    pub fn from_proof(blob: Bytes, proof: BlobstreamProof) -> Self {
+        Self { blob, proof }
+    }
+
+    /// Splits the payload into the blob data and its [BlobstreamProof]
+    pub fn into_parts(self) -> (Bytes, BlobstreamProof) {
+        (self.blob, self.proof)
+    }
+
+    /// Returns the keccak256 hash of the payload's binary encoding, identifying its content.
+    pub fn content_hash(&self) -> B256 {
+        keccak256(self.to_bytes().expect("oracle payloads always serialize"))
     }
 
     /// Serialize the struct to bytes using serde with a binary format
@@ -59,4 +157,66 @@ impl OraclePayload {
         let deserialized = bincode::deserialize(bytes)?;
         Ok(deserialized)
     }
+
+    /// Serialize the struct to JSON, with the byte fields hex encoded and the Celestia proofs in
+    /// the format served by celestia-node
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> Result<String, Box<dyn core::error::Error>> {
+        let json = serde_json::to_string(self)?;
+        Ok(json)
+    }
+
+    /// Deserialize from JSON back into the struct
+    #[cfg(feature = "json")]
+    pub fn from_json(json: &str) -> Result<Self, Box<dyn core::error::Error>> {
+        let deserialized = serde_json::from_str(json)?;
+        Ok(deserialized)
+    }
+}
+
+/// An [OraclePayload] along with the index of the blob's first share in its block, which its share
+/// proof is checked to start at
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexedOraclePayload {
+    /// The index of the blob's first share in the block
+    pub index: u64,
+    /// The blob and the proofs of its inclusion
+    pub payload: OraclePayload,
+}
+
+impl IndexedOraclePayload {
+    /// Create a new IndexedOraclePayload instance
+    pub fn new(index: u64, payload: OraclePayload) -> Self {
+        Self { index, payload }
+    }
+}
+
+/// A structure proving that a namespace holds no data in a Celestia block
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NamespaceAbsencePayload {
+    /// The namespace proven to hold no data
+    pub namespace: Namespace,
+    /// The data availability header of the block, which hashes to its data root
+    pub dah: DataAvailabilityHeader,
+    /// The namespace absence proofs for the rows whose namespace range covers the namespace
+    pub namespace_data: NamespaceData,
+    /// The proof that the block's data root was committed to by Blobstream
+    pub inclusion_proof: DataRootInclusionProof,
+}
+
+impl NamespaceAbsencePayload {
+    /// Create a new NamespaceAbsencePayload instance
+    pub fn new(
+        namespace: Namespace,
+        dah: DataAvailabilityHeader,
+        namespace_data: NamespaceData,
+        inclusion_proof: DataRootInclusionProof,
+    ) -> Self {
+        Self {
+            namespace,
+            dah,
+            namespace_data,
+            inclusion_proof,
+        }
+    }
 }
@@ -72,24 +72,50 @@ where
     B: BlobProvider + Send + Sync + Debug + Clone,
     C: CelestiaProvider + Send + Sync + Debug + Clone,
 {
-    /// Constructs a new oracle-backed derivation pipeline.
+    /// Constructs a new oracle-backed derivation pipeline, fetching the batcher data of pointers
+    /// tagged with `da_layer_byte` from Celestia.
+    #[allow(clippy::too_many_arguments)]
     pub async fn new(
         cfg: Arc<RollupConfig>,
         sync_start: Arc<RwLock<PipelineCursor>>,
         caching_oracle: Arc<O>,
         blob_provider: B,
         chain_provider: OracleL1ChainProvider<O>,
-        mut l2_chain_provider: OracleL2ChainProvider<O>,
+        l2_chain_provider: OracleL2ChainProvider<O>,
         celestia_provider: C,
+        da_layer_byte: u8,
+    ) -> PipelineResult<Self> {
+        let dap = EthereumDataSource::new_from_parts(chain_provider.clone(), blob_provider, &cfg);
+        let celestia_data_source = CelestiaDASource::new(celestia_provider);
+        let dap =
+            CelestiaDADataSource::new(dap, celestia_data_source).with_da_layer_byte(da_layer_byte);
+
+        Self::new_with_dap(
+            cfg,
+            sync_start,
+            caching_oracle,
+            chain_provider,
+            l2_chain_provider,
+            dap,
+        )
+        .await
+    }
+
+    /// Constructs a new oracle-backed derivation pipeline reading from an already assembled data
+    /// availability provider, e.g. one backed by fixtures in tests.
+    pub async fn new_with_dap(
+        cfg: Arc<RollupConfig>,
+        sync_start: Arc<RwLock<PipelineCursor>>,
+        caching_oracle: Arc<O>,
+        chain_provider: OracleL1ChainProvider<O>,
+        mut l2_chain_provider: OracleL2ChainProvider<O>,
+        dap: OracleDataProvider<O, B, C>,
     ) -> PipelineResult<Self> {
         let attributes = StatefulAttributesBuilder::new(
             cfg.clone(),
             l2_chain_provider.clone(),
             chain_provider.clone(),
         );
-        let dap = EthereumDataSource::new_from_parts(chain_provider.clone(), blob_provider, &cfg);
-        let celestia_data_source = CelestiaDASource::new(celestia_provider);
-        let dap = CelestiaDADataSource::new(dap, celestia_data_source);
 
         let mut pipeline = PipelineBuilder::new()
             .rollup_config(cfg.clone())
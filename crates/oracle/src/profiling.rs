@@ -0,0 +1,67 @@
+//! Instrumentation of the steps verifying a served payload, to find which one dominates the cost
+//! of proving, e.g. in zkVM cycles.
+//!
+//! Steps are only reported under the `profiling` feature, once a sink is set with
+//! `set_profiling_sink`. Otherwise the instrumentation compiles to nothing.
+
+/// A step of the verification of a served payload
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VerificationStep {
+    /// Verifying a blob against its whole Blobstream proof, from its commitment to the Blobstream
+    /// storage proof
+    BlobProof,
+    /// Verifying the proofs of a namespace's absence from the rows of a block
+    NamespaceAbsence,
+    /// Verifying the data root tuple against the Blobstream data commitment
+    DataRootTuple,
+    /// Verifying the data commitment against the Blobstream storage proof
+    StorageProof,
+}
+
+impl VerificationStep {
+    /// Returns the name of the step, e.g. to label a zkVM cycle tracker.
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::BlobProof => "blob-proof",
+            Self::NamespaceAbsence => "namespace-absence",
+            Self::DataRootTuple => "data-root-tuple",
+            Self::StorageProof => "storage-proof",
+        }
+    }
+}
+
+/// Receives the boundaries of each verification step, and measures them with whatever clock or
+/// cycle counter the environment provides
+pub trait ProfilingSink: Send + Sync {
+    /// Called right before `step` runs.
+    fn step_started(&self, step: VerificationStep);
+
+    /// Called right after `step` ran, whether it succeeded or not.
+    fn step_finished(&self, step: VerificationStep);
+}
+
+#[cfg(feature = "profiling")]
+static SINK: spin::Once<&'static dyn ProfilingSink> = spin::Once::new();
+
+/// Sets the sink verification steps are reported to. Only the first call takes effect.
+#[cfg(feature = "profiling")]
+pub fn set_profiling_sink(sink: &'static dyn ProfilingSink) {
+    SINK.call_once(|| sink);
+}
+
+/// Runs `f` as the verification `step`, reporting its boundaries to the sink if one is set.
+#[inline(always)]
+pub(crate) fn profile<T>(step: VerificationStep, f: impl FnOnce() -> T) -> T {
+    #[cfg(feature = "profiling")]
+    if let Some(sink) = SINK.get() {
+        sink.step_started(step);
+        let output = f();
+        sink.step_finished(step);
+        return output;
+    }
+
+    #[cfg(not(feature = "profiling"))]
+    let _ = step;
+
+    f()
+}
@@ -1,31 +1,95 @@
 use alloc::boxed::Box;
-use alloc::string::ToString;
+use alloc::format;
 use alloc::sync::Arc;
 use alloc::vec::Vec;
-use alloy_primitives::{keccak256, Bytes};
+use alloy_primitives::{hex, Bytes, FixedBytes, U256};
 use async_trait::async_trait;
-use celestia_types::Commitment;
-use hana_blobstream::blobstream::{encode_data_root_tuple, verify_data_commitment_storage};
-use hana_celestia::CelestiaProvider;
+use celestia_types::{hash::Hash, nmt::Namespace, AppVersion, Commitment};
+use hana_blobstream::blobstream::DATA_COMMITMENTS_SLOT;
+use hana_celestia::{CelestiaProvider, DEFAULT_MAX_BLOB_BYTES};
 use kona_preimage::errors::PreimageOracleError;
-use kona_preimage::{CommsClient, PreimageKey, PreimageKeyType};
+use kona_preimage::CommsClient;
 use kona_proof::errors::OracleProviderError;
 use kona_proof::Hint;
-use tracing::info;
+use tracing::{field, info_span, warn, Instrument, Span};
 
-use crate::hint::HintWrapper;
-use crate::payload::OraclePayload;
+use crate::hint::{CelestiaHint, HintWrapper};
+use crate::payload::{CelestiaPayload, OraclePayload};
+use crate::verify::{
+    verify_namespace_absence, verify_oracle_blobs, verify_oracle_payload,
+    verify_oracle_payload_at_index,
+};
+
+/// A blob served by the host, along with the Blobstream data commitment it was verified against
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifiedBlob {
+    /// The blob data, empty if the namespace was proven to hold no data at the height
+    pub blob: Bytes,
+    /// The data root of the Celestia block holding the blob
+    pub data_root: Hash,
+    /// The Blobstream data commitment covering the block
+    pub data_commitment: FixedBytes<32>,
+    /// The Blobstream proof nonce of the data commitment
+    pub proof_nonce: U256,
+}
 
 /// An oracle-backed da storage.
 #[derive(Debug, Clone)]
 pub struct OracleCelestiaProvider<T: CommsClient> {
     oracle: Arc<T>,
+    max_blob_bytes: usize,
+    skip_storage_proof: bool,
+    namespace: Namespace,
+    app_version: AppVersion,
+    data_commitments_slot: u32,
 }
 
 impl<T: CommsClient + Clone> OracleCelestiaProvider<T> {
-    /// Constructs a new `OracleBlobProvider`.
-    pub fn new(oracle: Arc<T>) -> Self {
-        Self { oracle }
+    /// Constructs a new `OracleBlobProvider` for the rollup posting to `namespace`. Namespace
+    /// absence proofs served by the host for another namespace are rejected.
+    pub fn new(oracle: Arc<T>, namespace: Namespace) -> Self {
+        Self {
+            oracle,
+            max_blob_bytes: DEFAULT_MAX_BLOB_BYTES,
+            skip_storage_proof: false,
+            namespace,
+            app_version: AppVersion::latest(),
+            data_commitments_slot: DATA_COMMITMENTS_SLOT,
+        }
+    }
+
+    /// Sets the Celestia app version whose share layout the blob commitments are recomputed
+    /// under, defaults to [AppVersion::latest].
+    pub fn with_app_version(mut self, app_version: AppVersion) -> Self {
+        self.app_version = app_version;
+        self
+    }
+
+    /// Sets the storage slot of the Blobstream `state_dataCommitments` mapping the storage proofs
+    /// served by the host are checked against, defaults to [DATA_COMMITMENTS_SLOT]. Must match
+    /// the slot the host was configured with.
+    pub fn with_data_commitments_slot(mut self, data_commitments_slot: u32) -> Self {
+        self.data_commitments_slot = data_commitments_slot;
+        self
+    }
+
+    /// Sets the size above which blobs served by the host are rejected, defaults to
+    /// [DEFAULT_MAX_BLOB_BYTES].
+    pub fn with_max_blob_bytes(mut self, max_blob_bytes: usize) -> Self {
+        self.max_blob_bytes = max_blob_bytes;
+        self
+    }
+
+    /// Sets whether to trust the Blobstream data commitment served by the host instead of
+    /// checking it against the Blobstream storage proof. Only meant for trusted setups.
+    pub fn with_skip_storage_proof(mut self, skip_storage_proof: bool) -> Self {
+        if skip_storage_proof {
+            warn!(
+                "Blobstream storage proof verification is DISABLED, data commitments served by the host are trusted as is"
+            );
+        }
+        self.skip_storage_proof = skip_storage_proof;
+        self
     }
 }
 
@@ -33,52 +97,305 @@ impl<T: CommsClient + Clone> OracleCelestiaProvider<T> {
 impl<T: CommsClient + Sync + Send> CelestiaProvider for OracleCelestiaProvider<T> {
     type Error = OracleProviderError;
 
+    /// The host serves the same preimage for a hint every time, and a payload failing
+    /// verification fails again, so no oracle error is worth a retry.
+    fn is_temporary(_: &Self::Error) -> bool {
+        false
+    }
+
+    fn namespace(&self) -> Option<Namespace> {
+        Some(self.namespace)
+    }
+
     async fn blob_get(&self, height: u64, commitment: Commitment) -> Result<Bytes, Self::Error> {
-        let mut encoded = Vec::new();
-        encoded.extend_from_slice(&height.to_le_bytes());
-        encoded.extend_from_slice(commitment.hash());
+        self.blob_get_verified(height, commitment)
+            .await
+            .map(|verified| verified.blob)
+    }
 
-        // Perform Inclusion checks against the data root
+    async fn blobs_get(
+        &self,
+        height: u64,
+        commitment: Commitment,
+    ) -> Result<Vec<(u64, Bytes)>, Self::Error> {
+        let span = info_span!(
+            target: "celestia-oracle",
+            "blobs_get",
+            height,
+            commitment = %hex::encode(commitment.hash()),
+            blobs = field::Empty,
+            outcome = field::Empty,
+        );
 
-        let hint = Hint::new(HintWrapper::CelestiaDA, encoded.clone());
+        let result = async {
+            match self
+                .fetch_payload(CelestiaHint::Blobs { height, commitment })
+                .await?
+            {
+                CelestiaPayload::Blobs(payloads) => {
+                    Span::current().record("blobs", payloads.len());
+                    for indexed in &payloads {
+                        self.check_blob_size(&indexed.payload.blob, height)?;
+                    }
+                    verify_oracle_blobs(
+                        &payloads,
+                        height,
+                        self.namespace,
+                        &commitment,
+                        self.app_version,
+                        self.data_commitments_slot,
+                        self.skip_storage_proof,
+                    )?;
+                    Span::current().record("outcome", "verified");
 
-        hint.send(&*self.oracle).await?;
+                    Ok(payloads
+                        .into_iter()
+                        .map(|indexed| (indexed.index, indexed.payload.blob))
+                        .collect())
+                }
+                CelestiaPayload::NamespaceAbsent(payload) => {
+                    Span::current().record("outcome", "namespace absent");
+                    self.check_namespace(payload.namespace, height)?;
+                    verify_namespace_absence(
+                        &payload,
+                        height,
+                        self.data_commitments_slot,
+                        self.skip_storage_proof,
+                    )?;
 
-        let oracle_result = self
-            .oracle
-            .get(PreimageKey::new(
-                *keccak256(encoded),
-                PreimageKeyType::GlobalGeneric,
-            ))
-            .await?;
+                    Ok(Vec::new())
+                }
+                CelestiaPayload::Blob(_) => {
+                    Err(unexpected_payload(HintWrapper::CelestiaDABlobs, height))
+                }
+            }
+        }
+        .instrument(span.clone())
+        .await;
 
-        let payload = OraclePayload::from_bytes(&oracle_result)
-            .expect("Failed to deserialize Celestia Oracle Payload");
+        if let Err(err) = &result {
+            span.record("outcome", field::display(format!("rejected: {err}")));
+        }
+        result
+    }
+
+    async fn blob_get_at_index(&self, height: u64, index: u64) -> Result<Bytes, Self::Error> {
+        let span = info_span!(
+            target: "celestia-oracle",
+            "blob_get_at_index",
+            height,
+            index,
+            blob_len = field::Empty,
+            shares = field::Empty,
+            storage_proof_nodes = field::Empty,
+            outcome = field::Empty,
+        );
+
+        let result = async {
+            match self
+                .fetch_payload(CelestiaHint::Index { height, index })
+                .await?
+            {
+                CelestiaPayload::Blob(payload) => {
+                    record_payload(&payload);
+                    self.check_blob_size(&payload.blob, height)?;
+                    // Perform Inclusion checks against the data root
+                    verify_oracle_payload_at_index(
+                        &payload,
+                        height,
+                        self.namespace,
+                        index,
+                        self.data_commitments_slot,
+                        self.skip_storage_proof,
+                    )?;
 
-        match payload.share_proof.verify(payload.data_root) {
-            Ok(_) => info!("Celestia blobs ShareProof succesfully verified"),
-            Err(err) => {
-                return Err(OracleProviderError::Preimage(PreimageOracleError::Other(
-                    err.to_string(),
-                )))
+                    Ok(payload.blob)
+                }
+                CelestiaPayload::NamespaceAbsent(payload) => {
+                    Span::current().record("outcome", "namespace absent");
+                    self.check_namespace(payload.namespace, height)?;
+                    verify_namespace_absence(
+                        &payload,
+                        height,
+                        self.data_commitments_slot,
+                        self.skip_storage_proof,
+                    )?;
+
+                    Ok(Bytes::new())
+                }
+                CelestiaPayload::Blobs(_) => {
+                    Err(unexpected_payload(HintWrapper::CelestiaDAIndex, height))
+                }
             }
         }
+        .instrument(span.clone())
+        .await;
 
-        let encoded_data_root_tuple = encode_data_root_tuple(height, &payload.data_root);
+        record_outcome(&span, result.as_ref());
+        result
+    }
+}
 
+/// Rejects a payload of another kind than the ones served for the `hint` type.
+fn unexpected_payload(hint: HintWrapper, height: u64) -> OracleProviderError {
+    OracleProviderError::Preimage(PreimageOracleError::Other(format!(
+        "host served a payload of another kind for the {hint} hint at height {height}"
+    )))
+}
+
+/// Records the sizes of a served payload on the current span.
+fn record_payload(payload: &OraclePayload) {
+    let span = Span::current();
+    span.record("blob_len", payload.blob.len());
+    span.record("shares", payload.proof.share_proof.shares().len());
+    span.record(
+        "storage_proof_nodes",
         payload
-            .data_root_tuple_proof
-            .verify(encoded_data_root_tuple, *payload.data_commitment)
-            .expect("Failed to verify data root tuple proof");
-
-        verify_data_commitment_storage(
-            payload.storage_root,
-            payload.storage_proof,
-            payload.proof_nonce,
-            payload.data_commitment,
-        )
-        .expect("Failed to verify data commitment against Blobstream storage slot");
-
-        Ok(payload.blob)
+            .proof
+            .storage_proof
+            .as_ref()
+            .map_or(0, |nodes| nodes.len()),
+    );
+}
+
+/// Records the verification outcome on `span`, keeping a namespace absence outcome when the
+/// absence was proven.
+fn record_outcome(span: &Span, result: Result<&Bytes, &OracleProviderError>) {
+    match result {
+        Ok(blob) if !blob.is_empty() => {
+            span.record("outcome", "verified");
+        }
+        Ok(_) => (),
+        Err(err) => {
+            span.record("outcome", field::display(format!("rejected: {err}")));
+        }
+    }
+}
+
+impl<T: CommsClient + Sync + Send> OracleCelestiaProvider<T> {
+    /// Fetches and verifies the blob with `commitment` at `height` like
+    /// [CelestiaProvider::blob_get], also returning the data root and the Blobstream data
+    /// commitment it was verified against.
+    pub async fn blob_get_verified(
+        &self,
+        height: u64,
+        commitment: Commitment,
+    ) -> Result<VerifiedBlob, OracleProviderError> {
+        let span = info_span!(
+            target: "celestia-oracle",
+            "blob_get",
+            height,
+            commitment = %hex::encode(commitment.hash()),
+            blob_len = field::Empty,
+            shares = field::Empty,
+            storage_proof_nodes = field::Empty,
+            outcome = field::Empty,
+        );
+
+        let result = async {
+            match self
+                .fetch_payload(CelestiaHint::Commitment { height, commitment })
+                .await?
+            {
+                CelestiaPayload::Blob(payload) => {
+                    record_payload(&payload);
+                    self.check_blob_size(&payload.blob, height)?;
+                    // Perform Inclusion checks against the data root
+                    verify_oracle_payload(
+                        &payload,
+                        height,
+                        self.namespace,
+                        &commitment,
+                        self.app_version,
+                        self.data_commitments_slot,
+                        self.skip_storage_proof,
+                    )?;
+
+                    Ok(VerifiedBlob {
+                        blob: payload.blob,
+                        data_root: payload.proof.data_root,
+                        data_commitment: payload.proof.data_commitment,
+                        proof_nonce: payload.proof.proof_nonce,
+                    })
+                }
+                CelestiaPayload::NamespaceAbsent(payload) => {
+                    Span::current().record("outcome", "namespace absent");
+                    self.check_namespace(payload.namespace, height)?;
+                    verify_namespace_absence(
+                        &payload,
+                        height,
+                        self.data_commitments_slot,
+                        self.skip_storage_proof,
+                    )?;
+
+                    Ok(VerifiedBlob {
+                        blob: Bytes::new(),
+                        data_root: payload.dah.hash(),
+                        data_commitment: payload.inclusion_proof.data_commitment,
+                        proof_nonce: payload.inclusion_proof.proof_nonce,
+                    })
+                }
+                CelestiaPayload::Blobs(_) => {
+                    Err(unexpected_payload(HintWrapper::CelestiaDA, height))
+                }
+            }
+        }
+        .instrument(span.clone())
+        .await;
+
+        record_outcome(&span, result.as_ref().map(|verified| &verified.blob));
+        result
+    }
+
+    /// Rejects blobs larger than the configured maximum, before spending any work on them.
+    fn check_blob_size(&self, blob: &Bytes, height: u64) -> Result<(), OracleProviderError> {
+        if blob.len() > self.max_blob_bytes {
+            return Err(OracleProviderError::Preimage(PreimageOracleError::Other(
+                format!(
+                    "Celestia blob at height {height} is {} bytes, above the {} byte limit",
+                    blob.len(),
+                    self.max_blob_bytes
+                ),
+            )));
+        }
+        Ok(())
+    }
+
+    /// Rejects a namespace absence proof for another namespace than the rollup's, which would
+    /// prove nothing about the rollup's data.
+    fn check_namespace(
+        &self,
+        namespace: Namespace,
+        height: u64,
+    ) -> Result<(), OracleProviderError> {
+        if namespace != self.namespace {
+            return Err(OracleProviderError::Preimage(PreimageOracleError::Other(
+                format!(
+                    "namespace absence at height {height} is proven for namespace {namespace:?}, expected {:?}",
+                    self.namespace
+                ),
+            )));
+        }
+        Ok(())
+    }
+
+    /// Sends the given hint to the host and reads back the [CelestiaPayload] stored behind the
+    /// hash of the hint data.
+    async fn fetch_payload(
+        &self,
+        hint: CelestiaHint,
+    ) -> Result<CelestiaPayload, OracleProviderError> {
+        let key = hint.preimage_key();
+        Hint::new(hint.hint_type(), hint.encode())
+            .send(&*self.oracle)
+            .await?;
+
+        let oracle_result = self.oracle.get(key).await?;
+
+        CelestiaPayload::from_bytes(&oracle_result).map_err(|err| {
+            OracleProviderError::Preimage(PreimageOracleError::Other(format!(
+                "failed to deserialize Celestia oracle payload: {err}"
+            )))
+        })
     }
 }
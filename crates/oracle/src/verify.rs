@@ -0,0 +1,254 @@
+//! Verification of [OraclePayload]s, decoupled from the oracle transport.
+
+use alloc::{collections::BTreeSet, format, string::String, vec::Vec};
+use alloy_primitives::B256;
+use celestia_types::{
+    hash::Hash,
+    nmt::{Namespace, NamespacedSha2Hasher},
+    AppVersion, Commitment,
+};
+use core::fmt::Display;
+use hana_blobstream::blobstream::DataRootInclusionProof;
+use kona_preimage::errors::PreimageOracleError;
+use kona_proof::errors::OracleProviderError;
+use tracing::info;
+
+use crate::{
+    payload::{IndexedOraclePayload, NamespaceAbsencePayload, OraclePayload},
+    profiling::{profile, VerificationStep},
+};
+
+/// Verifies an already-fetched [OraclePayload] for the blob in `namespace` at `height` with the
+/// given `commitment`.
+///
+/// This performs no I/O and delegates to [BlobstreamProof::verify]: the commitment is recomputed
+/// from the blob under the share layout of `app_version`, the share proof is checked against the
+/// data root, the data root tuple is checked against the Blobstream data commitment, and the data
+/// commitment is checked against the Blobstream storage proof of the `data_commitments_slot`
+/// mapping, unless `skip_storage_proof` is set.
+///
+/// [BlobstreamProof::verify]: hana_blobstream::blobstream::BlobstreamProof::verify
+pub fn verify_oracle_payload(
+    payload: &OraclePayload,
+    height: u64,
+    namespace: Namespace,
+    commitment: &Commitment,
+    app_version: AppVersion,
+    data_commitments_slot: u32,
+    skip_storage_proof: bool,
+) -> Result<(), OracleProviderError> {
+    let proof = &payload.proof;
+
+    profile(VerificationStep::BlobProof, || {
+        if skip_storage_proof {
+            proof.verify_without_storage_proof(
+                height,
+                &payload.blob,
+                namespace,
+                commitment,
+                app_version,
+            )
+        } else {
+            proof.verify(
+                height,
+                &payload.blob,
+                namespace,
+                commitment,
+                app_version,
+                data_commitments_slot,
+            )
+        }
+    })
+    .map_err(|err| {
+        verification_error(format!(
+            "height {height} commitment {}: {err}",
+            B256::from(*commitment.hash())
+        ))
+    })?;
+    info!("Celestia blob proof succesfully verified");
+
+    Ok(())
+}
+
+/// Verifies an already-fetched [OraclePayload] for the blob in `namespace` whose first share is
+/// at `index` in the block at `height`.
+///
+/// The hint carries no commitment, so this delegates to [BlobstreamProof::verify_at_index]
+/// instead: the proven shares are checked against the blob and against the shares the blob spans
+/// from `index`, then the proofs are checked like [verify_oracle_payload].
+///
+/// [BlobstreamProof::verify_at_index]: hana_blobstream::blobstream::BlobstreamProof::verify_at_index
+pub fn verify_oracle_payload_at_index(
+    payload: &OraclePayload,
+    height: u64,
+    namespace: Namespace,
+    index: u64,
+    data_commitments_slot: u32,
+    skip_storage_proof: bool,
+) -> Result<(), OracleProviderError> {
+    let proof = &payload.proof;
+
+    profile(VerificationStep::BlobProof, || {
+        if skip_storage_proof {
+            proof.verify_at_index_without_storage_proof(height, &payload.blob, namespace, index)
+        } else {
+            proof.verify_at_index(
+                height,
+                &payload.blob,
+                namespace,
+                index,
+                data_commitments_slot,
+            )
+        }
+    })
+    .map_err(|err| verification_error(format!("height {height} index {index}: {err}")))?;
+    info!("Celestia blob proof succesfully verified");
+
+    Ok(())
+}
+
+/// Verifies the already-fetched [IndexedOraclePayload]s served for every blob in `namespace` with
+/// the given `commitment` at `height`.
+///
+/// Each blob is verified like [verify_oracle_payload], and its share proof is checked to start at
+/// its index, so the blobs are ordered by proven indices. At least one blob must be served, and
+/// no index twice. That no blob was left out is not proven.
+pub fn verify_oracle_blobs(
+    payloads: &[IndexedOraclePayload],
+    height: u64,
+    namespace: Namespace,
+    commitment: &Commitment,
+    app_version: AppVersion,
+    data_commitments_slot: u32,
+    skip_storage_proof: bool,
+) -> Result<(), OracleProviderError> {
+    let blob = || {
+        format!(
+            "height {height} commitment {}",
+            B256::from(*commitment.hash())
+        )
+    };
+
+    if payloads.is_empty() {
+        return Err(verification_error(format!("{}: no blobs served", blob())));
+    }
+    let indices: BTreeSet<u64> = payloads.iter().map(|payload| payload.index).collect();
+    if indices.len() != payloads.len() {
+        return Err(verification_error(format!(
+            "{}: a share index is served twice",
+            blob()
+        )));
+    }
+
+    for IndexedOraclePayload { index, payload } in payloads {
+        verify_oracle_payload(
+            payload,
+            height,
+            namespace,
+            commitment,
+            app_version,
+            data_commitments_slot,
+            skip_storage_proof,
+        )?;
+        payload
+            .proof
+            .verify_share_range(*index)
+            .map_err(|err| verification_error(format!("{} index {index}: {err}", blob())))?;
+    }
+
+    Ok(())
+}
+
+/// Verifies an already-fetched [NamespaceAbsencePayload] for the block at `height`.
+///
+/// Every row of the data availability header whose namespace range covers the namespace must
+/// carry a proof of the namespace's absence, and the header must hash to a data root committed to
+/// by Blobstream. The data commitment is checked against the Blobstream storage proof of the
+/// `data_commitments_slot` mapping unless `skip_storage_proof` is set.
+pub fn verify_namespace_absence(
+    payload: &NamespaceAbsencePayload,
+    height: u64,
+    data_commitments_slot: u32,
+    skip_storage_proof: bool,
+) -> Result<(), OracleProviderError> {
+    let namespace = payload.namespace;
+    profile(VerificationStep::NamespaceAbsence, || {
+        let mut rows = payload.namespace_data.rows.iter();
+
+        for row_root in payload
+            .dah
+            .row_roots()
+            .iter()
+            .filter(|root| root.contains::<NamespacedSha2Hasher>(*namespace))
+        {
+            let row = rows.next().ok_or_else(|| {
+                verification_error(format!(
+                    "missing absence proof for namespace {namespace:?} at height {height}"
+                ))
+            })?;
+
+            if !row.shares.is_empty() || !row.proof.is_of_absence() {
+                return Err(verification_error(format!(
+                    "namespace {namespace:?} holds data at height {height}"
+                )));
+            }
+
+            row.proof
+                .verify_complete_namespace(row_root, &[] as &[Vec<u8>], *namespace)
+                .map_err(|err| {
+                    verification_error(format!(
+                        "failed to verify absence proof for namespace {namespace:?} at height {height}: {err:?}"
+                    ))
+                })?;
+        }
+
+        if rows.next().is_some() {
+            return Err(verification_error(format!(
+                "unexpected absence proofs for namespace {namespace:?} at height {height}"
+            )));
+        }
+
+        Ok(())
+    })?;
+    info!("Celestia namespace absence proof succesfully verified");
+
+    verify_data_root_inclusion(
+        &payload.inclusion_proof,
+        height,
+        &payload.dah.hash(),
+        format_args!("namespace {namespace:?}"),
+        data_commitments_slot,
+        skip_storage_proof,
+    )
+}
+
+/// Checks the data root tuple against the Blobstream data commitment, and the data commitment
+/// against the Blobstream storage proof of the `data_commitments_slot` mapping unless
+/// `skip_storage_proof` is set.
+fn verify_data_root_inclusion(
+    proof: &DataRootInclusionProof,
+    height: u64,
+    data_root: &Hash,
+    blob: impl Display,
+    data_commitments_slot: u32,
+    skip_storage_proof: bool,
+) -> Result<(), OracleProviderError> {
+    let verified = profile(VerificationStep::DataRootTuple, || {
+        proof.verify_without_storage_proof(height, data_root)
+    })
+    .and_then(|()| {
+        if skip_storage_proof {
+            return Ok(());
+        }
+        profile(VerificationStep::StorageProof, || {
+            proof.verify_storage_proof(data_commitments_slot)
+        })
+    });
+
+    verified.map_err(|err| verification_error(format!("height {height} {blob}: {err}")))
+}
+
+/// Wraps a verification failure message into an [OracleProviderError].
+fn verification_error(message: String) -> OracleProviderError {
+    OracleProviderError::Preimage(PreimageOracleError::Other(message))
+}
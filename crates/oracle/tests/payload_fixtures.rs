@@ -0,0 +1,102 @@
+//! Checks of the payload fixtures measured by the `verify_payload` benchmark: each must verify
+//! for the hint it is named after, and survive the binary encoding the client decodes.
+
+mod support;
+
+use celestia_types::AppVersion;
+use hana_blobstream::blobstream::DATA_COMMITMENTS_SLOT;
+use hana_oracle::{
+    payload::CelestiaPayload,
+    verify::{verify_namespace_absence, verify_oracle_payload, verify_oracle_payload_at_index},
+};
+use support::{blob_namespace, fixtures, FixtureHint};
+
+#[test]
+fn fixtures_verify_for_their_hint() {
+    let fixtures = fixtures();
+    assert!(
+        fixtures.len() >= 2,
+        "expected at least two payload fixtures"
+    );
+
+    for fixture in &fixtures {
+        let result = match (&fixture.payload, &fixture.hint) {
+            (CelestiaPayload::Blob(payload), FixtureHint::Commitment(commitment)) => {
+                verify_oracle_payload(
+                    payload,
+                    fixture.height,
+                    blob_namespace(payload),
+                    commitment,
+                    AppVersion::latest(),
+                    DATA_COMMITMENTS_SLOT,
+                    false,
+                )
+            }
+            (CelestiaPayload::Blob(payload), FixtureHint::Index(index)) => {
+                verify_oracle_payload_at_index(
+                    payload,
+                    fixture.height,
+                    blob_namespace(payload),
+                    *index,
+                    DATA_COMMITMENTS_SLOT,
+                    false,
+                )
+            }
+            (CelestiaPayload::NamespaceAbsent(payload), _) => {
+                verify_namespace_absence(payload, fixture.height, DATA_COMMITMENTS_SLOT, false)
+            }
+            (CelestiaPayload::Blobs(_), _) => {
+                panic!("fixture {} is not named after a blobs hint", fixture.name)
+            }
+        };
+
+        result.unwrap_or_else(|err| panic!("fixture {}: {err}", fixture.name));
+    }
+}
+
+#[test]
+fn fixtures_round_trip_through_the_binary_encoding() {
+    for fixture in fixtures() {
+        let bytes = fixture.payload.to_bytes().unwrap();
+
+        assert_eq!(
+            CelestiaPayload::from_bytes(&bytes).unwrap(),
+            fixture.payload,
+            "fixture {}",
+            fixture.name
+        );
+    }
+}
+
+/// A height or index other than the one a fixture was served for fails its verification.
+#[test]
+fn fixtures_do_not_verify_for_another_hint() {
+    for fixture in fixtures() {
+        let CelestiaPayload::Blob(payload) = &fixture.payload else {
+            continue;
+        };
+        let namespace = blob_namespace(payload);
+
+        let result = match fixture.hint {
+            FixtureHint::Commitment(commitment) => verify_oracle_payload(
+                payload,
+                fixture.height + 1,
+                namespace,
+                &commitment,
+                AppVersion::latest(),
+                DATA_COMMITMENTS_SLOT,
+                false,
+            ),
+            FixtureHint::Index(index) => verify_oracle_payload_at_index(
+                payload,
+                fixture.height,
+                namespace,
+                index + 1,
+                DATA_COMMITMENTS_SLOT,
+                false,
+            ),
+        };
+
+        assert!(result.is_err(), "fixture {} verified", fixture.name);
+    }
+}
@@ -0,0 +1,387 @@
+//! Round trips of the payloads served by the host through their binary encoding, which is the
+//! format stored on disk and sent over the preimage channels to the client.
+//!
+//! The Celestia proofs are built from JSON in the format served by celestia-node, which is how
+//! the host receives them.
+
+use alloy_primitives::{b256, hex, Bytes, B256, U256};
+use celestia_types::{
+    hash::Hash, nmt::Namespace, row_namespace_data::NamespaceData, DataAvailabilityHeader,
+    MerkleProof, ShareProof,
+};
+use hana_blobstream::blobstream::BlobstreamProof;
+use hana_oracle::payload::{
+    CelestiaPayload, IndexedOraclePayload, NamespaceAbsencePayload, OraclePayload,
+    UnsupportedPayloadVersion, PAYLOAD_FORMAT_VERSION,
+};
+use serde_json::json;
+
+/// Size in bytes of a Celestia share
+const SHARE_SIZE: usize = 512;
+
+/// The user-specifiable suffix of the version 0 namespace the proofs are built for
+const NAMESPACE_ID: [u8; 10] = [0xaa; 10];
+
+/// The L1 block the storage proof is taken at
+const STORAGE_PROOF_BLOCK: B256 =
+    b256!("0x1111111111111111111111111111111111111111111111111111111111111111");
+
+/// Encodes `bytes` as standard padded base64, the encoding of byte fields in celestia-node JSON.
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (i, byte)| {
+            group | ((*byte as u32) << (16 - 8 * i))
+        });
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[((group >> (18 - 6 * i)) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// The 29 byte version 0 namespace the proofs are built for
+fn namespace_bytes() -> Vec<u8> {
+    Namespace::new_v0(&NAMESPACE_ID)
+        .expect("valid v0 namespace")
+        .as_bytes()
+        .to_vec()
+}
+
+/// A namespaced hash covering only the test namespace, with `seed` as its digest
+fn namespaced_hash(seed: u8) -> Vec<u8> {
+    let mut hash = namespace_bytes();
+    hash.extend(namespace_bytes());
+    hash.extend([seed; 32]);
+    hash
+}
+
+/// A share of the test namespace, whose data bytes are all `seed`
+fn share(seed: u8) -> Vec<u8> {
+    let mut share = namespace_bytes();
+    share.resize(SHARE_SIZE, seed);
+    share
+}
+
+fn merkle_proof() -> MerkleProof {
+    serde_json::from_value(json!({
+        "total": 4,
+        "index": 1,
+        "leaf_hash": base64(&[0x22; 32]),
+        "aunts": [base64(&[0x33; 32]), base64(&[0x44; 32])],
+    }))
+    .expect("data root tuple proof")
+}
+
+fn share_proof() -> ShareProof {
+    serde_json::from_value(json!({
+        "data": [base64(&share(0x55)), base64(&share(0x66))],
+        "share_proofs": [{
+            "start": 1,
+            "end": 3,
+            "nodes": [base64(&namespaced_hash(0x77)), base64(&namespaced_hash(0x88))],
+        }],
+        "namespace_id": base64(&namespace_bytes()[1..]),
+        "row_proof": {
+            "row_roots": [hex::encode_upper(namespaced_hash(0x99))],
+            "proofs": [{
+                "total": 2,
+                "index": 0,
+                "leaf_hash": base64(&[0xaa; 32]),
+                "aunts": [base64(&[0xbb; 32])],
+            }],
+            "start_row": 0,
+            "end_row": 0,
+        },
+        "namespace_version": 0,
+    }))
+    .expect("share proof")
+}
+
+fn namespace_data() -> NamespaceData {
+    serde_json::from_value(json!([{
+        "shares": [],
+        "proof": {
+            "start": 1,
+            "end": 2,
+            "nodes": [base64(&namespaced_hash(0xcc))],
+            "leaf_hash": base64(&namespaced_hash(0xdd)),
+        },
+    }]))
+    .expect("namespace data")
+}
+
+fn dah() -> DataAvailabilityHeader {
+    serde_json::from_value(json!({
+        "row_roots": [base64(&namespaced_hash(0x01)), base64(&namespaced_hash(0x02))],
+        "column_roots": [base64(&namespaced_hash(0x03)), base64(&namespaced_hash(0x04))],
+    }))
+    .expect("data availability header")
+}
+
+fn blobstream_proof(storage_proof_block: Option<B256>) -> BlobstreamProof {
+    BlobstreamProof::new(
+        Hash::Sha256([0xee; 32]),
+        B256::repeat_byte(0xef),
+        merkle_proof(),
+        share_proof(),
+        U256::from(4_206_669u64),
+        B256::repeat_byte(0xf0),
+        Some(vec![
+            Bytes::from_static(&[0xf1; 83]),
+            Bytes::from_static(&[0xf2; 532]),
+        ]),
+    )
+    .with_storage_proof_block(storage_proof_block)
+}
+
+/// A payload small enough to pin its binary encoding in [GOLDEN_PAYLOAD]
+fn golden_payload() -> CelestiaPayload {
+    let data_root_tuple_proof = serde_json::from_value(json!({
+        "total": 2,
+        "index": 1,
+        "leaf_hash": base64(&[0x22; 32]),
+        "aunts": [base64(&[0x33; 32])],
+    }))
+    .expect("data root tuple proof");
+    let share_proof = serde_json::from_value(json!({
+        "data": [base64(&share(0x55))],
+        "share_proofs": [{
+            "start": 0,
+            "end": 1,
+            "nodes": [base64(&namespaced_hash(0x77))],
+        }],
+        "namespace_id": base64(&namespace_bytes()[1..]),
+        "row_proof": {
+            "row_roots": [hex::encode_upper(namespaced_hash(0x99))],
+            "proofs": [{
+                "total": 2,
+                "index": 0,
+                "leaf_hash": base64(&[0xaa; 32]),
+                "aunts": [base64(&[0xbb; 32])],
+            }],
+            "start_row": 0,
+            "end_row": 0,
+        },
+        "namespace_version": 0,
+    }))
+    .expect("share proof");
+
+    CelestiaPayload::Blob(OraclePayload::from_proof(
+        Bytes::from_static(b"hana"),
+        BlobstreamProof::new(
+            Hash::Sha256([0xee; 32]),
+            B256::repeat_byte(0xef),
+            data_root_tuple_proof,
+            share_proof,
+            U256::from(4242),
+            B256::repeat_byte(0xf0),
+            Some(vec![Bytes::from_static(&[0xf1; 4])]),
+        )
+        .with_storage_proof_block(Some(STORAGE_PROOF_BLOCK)),
+    ))
+}
+
+/// The binary encoding of [golden_payload], in lines of 48 bytes
+const GOLDEN_PAYLOAD: &[&str] = &[
+    "0200000000040000000000000068616e6140000000000000004545454545454545454545454545454545454545454545",
+    "454545454545454545454545454545454545454545454545454545454545454545454545454545454520000000000000",
+    "00efefefefefefefefefefefefefefefefefefefefefefefefefefefefefefefef010000000000000032010000000000",
+    "0000312c0000000000000049694969496949694969496949694969496949694969496949694969496949694969496949",
+    "6949694969493d01000000000000002c000000000000004d7a4d7a4d7a4d7a4d7a4d7a4d7a4d7a4d7a4d7a4d7a4d7a4d",
+    "7a4d7a4d7a4d7a4d7a4d7a4d7a4d7a4d7a4d3d0100000000000000ac0200000000000041414141414141414141414141",
+    "4141414141414141414141414b7171717171717171717171717056565656565656565656565656565656565656565656",
+    "565656565656565656565656565656565656565656565656565656565656565656565656565656565656565656565656",
+    "565656565656565656565656565656565656565656565656565656565656565656565656565656565656565656565656",
+    "565656565656565656565656565656565656565656565656565656565656565656565656565656565656565656565656",
+    "565656565656565656565656565656565656565656565656565656565656565656565656565656565656565656565656",
+    "565656565656565656565656565656565656565656565656565656565656565656565656565656565656565656565656",
+    "565656565656565656565656565656565656565656565656565656565656565656565656565656565656565656565656",
+    "565656565656565656565656565656565656565656565656565656565656565656565656565656565656565656565656",
+    "565656565656565656565656565656565656565656565656565656565656565656565656565656565656565656565656",
+    "565656565656565656565656565656565656565656565656565656565656565656565656565656565656565656565656",
+    "565656565656565656565656565656565656565656565656565656565656565656565656565656565656565656565656",
+    "565656565656565656565656565656565656565656565656565656565656565656565656565656565656565656565656",
+    "565656565656565656565656565656565656565656565656565656565656565656565656565656565656565656565656",
+    "565656565656565656565656565656565656565656565656565656565656565656565656565656565656565656553d01",
+    "000000000000000000000001000000010000000000000078000000000000004141414141414141414141414141414141",
+    "41414141414141414b7171717171717171717171716f4141414141414141414141414141414141414141414141414171",
+    "7171717171717171717171716e6433643364336433643364336433643364336433643364336433643364336433643364",
+    "336433643364330000000000000000280000000000000041414141414141414141414141414141414141414141414171",
+    "717171717171717171717171673d3d010100000000000000b40000000000000030303030303030303030303030303030",
+    "303030303030303030303030303030303030303030304141414141414141414141414141414141414141303030303030",
+    "303030303030303030303030303030303030303030303030303030303030303041414141414141414141414141414141",
+    "414141413939393939393939393939393939393939393939393939393939393939393939393939393939393939393939",
+    "393939393939393939393939393939393939393901000000000000000100000000000000320100000000000000302c00",
+    "000000000000717171717171717171717171717171717171717171717171717171717171717171717171717171717171",
+    "6f3d01000000000000002c00000000000000753775377537753775377537753775377537753775377537753775377537",
+    "753775377537753775377537733d00000000000000000000000000000000000000002000000000000000000000000000",
+    "00000000000000000000000000000000000000000000000010922000000000000000f0f0f0f0f0f0f0f0f0f0f0f0f0f0",
+    "f0f0f0f0f0f0f0f0f0f0f0f0f0f0f0f0f0f00101000000000000000400000000000000f1f1f1f1012000000000000000",
+    "1111111111111111111111111111111111111111111111111111111111111111",
+];
+
+fn oracle_payload() -> OraclePayload {
+    OraclePayload::from_proof(
+        Bytes::from_static(b"a rollup batch posted to Celestia"),
+        blobstream_proof(Some(STORAGE_PROOF_BLOCK)),
+    )
+}
+
+fn namespace_absence_payload() -> NamespaceAbsencePayload {
+    let proof = blobstream_proof(Some(STORAGE_PROOF_BLOCK));
+    NamespaceAbsencePayload {
+        namespace: Namespace::new_v0(&NAMESPACE_ID).unwrap(),
+        dah: dah(),
+        namespace_data: namespace_data(),
+        inclusion_proof: proof.inclusion_proof(),
+    }
+}
+
+#[test]
+fn blobstream_proof_round_trips() {
+    for storage_proof_block in [None, Some(STORAGE_PROOF_BLOCK)] {
+        let proof = blobstream_proof(storage_proof_block);
+        let bytes = proof.to_bytes().unwrap();
+
+        assert_eq!(BlobstreamProof::from_bytes(&bytes).unwrap(), proof);
+    }
+}
+
+#[test]
+fn oracle_payload_round_trips() {
+    let payload = oracle_payload();
+    let bytes = payload.to_bytes().unwrap();
+
+    assert_eq!(OraclePayload::from_bytes(&bytes).unwrap(), payload);
+}
+
+#[test]
+fn celestia_payload_round_trips() {
+    for payload in [
+        CelestiaPayload::Blob(oracle_payload()),
+        CelestiaPayload::NamespaceAbsent(namespace_absence_payload()),
+        CelestiaPayload::Blobs(vec![
+            IndexedOraclePayload::new(2, oracle_payload()),
+            IndexedOraclePayload::new(9, oracle_payload()),
+        ]),
+    ] {
+        let bytes = payload.to_bytes().unwrap();
+
+        assert_eq!(CelestiaPayload::from_bytes(&bytes).unwrap(), payload);
+        assert_eq!(
+            CelestiaPayload::from_bytes(&bytes).unwrap().content_hash(),
+            payload.content_hash()
+        );
+    }
+}
+
+/// Pins the layout of the oracle payload: the length prefixed blob, then the proof.
+#[test]
+fn oracle_payload_layout() {
+    let payload = oracle_payload();
+    let bytes = payload.to_bytes().unwrap();
+
+    let blob_len = payload.blob.len();
+    assert_eq!(bytes[..8], (blob_len as u64).to_le_bytes());
+    assert_eq!(bytes[8..8 + blob_len], payload.blob[..]);
+    assert_eq!(bytes[8 + blob_len..], payload.proof.to_bytes().unwrap()[..]);
+}
+
+/// Pins the layout of the Celestia payload: the format version, the variant index, then the
+/// inner payload.
+#[test]
+fn celestia_payload_layout() {
+    let blob = oracle_payload();
+    let bytes = CelestiaPayload::Blob(blob.clone()).to_bytes().unwrap();
+    assert_eq!(bytes[0], PAYLOAD_FORMAT_VERSION);
+    assert_eq!(bytes[1..5], 0u32.to_le_bytes());
+    assert_eq!(bytes[5..], blob.to_bytes().unwrap()[..]);
+
+    let absence = namespace_absence_payload();
+    let bytes = CelestiaPayload::NamespaceAbsent(absence.clone())
+        .to_bytes()
+        .unwrap();
+    assert_eq!(bytes[0], PAYLOAD_FORMAT_VERSION);
+    assert_eq!(bytes[1..5], 1u32.to_le_bytes());
+    assert_eq!(bytes[5..], bincode::serialize(&absence).unwrap()[..]);
+
+    let blobs = vec![IndexedOraclePayload::new(2, blob)];
+    let bytes = CelestiaPayload::Blobs(blobs.clone()).to_bytes().unwrap();
+    assert_eq!(bytes[0], PAYLOAD_FORMAT_VERSION);
+    assert_eq!(bytes[1..5], 2u32.to_le_bytes());
+    assert_eq!(bytes[5..], bincode::serialize(&blobs).unwrap()[..]);
+}
+
+#[test]
+fn rejects_other_format_versions() {
+    let mut bytes = CelestiaPayload::Blob(oracle_payload()).to_bytes().unwrap();
+    bytes[0] = PAYLOAD_FORMAT_VERSION + 1;
+
+    let err = CelestiaPayload::from_bytes(&bytes).unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<UnsupportedPayloadVersion>(),
+        Some(&UnsupportedPayloadVersion {
+            found: Some(PAYLOAD_FORMAT_VERSION + 1)
+        })
+    );
+
+    let err = CelestiaPayload::from_bytes(&[]).unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<UnsupportedPayloadVersion>(),
+        Some(&UnsupportedPayloadVersion { found: None })
+    );
+}
+
+/// A payload encoded without the format version, as served before it was introduced, is
+/// rejected rather than decoded into garbage.
+#[test]
+fn rejects_unversioned_payloads() {
+    for payload in [
+        CelestiaPayload::Blob(oracle_payload()),
+        CelestiaPayload::NamespaceAbsent(namespace_absence_payload()),
+    ] {
+        let unversioned = bincode::serialize(&payload).unwrap();
+
+        assert!(CelestiaPayload::from_bytes(&unversioned).is_err());
+        assert!(CelestiaPayload::blob_from_bytes(&unversioned).is_err());
+    }
+}
+
+/// Pins the storage proof block as the last field of the proof: an option tag followed by the
+/// block hash.
+#[test]
+fn storage_proof_block_is_the_last_field() {
+    let without = blobstream_proof(None).to_bytes().unwrap();
+    let with = blobstream_proof(Some(STORAGE_PROOF_BLOCK))
+        .to_bytes()
+        .unwrap();
+
+    let (tag, prefix) = without.split_last().unwrap();
+    assert_eq!(*tag, 0);
+    assert_eq!(with[..prefix.len()], prefix[..]);
+    assert_eq!(with[prefix.len()], 1);
+    assert!(with.ends_with(STORAGE_PROOF_BLOCK.as_slice()));
+}
+
+/// Pins the binary encoding of a payload byte for byte, including the encoding of the Celestia
+/// and tendermint types it embeds, which the layout tests do not cover. A change here breaks
+/// every stored payload, and calls for a bump of the [PAYLOAD_FORMAT_VERSION].
+#[test]
+fn encoding_matches_the_golden_payload() {
+    let golden = hex::decode(GOLDEN_PAYLOAD.concat()).unwrap();
+    let payload = golden_payload();
+
+    assert_eq!(
+        hex::encode(payload.to_bytes().unwrap()),
+        hex::encode(&golden),
+        "the binary encoding of payloads changed"
+    );
+    assert_eq!(CelestiaPayload::from_bytes(&golden).unwrap(), payload);
+}
@@ -0,0 +1,123 @@
+//! The preimage key of a Celestia payload, which the client derives from the hint it sends and the
+//! host from the hint data it receives: both must land on the same key.
+
+use alloy_primitives::keccak256;
+use celestia_types::Commitment;
+use hana_celestia::CelestiaPointer;
+use hana_oracle::hint::{
+    celestia_preimage_key, hint_preimage_key, CelestiaHint, CELESTIA_DA_BLOBS_KEY_PREFIX,
+};
+use kona_preimage::{PreimageKey, PreimageKeyType};
+
+const HEIGHT: u64 = 4_200_042;
+
+fn commitment() -> Commitment {
+    Commitment::new([7; 32])
+}
+
+/// Hint data laid out by hand: the height as little endian, then the commitment or the index.
+fn expected_data(height: u64, rest: &[u8]) -> Vec<u8> {
+    let mut data = height.to_le_bytes().to_vec();
+    data.extend_from_slice(rest);
+    data
+}
+
+/// The key of hint data laid out by hand, see [expected_data].
+fn expected_key(height: u64, rest: &[u8]) -> PreimageKey {
+    PreimageKey::new(
+        *keccak256(expected_data(height, rest)),
+        PreimageKeyType::GlobalGeneric,
+    )
+}
+
+#[test]
+fn commitment_hints_are_keyed_by_the_hash_of_their_data() {
+    let hint = CelestiaHint::Commitment {
+        height: HEIGHT,
+        commitment: commitment(),
+    };
+    let expected = expected_key(HEIGHT, &[7; 32]);
+
+    assert_eq!(celestia_preimage_key(HEIGHT, commitment()), expected);
+    assert_eq!(hint.preimage_key(), expected);
+    assert_eq!(hint_preimage_key(&hint.encode()), expected);
+}
+
+#[test]
+fn index_hints_are_keyed_by_the_hash_of_their_data() {
+    let hint = CelestiaHint::Index {
+        height: HEIGHT,
+        index: 5,
+    };
+    let expected = expected_key(HEIGHT, &5u64.to_le_bytes());
+
+    assert_eq!(hint.preimage_key(), expected);
+    assert_eq!(hint_preimage_key(&hint.encode()), expected);
+}
+
+/// Hints for every blob of a commitment carry the data of the hint for one blob, so their key is
+/// prefixed to keep the payloads apart.
+#[test]
+fn blobs_hints_are_keyed_apart_from_commitment_hints() {
+    let hint = CelestiaHint::Blobs {
+        height: HEIGHT,
+        commitment: commitment(),
+    };
+    let mut data = CELESTIA_DA_BLOBS_KEY_PREFIX.to_vec();
+    data.extend_from_slice(&hint.encode());
+
+    assert_eq!(hint.encode(), expected_data(HEIGHT, &[7; 32]));
+    assert_eq!(
+        hint.preimage_key(),
+        PreimageKey::new(*keccak256(&data), PreimageKeyType::GlobalGeneric)
+    );
+    assert_ne!(
+        hint.preimage_key(),
+        celestia_preimage_key(HEIGHT, commitment())
+    );
+}
+
+/// The host keys the payload by the hint it decoded, which must be the key the client reads for
+/// the pointer it hinted.
+#[test]
+fn host_and_client_derive_the_same_key() {
+    let pointers = [
+        CelestiaPointer::Commitment {
+            height: HEIGHT,
+            commitment: commitment(),
+        },
+        CelestiaPointer::Index {
+            height: HEIGHT,
+            index: 5,
+        },
+    ];
+
+    for pointer in pointers {
+        let client = CelestiaHint::from(pointer.clone());
+        let data = client.encode();
+        let host = CelestiaHint::decode(client.hint_type(), &data).unwrap();
+
+        assert_eq!(host, client);
+        assert_eq!(host.preimage_key(), client.preimage_key(), "{pointer:?}");
+    }
+}
+
+#[test]
+fn hints_for_other_blobs_have_other_keys() {
+    let key = celestia_preimage_key(HEIGHT, commitment());
+
+    assert_ne!(celestia_preimage_key(HEIGHT + 1, commitment()), key);
+    assert_ne!(celestia_preimage_key(HEIGHT, Commitment::new([8; 32])), key);
+    assert_ne!(
+        CelestiaHint::Index {
+            height: HEIGHT,
+            index: 5,
+        }
+        .preimage_key(),
+        CelestiaHint::Index {
+            height: HEIGHT,
+            index: 6,
+        }
+        .preimage_key()
+    );
+}
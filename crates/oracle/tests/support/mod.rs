@@ -0,0 +1,90 @@
+//! Loading of the payload fixtures in `fixtures/`, shared by the fixture tests and the
+//! `verify_payload` benchmark.
+//!
+//! Each file is named after the hint it was served for, like the files of a payload archive
+//! written by a host run with `--payload-archive`: `<height>_<commitment hex>` or
+//! `<height>_index_<index>`. Files with the `payload` extension hold the binary encoding, files
+//! with the `json` extension the JSON encoding, which does not depend on the
+//! [PAYLOAD_FORMAT_VERSION](hana_oracle::payload::PAYLOAD_FORMAT_VERSION).
+
+use alloy_primitives::hex;
+use celestia_types::{nmt::Namespace, Commitment};
+use hana_oracle::payload::{CelestiaPayload, OraclePayload};
+use std::{fs, path::Path};
+
+/// The Celestia hint a fixture payload was served for
+pub enum FixtureHint {
+    Commitment(Commitment),
+    Index(u64),
+}
+
+/// A payload fixture along with the hint it was served for
+pub struct Fixture {
+    pub name: String,
+    pub height: u64,
+    pub hint: FixtureHint,
+    pub payload: CelestiaPayload,
+}
+
+/// Parses the height and hint of a fixture from its file stem.
+fn parse_hint(stem: &str) -> Option<(u64, FixtureHint)> {
+    let (height, rest) = stem.split_once('_')?;
+    let height = height.parse().ok()?;
+
+    let hint = match rest.strip_prefix("index_") {
+        Some(index) => FixtureHint::Index(index.parse().ok()?),
+        None => {
+            let commitment: [u8; 32] = hex::decode(rest).ok()?.try_into().ok()?;
+            FixtureHint::Commitment(Commitment::new(commitment))
+        }
+    };
+    Some((height, hint))
+}
+
+/// Loads the payload fixtures, sorted by name. Panics if there are none, so a missing fixtures
+/// directory fails the run instead of measuring or checking nothing.
+pub fn fixtures() -> Vec<Fixture> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures");
+    let mut fixtures: Vec<Fixture> = fs::read_dir(&dir)
+        .unwrap_or_else(|err| panic!("oracle fixtures directory {}: {err}", dir.display()))
+        .map(|entry| entry.expect("oracle fixture entry").path())
+        .filter_map(|path| {
+            let bytes = || fs::read(&path).unwrap();
+            let payload = match path.extension().and_then(|ext| ext.to_str()) {
+                Some("payload") => {
+                    CelestiaPayload::from_bytes(&bytes()).map_err(|err| err.to_string())
+                }
+                Some("json") => serde_json::from_slice(&bytes()).map_err(|err| err.to_string()),
+                _ => return None,
+            }
+            .unwrap_or_else(|err| panic!("invalid fixture {}: {err}", path.display()));
+
+            let name = path.file_stem().unwrap().to_string_lossy().into_owned();
+            let (height, hint) = parse_hint(&name).unwrap_or_else(|| {
+                panic!("fixture {} is not named after its hint", path.display())
+            });
+
+            Some(Fixture {
+                name,
+                height,
+                hint,
+                payload,
+            })
+        })
+        .collect();
+
+    assert!(
+        !fixtures.is_empty(),
+        "no payload fixtures in {}",
+        dir.display()
+    );
+
+    fixtures.sort_by(|a, b| a.name.cmp(&b.name));
+    fixtures
+}
+
+/// Returns the namespace of the blob, which its proven shares start with.
+pub fn blob_namespace(payload: &OraclePayload) -> Namespace {
+    let share = &payload.proof.share_proof.shares()[0];
+    Namespace::new(share[0], &share[1..29]).expect("fixture share namespace")
+}
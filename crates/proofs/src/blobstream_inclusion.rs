@@ -1,154 +1,1373 @@
-use alloc::{boxed::Box, vec::Vec};
-use alloy_primitives::{keccak256, Address, Bytes, B256};
+use alloc::{collections::BTreeSet, vec::Vec};
+use alloy_primitives::{hex, Address, Bytes, B256, U256};
 use alloy_provider::{Provider, RootProvider};
-use alloy_rpc_types_eth::{BlockNumberOrTag, Filter, FilterBlockOption, FilterSet};
-use alloy_sol_types::SolEvent;
+use alloy_rpc_types_eth::{BlockId, BlockNumberOrTag, Filter, FilterBlockOption, FilterSet};
+use alloy_transport::TransportError;
+use anyhow::Context;
 use celestia_rpc::{blobstream::BlobstreamClient, Client, HeaderClient, ShareClient};
-use celestia_types::Blob;
+use celestia_types::{
+    hash::Hash, nmt::Namespace, row_namespace_data::NamespaceData, AppVersion, Blob, Commitment,
+    DataAvailabilityHeader, ExtendedHeader, ShareProof,
+};
+use core::{future::IntoFuture, num::NonZeroU64, time::Duration};
 use hana_blobstream::blobstream::{
-    calculate_mapping_slot, encode_data_root_tuple, verify_data_commitment_storage,
-    BlobstreamProof, SP1Blobstream, SP1BlobstreamDataCommitmentStored, DATA_COMMITMENTS_SLOT,
+    calculate_mapping_slot, BlobstreamProof, DataRootInclusionProof, SP1Blobstream,
+    SP1BlobstreamDataCommitmentStored,
 };
-use tracing::info;
+use std::collections::HashMap;
+use tracing::{debug, info, warn};
+
+use crate::{contract::BlobstreamContract, rate_limit::RateLimiter};
+
+pub use hana_blobstream::{blobstream::ods_share_range, error::InvalidShareRange};
 
 // Geth has a default of 5000 block limit for filters
-const FILTER_BLOCK_RANGE: u64 = 5000;
+const FILTER_BLOCK_RANGE: NonZeroU64 = match NonZeroU64::new(5000) {
+    Some(window) => window,
+    None => unreachable!(),
+};
+
+/// JSON-RPC error code used by providers such as Infura when a query exceeds their limits. They
+/// also return it for rate limits, so it only marks a result limit along with a message fragment
+/// of [LIMIT_EXCEEDED_RESULT_MESSAGES].
+const LIMIT_EXCEEDED_CODE: i64 = -32005;
+
+/// Fragments of the error messages providers return when a `get_logs` query matches too many
+/// logs, lowercased
+const RESULT_LIMIT_MESSAGES: [&str; 6] = [
+    "query returned more than",
+    "log response size exceeded",
+    "too many results",
+    "exceeds max results",
+    "response size is larger than",
+    "block range",
+];
+
+/// Fragments of the error messages that mark a result limit only in errors with
+/// [LIMIT_EXCEEDED_CODE], lowercased
+const LIMIT_EXCEEDED_RESULT_MESSAGES: [&str; 2] = ["results", "range"];
+
+/// JSON-RPC error code returned for methods the node does not implement
+const METHOD_NOT_FOUND_CODE: i64 = -32601;
+
+/// Fragments of the error messages nodes return for methods they do not serve, lowercased
+const METHOD_UNSUPPORTED_MESSAGES: [&str; 4] = [
+    "method not found",
+    "not supported",
+    "unsupported method",
+    "not available",
+];
+
+/// Number of events an adaptive scan aims to match with each `get_logs` query
+const ADAPTIVE_TARGET_EVENTS: f64 = 50.0;
+
+/// Smallest window an adaptive scan shrinks to
+const ADAPTIVE_MIN_WINDOW: u64 = 100;
+
+/// Largest window an adaptive scan grows to
+const ADAPTIVE_MAX_WINDOW: u64 = 100_000;
+
+/// Weight of the latest window in the moving average of events per block
+const ADAPTIVE_SMOOTHING: f64 = 0.5;
+
+/// Default time allowed for a single Celestia or L1 RPC call
+pub const DEFAULT_RPC_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How the scan for Blobstream data commitment events walks the L1 chain
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ScanStrategy {
+    /// Walk back from the latest block in `get_logs` windows
+    #[default]
+    Backward,
+    /// Bisect the L1 blocks on the contract's `latestBlock` to find the block the covering
+    /// commitment was stored in, then query the logs of that block only. Takes a logarithmic
+    /// number of calls but needs an L1 node serving historical state. Suits chains with fast
+    /// blocks such as Arbitrum, where walking back in windows is slow.
+    Bisection,
+}
+
+/// Configuration of the scan for Blobstream data commitment events
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ScanConfig {
+    /// The L1 block the Blobstream contract was deployed at. No event can be found below it, so
+    /// the scan stops there instead of walking back to genesis.
+    pub deployment_block: Option<u64>,
+    /// Number of L1 blocks below the latest one to start the scan at. Events in more deeply
+    /// confirmed blocks are less likely to be reorged out, invalidating the proofs built against
+    /// them, but the newest commitments are only found once they are this deep. Defaults to 0,
+    /// scanning from the latest block.
+    pub l1_confirmations: u64,
+    /// Whether to size each `get_logs` window from the event density seen so far, instead of
+    /// walking back in fixed windows. Cuts the number of queries at the cost of a
+    /// non-deterministic query pattern.
+    pub adaptive: bool,
+    /// The strategy used to find the event
+    pub strategy: ScanStrategy,
+    /// Number of L1 blocks in each `get_logs` window of a backward scan, defaults to 5000
+    pub window: Option<NonZeroU64>,
+    /// Maximum number of L1 blocks below the head a scan traverses before giving up, on top of
+    /// the deployment block floor. Unbounded if unset.
+    pub max_scan_blocks: Option<u64>,
+}
+
+/// The range of L1 blocks a scan for data commitment events traverses
+#[derive(Debug, Clone, Copy)]
+struct ScanBounds {
+    /// The lowest L1 block scanned
+    floor: u64,
+    /// The highest L1 block scanned
+    head: u64,
+    /// Whether the floor was raised above the deployment block by [ScanConfig::max_scan_blocks]
+    budget_limited: bool,
+}
+
+impl ScanBounds {
+    /// Returns the error for a scan of these bounds that found no event covering
+    /// `celestia_height`.
+    fn not_found(
+        &self,
+        celestia_height: u64,
+        blobstream_address: Address,
+        max_scan_blocks: Option<u64>,
+    ) -> FindDataCommitmentError {
+        match max_scan_blocks {
+            Some(max_scan_blocks) if self.budget_limited => {
+                FindDataCommitmentError::ScanBudgetExceeded {
+                    celestia_height,
+                    max_scan_blocks,
+                    from_block: self.floor,
+                    to_block: self.head,
+                }
+            }
+            _ => FindDataCommitmentError::NotFound {
+                celestia_height,
+                blobstream_address,
+                from_block: self.floor,
+                to_block: self.head,
+            },
+        }
+    }
+}
+
+/// Sizes the windows of an adaptive scan to match about [ADAPTIVE_TARGET_EVENTS] events each,
+/// from a moving average of the events per block seen in previous windows
+#[derive(Debug, Default)]
+struct AdaptiveWindow {
+    events_per_block: Option<f64>,
+}
+
+impl AdaptiveWindow {
+    /// Records that `events` events were found in `blocks` blocks and returns the next window.
+    fn next_window(&mut self, current: u64, events: usize, blocks: u64) -> u64 {
+        let density = events as f64 / blocks.max(1) as f64;
+        let average = match self.events_per_block {
+            Some(average) => average * (1.0 - ADAPTIVE_SMOOTHING) + density * ADAPTIVE_SMOOTHING,
+            None => density,
+        };
+        self.events_per_block = Some(average);
+
+        let window = if average > 0.0 {
+            (ADAPTIVE_TARGET_EVENTS / average) as u64
+        } else {
+            // Nothing found so far, the range is sparse
+            current.saturating_mul(2)
+        };
+        window.clamp(ADAPTIVE_MIN_WINDOW, ADAPTIVE_MAX_WINDOW)
+    }
+}
+
+/// Configuration of the Blobstream proof fetching
+#[derive(Debug, Clone)]
+pub struct ProofConfig {
+    /// The configuration of the data commitment event scan
+    pub scan: ScanConfig,
+    /// The time allowed for each Celestia or L1 RPC call, including every `get_logs` scan
+    pub rpc_timeout: Duration,
+    /// The Blobstream program verification key the contract must be configured with, if pinned
+    pub expected_vkey: Option<B256>,
+    /// Whether to leave out the storage proof of the data commitment, which then has to be
+    /// trusted by the verifier
+    pub skip_storage_proof: bool,
+    /// Whether to verify the proofs before returning them, like the client does: the blob's
+    /// commitment, then the share, data root tuple and storage proofs. The client verifies them
+    /// again, so a trusted host may skip this to halve the work.
+    pub verify_on_host: bool,
+    /// Limit on the rate of `get_logs` calls made by the scan, which waits for the limiter
+    /// instead of erroring when it is reached. Unlimited if unset.
+    pub l1_rate_limit: Option<RateLimiter>,
+    /// Data commitment events to use for the Celestia heights they cover instead of scanning L1
+    /// for them, e.g. to reproduce a past proof. Each is checked against the commitment the
+    /// contract stored at its proof nonce before use.
+    pub pinned_commitments: Vec<SP1BlobstreamDataCommitmentStored>,
+    /// The L1 block to take the storage proofs at and to start the event scan from, e.g. to
+    /// reproduce a past proof against an archive node. The latest block if unset.
+    pub l1_proof_block: Option<BlockId>,
+}
+
+impl ProofConfig {
+    /// Waits until the [Self::l1_rate_limit] allows another `get_logs` call.
+    async fn throttle_get_logs(&self) {
+        if let Some(limiter) = &self.l1_rate_limit {
+            limiter.acquire().await;
+        }
+    }
+}
+
+impl Default for ProofConfig {
+    fn default() -> Self {
+        Self {
+            scan: ScanConfig::default(),
+            rpc_timeout: DEFAULT_RPC_TIMEOUT,
+            expected_vkey: None,
+            skip_storage_proof: false,
+            verify_on_host: true,
+            l1_rate_limit: None,
+            pinned_commitments: Vec::new(),
+            l1_proof_block: None,
+        }
+    }
+}
+
+/// A Celestia or L1 RPC call that did not complete within the configured timeout.
+///
+/// Returned as is or wrapped in an [anyhow::Error], so callers can downcast to it to decide
+/// whether to retry.
+#[derive(Debug, Clone, Copy, thiserror::Error)]
+#[error("RPC call {call} timed out after {timeout:?}")]
+pub struct RpcTimeout {
+    /// The RPC call that timed out
+    pub call: &'static str,
+    /// The timeout that elapsed
+    pub timeout: Duration,
+}
+
+/// A blob returned by the Celestia node without the index of its first share, which is needed to
+/// prove its shares
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("Celestia blob {} at height {height} has no share index", hex::encode(.commitment.hash()))]
+pub struct MissingBlobIndex {
+    /// The Celestia height of the blob
+    pub height: u64,
+    /// The commitment of the blob
+    pub commitment: Commitment,
+}
+
+/// Awaits the RPC call `fut`, failing with [RpcTimeout] if it takes longer than `timeout`.
+pub async fn with_timeout<F: IntoFuture>(
+    call: &'static str,
+    timeout: Duration,
+    fut: F,
+) -> Result<F::Output, RpcTimeout> {
+    tokio::time::timeout(timeout, fut)
+        .await
+        .map_err(|_| RpcTimeout { call, timeout })
+}
+
+/// The L1 node does not serve `eth_getProof`, which the Blobstream storage proofs are fetched
+/// with
+#[derive(Debug, Clone, thiserror::Error)]
+#[error(
+    "L1 node does not support eth_getProof, needed to prove Blobstream {blobstream_address} storage; point the host at an archive node serving eth_getProof ({reason})"
+)]
+pub struct StorageProofUnsupported {
+    /// The Blobstream contract whose storage was queried
+    pub blobstream_address: Address,
+    /// The error returned by the node
+    pub reason: String,
+}
+
+/// A data commitment event whose Celestia block range the Blobstream contract could not have
+/// committed to, hinting at a spoofed log or a wrong contract address
+#[derive(Debug, Clone, thiserror::Error)]
+#[error(
+    "Blobstream data commitment event at proof nonce {proof_nonce} covers Celestia blocks [{start_block}, {end_block}), which is empty or exceeds the {data_commitment_max} block maximum"
+)]
+pub struct InvalidDataCommitmentRange {
+    /// The proof nonce of the event
+    pub proof_nonce: U256,
+    /// The first Celestia block covered by the event
+    pub start_block: u64,
+    /// The Celestia block the event's range ends before
+    pub end_block: u64,
+    /// The maximum number of blocks the contract commits to at once
+    pub data_commitment_max: u64,
+}
+
+/// Checks that the Celestia block range of `event` is one the Blobstream contract could have
+/// committed to, given its `DATA_COMMITMENT_MAX`.
+pub fn check_data_commitment_range(
+    event: &SP1BlobstreamDataCommitmentStored,
+    data_commitment_max: u64,
+) -> Result<(), InvalidDataCommitmentRange> {
+    if event.start_block < event.end_block
+        && event.end_block - event.start_block <= data_commitment_max
+    {
+        return Ok(());
+    }
+
+    Err(InvalidDataCommitmentRange {
+        proof_nonce: event.proof_nonce,
+        start_block: event.start_block,
+        end_block: event.end_block,
+        data_commitment_max,
+    })
+}
+
+/// A data commitment event whose proof nonce the Blobstream contract has not stored a commitment
+/// at, hinting at a spoofed or future event whose storage slot may still verify against stale
+/// state
+#[derive(Debug, Clone, thiserror::Error)]
+#[error(
+    "Blobstream data commitment event has proof nonce {proof_nonce}, but the contract only stored commitments at nonces [1, {state_proof_nonce})"
+)]
+pub struct InvalidProofNonce {
+    /// The proof nonce of the event
+    pub proof_nonce: U256,
+    /// The proof nonce the contract will store its next commitment at
+    pub state_proof_nonce: U256,
+}
+
+/// Checks that the proof nonce of `event` is one the Blobstream contract stored a commitment at,
+/// given its current `state_proofNonce`.
+pub fn check_proof_nonce(
+    event: &SP1BlobstreamDataCommitmentStored,
+    state_proof_nonce: U256,
+) -> Result<(), InvalidProofNonce> {
+    if event.proof_nonce > U256::ZERO && event.proof_nonce < state_proof_nonce {
+        return Ok(());
+    }
+
+    Err(InvalidProofNonce {
+        proof_nonce: event.proof_nonce,
+        state_proof_nonce,
+    })
+}
+
+/// Errors returned while searching for the Blobstream data commitment covering a Celestia height
+#[derive(Debug, thiserror::Error)]
+pub enum FindDataCommitmentError {
+    /// No data commitment event in the scanned L1 block range covers the Celestia height
+    #[error(
+        "No matching event found for Celestia height {celestia_height} at Blobstream {blobstream_address} in L1 blocks [{from_block}, {to_block}]"
+    )]
+    NotFound {
+        /// The Celestia height searched for
+        celestia_height: u64,
+        /// The Blobstream contract queried
+        blobstream_address: Address,
+        /// The lowest L1 block scanned
+        from_block: u64,
+        /// The highest L1 block scanned
+        to_block: u64,
+    },
+    /// An L1 RPC call failed during the scan
+    #[error("L1 RPC error while scanning for data commitment events: {0}")]
+    Rpc(#[from] TransportError),
+    /// An L1 RPC call timed out during the scan
+    #[error(transparent)]
+    RpcTimeout(#[from] RpcTimeout),
+    /// Reading the contract's `DATA_COMMITMENT_MAX` failed
+    #[error("failed to read the Blobstream DATA_COMMITMENT_MAX: {0}")]
+    DataCommitmentMax(alloy_contract::Error),
+    /// The covering event has a range the contract could not have committed to
+    #[error(transparent)]
+    InvalidRange(#[from] InvalidDataCommitmentRange),
+    /// Reading the commitment the contract stored at a pinned event's proof nonce failed
+    #[error("failed to read the Blobstream state_dataCommitments: {0}")]
+    StoredDataCommitment(alloy_contract::Error),
+    /// A pinned event's data commitment is not the one the contract stored at its proof nonce
+    #[error(
+        "Pinned Blobstream event at proof nonce {proof_nonce} has data commitment {pinned}, but the contract stored {stored}"
+    )]
+    PinnedMismatch {
+        /// The proof nonce of the pinned event
+        proof_nonce: U256,
+        /// The data commitment of the pinned event
+        pinned: B256,
+        /// The data commitment stored by the contract at the proof nonce
+        stored: B256,
+    },
+    /// The L1 node does not know the block proofs are pinned to
+    #[error("L1 proof block {0} not found")]
+    ProofBlockNotFound(BlockId),
+    /// The scan traversed the maximum number of L1 blocks without finding the covering event
+    #[error(
+        "No matching event found for Celestia height {celestia_height} within the {max_scan_blocks} block scan budget, covering L1 blocks [{from_block}, {to_block}]"
+    )]
+    ScanBudgetExceeded {
+        /// The Celestia height searched for
+        celestia_height: u64,
+        /// The maximum number of L1 blocks a scan traverses
+        max_scan_blocks: u64,
+        /// The lowest L1 block scanned
+        from_block: u64,
+        /// The highest L1 block scanned
+        to_block: u64,
+    },
+}
+
+/// The cost of a search for a Blobstream data commitment event
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ScanStats {
+    /// Number of L1 blocks whose logs were fetched
+    pub blocks_scanned: u64,
+    /// Number of `get_logs` calls made, including those rejected for matching too many logs
+    pub get_logs_calls: u64,
+    /// Number of `get_logs` windows whose logs were fetched
+    pub windows: u64,
+    /// Number of Blobstream contract calls made, by a bisection scan or to check the event found
+    pub contract_calls: u64,
+}
+
+impl ScanStats {
+    /// Records a `get_logs` window of L1 blocks `[start, end]` whose logs were fetched.
+    fn record_window(&mut self, start: u64, end: u64) {
+        self.blocks_scanned += end - start + 1;
+        self.windows += 1;
+    }
+}
 
 /// Find the data commitment  that contains the given Celestia height by parsing event logs
-pub async fn find_data_commitment(
+pub async fn find_data_commitment<C: BlobstreamContract>(
     celestia_height: u64,
-    blobstream_address: Address,
+    contract: &C,
     eth_provider: &RootProvider,
-) -> Result<SP1BlobstreamDataCommitmentStored, Box<dyn core::error::Error>> {
-    let eth_block_height = eth_provider.get_block_number().await?;
-    // Calculate event signature manually for reliability
-    let event_signature = "DataCommitmentStored(uint256,uint64,uint64,bytes32)";
-    let event_selector = keccak256(event_signature.as_bytes());
-    let topic0: FilterSet<B256> = vec![event_selector.into()].into();
-
-    // Start from the given Ethereum block height and scan backwards
-    let mut end = eth_block_height;
-    let mut start = if end > FILTER_BLOCK_RANGE {
-        end - FILTER_BLOCK_RANGE
-    } else {
-        0
+    config: &ProofConfig,
+) -> Result<SP1BlobstreamDataCommitmentStored, FindDataCommitmentError> {
+    find_data_commitment_with_stats(
+        celestia_height,
+        contract,
+        eth_provider,
+        config,
+        &mut ScanStats::default(),
+    )
+    .await
+}
+
+/// Like [find_data_commitment], recording the cost of the search in `stats`, including when it
+/// fails.
+///
+/// A pinned event covering the height is used instead of scanning L1. The range of the event
+/// used is checked against the contract's `DATA_COMMITMENT_MAX`, to reject events the contract
+/// could not have emitted.
+pub async fn find_data_commitment_with_stats<C: BlobstreamContract>(
+    celestia_height: u64,
+    contract: &C,
+    eth_provider: &RootProvider,
+    config: &ProofConfig,
+    stats: &mut ScanStats,
+) -> Result<SP1BlobstreamDataCommitmentStored, FindDataCommitmentError> {
+    let event = match config
+        .pinned_commitments
+        .iter()
+        .find(|event| event.start_block <= celestia_height && celestia_height < event.end_block)
+    {
+        Some(event) => check_pinned_commitment(event, contract, eth_provider, config, stats)
+            .await?
+            .clone(),
+        None => {
+            scan_data_commitment(celestia_height, contract, eth_provider, config, stats).await?
+        }
     };
 
-    loop {
-        // Create filter for DataCommitmentStored events
-        let filter = Filter {
-            block_option: FilterBlockOption::Range {
-                from_block: Some(BlockNumberOrTag::Number(start.into())),
-                to_block: Some(BlockNumberOrTag::Number(end.into())),
-            },
-            address: vec![blobstream_address].into(),
-            topics: [
-                topic0.clone(),
-                Default::default(),
-                Default::default(),
-                Default::default(),
-            ],
-        };
+    let data_commitment_max =
+        read_data_commitment_max(contract, eth_provider, config, stats).await?;
+    check_data_commitment_range(&event, data_commitment_max)?;
+
+    Ok(event)
+}
+
+/// Finds the data commitments covering each of `celestia_heights` with a single backward scan
+/// of L1, instead of one scan per height, recording the cost of the search in `stats`.
+///
+/// Heights covered by a pinned event are resolved to it first. Each `get_logs` window is matched
+/// against every height not resolved yet, and the scan stops once all of them are. Errors if the
+/// scan reaches the deployment block with a height left unresolved. The ranges of the events
+/// found are checked like in [find_data_commitment_with_stats].
+pub async fn find_data_commitments<C: BlobstreamContract>(
+    celestia_heights: &[u64],
+    contract: &C,
+    eth_provider: &RootProvider,
+    config: &ProofConfig,
+    stats: &mut ScanStats,
+) -> Result<HashMap<u64, SP1BlobstreamDataCommitmentStored>, FindDataCommitmentError> {
+    let mut pending: BTreeSet<u64> = celestia_heights.iter().copied().collect();
+    let mut resolved = HashMap::with_capacity(pending.len());
+    if pending.is_empty() {
+        return Ok(resolved);
+    }
+
+    for event in &config.pinned_commitments {
+        if event.start_block >= event.end_block {
+            continue;
+        }
+        let covered: Vec<u64> = pending
+            .range(event.start_block..event.end_block)
+            .copied()
+            .collect();
+        if covered.is_empty() {
+            continue;
+        }
+
+        check_pinned_commitment(event, contract, eth_provider, config, stats).await?;
+        for height in covered {
+            pending.remove(&height);
+            resolved.insert(height, event.clone());
+        }
+    }
 
-        // Get logs using the client reference
-        let logs = eth_provider.get_logs(&filter).await?;
-
-        // Parse logs using the generated event type
-        for log in logs {
-            // Try to decode the log using SP1Blobstream's generated event decoder
-            if let Ok(event) =
-                SP1Blobstream::DataCommitmentStored::decode_log(&log.clone().into(), true)
-            {
-                // Check if this event contains the celestia_height
-                if event.startBlock <= celestia_height && celestia_height < event.endBlock {
-                    let stored_event = SP1BlobstreamDataCommitmentStored {
-                        proof_nonce: event.proofNonce,
-                        start_block: event.startBlock,
-                        end_block: event.endBlock,
-                        data_commitment: event.dataCommitment,
-                    };
-
-                    info!(
-                        "Found Data Root submission event block_number={} proof_nonce={} start={} end={}",
-                        log.clone().block_number.unwrap(),
-                        stored_event.proof_nonce,
-                        stored_event.start_block,
-                        stored_event.end_block
-                    );
-
-                    return Ok(stored_event);
+    if let Some(&highest) = pending.last() {
+        let bounds = scan_bounds(highest, eth_provider, config).await?;
+        scan_backward(contract, eth_provider, bounds, config, stats, |events| {
+            for event in events {
+                // An empty or inverted range covers nothing, and is rejected below if matched
+                if event.start_block >= event.end_block {
+                    continue;
+                }
+
+                let covered: Vec<u64> = pending
+                    .range(event.start_block..event.end_block)
+                    .copied()
+                    .collect();
+                for height in covered {
+                    pending.remove(&height);
+                    resolved.insert(height, event.clone());
                 }
             }
+            pending.is_empty()
+        })
+        .await?;
+
+        if let Some(&celestia_height) = pending.last() {
+            return Err(bounds.not_found(
+                celestia_height,
+                contract.address(),
+                config.scan.max_scan_blocks,
+            ));
+        }
+    }
+
+    let data_commitment_max =
+        read_data_commitment_max(contract, eth_provider, config, stats).await?;
+    for event in resolved.values() {
+        check_data_commitment_range(event, data_commitment_max)?;
+    }
+
+    Ok(resolved)
+}
+
+/// Checks that the data commitment of the pinned `event` is the one the contract stored at its
+/// proof nonce, returning the event.
+async fn check_pinned_commitment<'a, C: BlobstreamContract>(
+    event: &'a SP1BlobstreamDataCommitmentStored,
+    contract: &C,
+    eth_provider: &RootProvider,
+    config: &ProofConfig,
+    stats: &mut ScanStats,
+) -> Result<&'a SP1BlobstreamDataCommitmentStored, FindDataCommitmentError> {
+    stats.contract_calls += 1;
+    let stored = match with_timeout(
+        "state_dataCommitments",
+        config.rpc_timeout,
+        contract.data_commitment(eth_provider, event.proof_nonce),
+    )
+    .await?
+    {
+        Ok(stored) => stored,
+        Err(alloy_contract::Error::TransportError(err)) => return Err(err.into()),
+        Err(err) => return Err(FindDataCommitmentError::StoredDataCommitment(err)),
+    };
+
+    if stored != event.data_commitment {
+        return Err(FindDataCommitmentError::PinnedMismatch {
+            proof_nonce: event.proof_nonce,
+            pinned: event.data_commitment,
+            stored,
+        });
+    }
+
+    info!(
+        "Using pinned data commitment event proof_nonce={} start={} end={}",
+        event.proof_nonce, event.start_block, event.end_block
+    );
+    Ok(event)
+}
+
+/// Reads the contract's `DATA_COMMITMENT_MAX`.
+async fn read_data_commitment_max<C: BlobstreamContract>(
+    contract: &C,
+    eth_provider: &RootProvider,
+    config: &ProofConfig,
+    stats: &mut ScanStats,
+) -> Result<u64, FindDataCommitmentError> {
+    stats.contract_calls += 1;
+    match with_timeout(
+        "DATA_COMMITMENT_MAX",
+        config.rpc_timeout,
+        contract.data_commitment_max(eth_provider),
+    )
+    .await?
+    {
+        Ok(max) => Ok(max),
+        Err(alloy_contract::Error::TransportError(err)) => Err(err.into()),
+        Err(err) => Err(FindDataCommitmentError::DataCommitmentMax(err)),
+    }
+}
+
+/// Returns the number and hash of the [ProofConfig::l1_proof_block], if one is set.
+pub async fn resolve_l1_proof_block(
+    eth_provider: &RootProvider,
+    config: &ProofConfig,
+) -> Result<Option<(u64, B256)>, FindDataCommitmentError> {
+    let Some(block_id) = config.l1_proof_block else {
+        return Ok(None);
+    };
+
+    let block = match block_id {
+        BlockId::Hash(hash) => {
+            with_timeout(
+                "eth_getBlockByHash",
+                config.rpc_timeout,
+                eth_provider.get_block_by_hash(hash.block_hash),
+            )
+            .await??
         }
+        BlockId::Number(number) => {
+            with_timeout(
+                "eth_getBlockByNumber",
+                config.rpc_timeout,
+                eth_provider.get_block_by_number(number),
+            )
+            .await??
+        }
+    }
+    .ok_or(FindDataCommitmentError::ProofBlockNotFound(block_id))?;
+
+    Ok(Some((block.header.number, block.header.hash)))
+}
 
-        // If we've reached the beginning of the chain, stop
-        if start == 0 {
-            return Err("No matching event found for the given Celestia height".into());
+/// Returns the lowest and highest L1 blocks to scan for the data commitment covering
+/// `celestia_height`: the deployment block, or genesis if unknown, raised to stay within the
+/// scan budget, and the confirmed head, or the L1 proof block if one is pinned.
+async fn scan_bounds(
+    celestia_height: u64,
+    eth_provider: &RootProvider,
+    config: &ProofConfig,
+) -> Result<ScanBounds, FindDataCommitmentError> {
+    let eth_block_height = match resolve_l1_proof_block(eth_provider, config).await? {
+        Some((number, _)) => number,
+        None => with_timeout(
+            "eth_blockNumber",
+            config.rpc_timeout,
+            eth_provider.get_block_number(),
+        )
+        .await??
+        .saturating_sub(config.scan.l1_confirmations),
+    };
+
+    let floor = match config.scan.deployment_block {
+        Some(deployment_block) => deployment_block.min(eth_block_height),
+        None if config.scan.max_scan_blocks.is_some() => 0,
+        None => {
+            warn!(
+                "Blobstream deployment block unknown, scanning back to genesis for Celestia height {} may be expensive",
+                celestia_height
+            );
+            0
+        }
+    };
+
+    let budget_floor = config
+        .scan
+        .max_scan_blocks
+        .map(|max| eth_block_height.saturating_sub(max.saturating_sub(1)))
+        .unwrap_or(0);
+
+    Ok(ScanBounds {
+        floor: floor.max(budget_floor),
+        head: eth_block_height,
+        budget_limited: budget_floor > floor,
+    })
+}
+
+/// Scans L1 for the data commitment event covering `celestia_height` with the configured
+/// strategy.
+async fn scan_data_commitment<C: BlobstreamContract>(
+    celestia_height: u64,
+    contract: &C,
+    eth_provider: &RootProvider,
+    config: &ProofConfig,
+    stats: &mut ScanStats,
+) -> Result<SP1BlobstreamDataCommitmentStored, FindDataCommitmentError> {
+    let bounds = scan_bounds(celestia_height, eth_provider, config).await?;
+
+    if config.scan.strategy == ScanStrategy::Bisection {
+        return bisect_data_commitment(
+            celestia_height,
+            contract,
+            eth_provider,
+            bounds,
+            config,
+            stats,
+        )
+        .await;
+    }
+
+    let mut found = None;
+    scan_backward(contract, eth_provider, bounds, config, stats, |events| {
+        found = find_covering_event(events, celestia_height);
+        found.is_some()
+    })
+    .await?;
+
+    found.ok_or_else(|| {
+        bounds.not_found(
+            celestia_height,
+            contract.address(),
+            config.scan.max_scan_blocks,
+        )
+    })
+}
+
+/// Scans the L1 blocks in `[floor, head]` of `bounds` for data commitment events in `get_logs`
+/// windows, from `head` backwards, handing each window's events to `visit` until it returns
+/// `true`.
+///
+/// Returns whether `visit` stopped the scan before it reached `floor`.
+async fn scan_backward<C: BlobstreamContract>(
+    contract: &C,
+    eth_provider: &RootProvider,
+    bounds: ScanBounds,
+    config: &ProofConfig,
+    stats: &mut ScanStats,
+    mut visit: impl FnMut(Vec<SP1BlobstreamDataCommitmentStored>) -> bool,
+) -> Result<bool, FindDataCommitmentError> {
+    let floor = bounds.floor;
+    // Start from the confirmed Ethereum block height and scan backwards
+    let mut end = bounds.head;
+    let mut window = config.scan.window.unwrap_or(FILTER_BLOCK_RANGE).get();
+    let mut adaptive_window = AdaptiveWindow::default();
+
+    loop {
+        let start = end.saturating_sub(window).max(floor);
+        let events = fetch_data_commitments(
+            contract,
+            eth_provider,
+            start,
+            end,
+            &mut window,
+            config,
+            stats,
+        )
+        .await?;
+
+        if config.scan.adaptive {
+            window = adaptive_window.next_window(window, events.len(), end - start + 1);
+        }
+
+        if visit(events) {
+            return Ok(true);
+        }
+
+        // If we've reached the deployment block or the beginning of the chain, stop
+        if start == floor {
+            return Ok(false);
         }
 
         // Move to the previous batch
         end = start;
-        start = if end > FILTER_BLOCK_RANGE {
-            end - FILTER_BLOCK_RANGE
-        } else {
-            0
+    }
+}
+
+/// Returns every data commitment event emitted by `contract` in L1 blocks `[from_block,
+/// to_block]`, in block order, recording the cost of the scan in `stats`.
+///
+/// The range is fetched in `get_logs` windows of the configured size, so callers can ingest the
+/// events of a whole range at once, e.g. to fill a cache. Logs that do not decode as a data
+/// commitment event are skipped.
+pub async fn scan_data_commitments<C: BlobstreamContract>(
+    from_block: u64,
+    to_block: u64,
+    contract: &C,
+    eth_provider: &RootProvider,
+    config: &ProofConfig,
+    stats: &mut ScanStats,
+) -> Result<Vec<SP1BlobstreamDataCommitmentStored>, FindDataCommitmentError> {
+    let mut window = config.scan.window.unwrap_or(FILTER_BLOCK_RANGE).get();
+    fetch_data_commitments(
+        contract,
+        eth_provider,
+        from_block,
+        to_block,
+        &mut window,
+        config,
+        stats,
+    )
+    .await
+}
+
+/// Fetches the data commitment events of L1 blocks `[start, end]` in `get_logs` windows of
+/// `window` blocks, from `start` forwards.
+///
+/// If the provider caps the number of results, the window is halved and the sub-range retried,
+/// and stays halved for the caller's next scan.
+async fn fetch_data_commitments<C: BlobstreamContract>(
+    contract: &C,
+    eth_provider: &RootProvider,
+    start: u64,
+    end: u64,
+    window: &mut u64,
+    config: &ProofConfig,
+    stats: &mut ScanStats,
+) -> Result<Vec<SP1BlobstreamDataCommitmentStored>, FindDataCommitmentError> {
+    let mut events = Vec::new();
+    if start > end {
+        return Ok(events);
+    }
+
+    let mut from = start;
+    loop {
+        let to = from.saturating_add(*window).min(end);
+        stats.get_logs_calls += 1;
+        config.throttle_get_logs().await;
+
+        let logs = match with_timeout(
+            "eth_getLogs",
+            config.rpc_timeout,
+            eth_provider.get_logs(&data_commitment_filter(contract, from, to)),
+        )
+        .await?
+        {
+            Ok(logs) => logs,
+            Err(err) if is_result_limit_error(&err) && to - from > 1 => {
+                *window = (to - from) / 2;
+                warn!(
+                    "get_logs for L1 blocks [{}, {}] hit the provider result limit, retrying with a {} block window",
+                    from, to, window
+                );
+                continue;
+            }
+            Err(err) => return Err(err.into()),
         };
+        stats.record_window(from, to);
+
+        events.extend(
+            logs.iter()
+                .filter_map(|log| contract.decode_data_commitment(log)),
+        );
+
+        if to == end {
+            return Ok(events);
+        }
+        from = to + 1;
+    }
+}
+
+/// Finds the data commitment covering `celestia_height` by bisecting the L1 blocks in
+/// `[floor, head]` of `bounds` on the Blobstream `latestBlock`.
+///
+/// `latestBlock` only grows, and the commitment covering the height is stored in the first L1
+/// block where it exceeds the height, so only that block's logs are queried. If that block is
+/// below a budget-limited floor, the floor's logs hold no covering event.
+async fn bisect_data_commitment<C: BlobstreamContract>(
+    celestia_height: u64,
+    contract: &C,
+    eth_provider: &RootProvider,
+    bounds: ScanBounds,
+    config: &ProofConfig,
+    stats: &mut ScanStats,
+) -> Result<SP1BlobstreamDataCommitmentStored, FindDataCommitmentError> {
+    let ScanBounds { floor, head, .. } = bounds;
+    let not_found = || {
+        bounds.not_found(
+            celestia_height,
+            contract.address(),
+            config.scan.max_scan_blocks,
+        )
+    };
+
+    stats.contract_calls += 1;
+    if latest_block_at(contract, eth_provider, head, config).await? <= celestia_height {
+        return Err(not_found());
+    }
+
+    // `latestBlock` exceeds the height at `high` throughout
+    let (mut low, mut high) = (floor, head);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        stats.contract_calls += 1;
+        if latest_block_at(contract, eth_provider, mid, config).await? > celestia_height {
+            high = mid;
+        } else {
+            low = mid + 1;
+        }
+    }
+    debug!(
+        "Blobstream latestBlock first exceeds Celestia height {} at L1 block {}",
+        celestia_height, high
+    );
+
+    let events = scan_data_commitments(high, high, contract, eth_provider, config, stats).await?;
+
+    find_covering_event(events, celestia_height).ok_or_else(not_found)
+}
+
+/// Reads the Blobstream `latestBlock` as of L1 block `block`. Reads from before the contract
+/// was deployed return no data and count as 0.
+async fn latest_block_at<C: BlobstreamContract>(
+    contract: &C,
+    eth_provider: &RootProvider,
+    block: u64,
+    config: &ProofConfig,
+) -> Result<u64, FindDataCommitmentError> {
+    match with_timeout(
+        "latestBlock",
+        config.rpc_timeout,
+        contract.latest_block(eth_provider, block),
+    )
+    .await?
+    {
+        Ok(latest) => Ok(latest),
+        Err(alloy_contract::Error::TransportError(err)) => Err(err.into()),
+        Err(_) => Ok(0),
+    }
+}
+
+/// Builds the filter for the Blobstream data commitment events in L1 blocks `[start, end]`.
+pub fn data_commitment_filter<C: BlobstreamContract>(contract: &C, start: u64, end: u64) -> Filter {
+    Filter {
+        block_option: FilterBlockOption::Range {
+            from_block: Some(BlockNumberOrTag::Number(start.into())),
+            to_block: Some(BlockNumberOrTag::Number(end.into())),
+        },
+        ..data_commitment_events_filter(contract)
+    }
+}
+
+/// Returns the filter matching every data commitment event of `contract`, without a block range,
+/// as used to subscribe to new events.
+pub fn data_commitment_events_filter<C: BlobstreamContract>(contract: &C) -> Filter {
+    let topic0: FilterSet<B256> = vec![contract.data_commitment_topic()].into();
+
+    Filter {
+        block_option: FilterBlockOption::Range {
+            from_block: None,
+            to_block: None,
+        },
+        address: vec![contract.address()].into(),
+        topics: [
+            topic0,
+            Default::default(),
+            Default::default(),
+            Default::default(),
+        ],
+    }
+}
+
+/// Returns the data commitment event among `events` whose range covers `celestia_height`.
+fn find_covering_event(
+    events: Vec<SP1BlobstreamDataCommitmentStored>,
+    celestia_height: u64,
+) -> Option<SP1BlobstreamDataCommitmentStored> {
+    for stored_event in events {
+        // Check if this event contains the celestia_height
+        if stored_event.start_block <= celestia_height && celestia_height < stored_event.end_block {
+            info!(
+                "Found Data Root submission event block_number={:?} proof_nonce={} start={} end={}",
+                stored_event.l1_block_number,
+                stored_event.proof_nonce,
+                stored_event.start_block,
+                stored_event.end_block
+            );
+
+            return Some(stored_event);
+        }
     }
+
+    None
+}
+
+/// Returns whether `err` is a provider rejecting a `get_logs` query for matching too many logs,
+/// rather than for e.g. exceeding a rate limit.
+fn is_result_limit_error(err: &TransportError) -> bool {
+    let Some(payload) = err.as_error_resp() else {
+        return false;
+    };
+
+    let message = payload.message.to_lowercase();
+    let mentions = |fragments: &[&str]| fragments.iter().any(|fragment| message.contains(fragment));
+
+    mentions(&RESULT_LIMIT_MESSAGES)
+        || (payload.code == LIMIT_EXCEEDED_CODE && mentions(&LIMIT_EXCEEDED_RESULT_MESSAGES))
 }
 
 /// Fetches a `BlobstreamProof` for the given blob, height, and blobstream contract address
-pub async fn get_blobstream_proof(
+pub async fn get_blobstream_proof<C: BlobstreamContract>(
     celestia_node: &Client,
     l1_provider: &RootProvider,
     height: u64,
     blob: Blob,
-    blobstream_address: Address,
+    contract: &C,
+    config: &ProofConfig,
 ) -> Result<BlobstreamProof, anyhow::Error> {
+    let index = blob_index(&blob, height)?;
+
     // Fetch the block's data root
-    let header = celestia_node.header_get_by_height(height).await?;
+    let header = with_timeout(
+        "header.GetByHeight",
+        config.rpc_timeout,
+        celestia_node.header_get_by_height(height),
+    )
+    .await??;
+
+    // The event scan only touches L1 and the share range only touches Celestia, so both can be
+    // in flight at once.
+    let (event, share_proof) = tokio::join!(
+        find_data_commitment(height, contract, l1_provider, config),
+        get_share_proof(celestia_node, &header, &blob, index, config),
+    );
+    let event = event?;
+    let share_proof = share_proof?;
+
+    let inclusion_proof =
+        get_data_root_inclusion_proof(celestia_node, l1_provider, height, contract, &event, config)
+            .await?;
+
+    let proof = assemble_blobstream_proof(header.dah.hash(), share_proof, inclusion_proof);
+    verify_blob_proof(&proof, &header, &blob, contract, config)?;
+
+    Ok(proof)
+}
+
+/// Fetches a `BlobstreamProof` for the given blob against the already fetched header of its
+/// block and an already discovered Blobstream data commitment event covering its height.
+///
+/// Callers fetching many blobs at the same height or within the same commitment range can fetch
+/// the header and discover the event once with [find_data_commitment], and reuse them, skipping
+/// the header fetches and the `get_logs` scans.
+pub async fn get_blobstream_proof_with_event<C: BlobstreamContract>(
+    celestia_node: &Client,
+    l1_provider: &RootProvider,
+    header: &ExtendedHeader,
+    blob: Blob,
+    contract: &C,
+    event: SP1BlobstreamDataCommitmentStored,
+    config: &ProofConfig,
+) -> Result<BlobstreamProof, anyhow::Error> {
+    let height = header.height().value();
+    let index = blob_index(&blob, height)?;
 
     let data_root = header.dah.hash();
 
-    let eds_row_roots = header.dah.row_roots();
-    let eds_size: u64 = eds_row_roots.len().try_into().unwrap();
-    let ods_size: u64 = eds_size / 2;
+    // With the header and event known, the share proof and the data root inclusion proof are
+    // independent of each other.
+    let (share_proof, inclusion_proof) = tokio::join!(
+        get_share_proof(celestia_node, header, &blob, index, config),
+        get_data_root_inclusion_proof(celestia_node, l1_provider, height, contract, &event, config),
+    );
 
-    let first_row_index: u64 = blob.index.unwrap() / eds_size;
-    let start_index = blob.index.unwrap() - (first_row_index * ods_size);
-    let end_index = start_index + blob.shares_len() as u64;
+    let proof = assemble_blobstream_proof(data_root, share_proof?, inclusion_proof?);
+    verify_blob_proof(&proof, header, &blob, contract, config)?;
 
-    let share_proof = celestia_node
-        .share_get_range(&header, start_index, end_index)
-        .await
-        .expect("Failed getting share proof")
-        .proof;
+    Ok(proof)
+}
 
-    // validate the proof before placing it on the KV store
-    share_proof
-        .verify(data_root)
-        .expect("failed to verify share proof against data root");
+/// Fetches the proof of the shares spanned by `blob`, whose first share is at `index`, against
+/// the header's data root.
+async fn get_share_proof(
+    celestia_node: &Client,
+    header: &ExtendedHeader,
+    blob: &Blob,
+    index: u64,
+    config: &ProofConfig,
+) -> Result<ShareProof, anyhow::Error> {
+    let eds_size = header.dah.row_roots().len() as u64;
+    let range = ods_share_range(index, eds_size, blob.shares_len() as u64)?;
 
-    let event = find_data_commitment(height, blobstream_address, l1_provider)
-        .await
-        .unwrap();
+    let share_proof = with_timeout(
+        "share.GetRange",
+        config.rpc_timeout,
+        celestia_node.share_get_range(header, range.start, range.end),
+    )
+    .await?
+    .with_context(|| {
+        format!(
+            "fetching the proof of shares {}..{} at height {}",
+            range.start,
+            range.end,
+            header.height().value()
+        )
+    })?
+    .proof;
 
-    let data_root_proof = celestia_node
-        .blobstream_get_data_root_tuple_inclusion_proof(height, event.start_block, event.end_block)
-        .await?;
+    Ok(share_proof)
+}
+
+/// Verifies the assembled proof of `blob` in the block of `header` the way the client does, before
+/// it is placed on the key-value store, unless [ProofConfig::verify_on_host] is unset.
+fn verify_blob_proof<C: BlobstreamContract>(
+    proof: &BlobstreamProof,
+    header: &ExtendedHeader,
+    blob: &Blob,
+    contract: &C,
+    config: &ProofConfig,
+) -> Result<(), anyhow::Error> {
+    if !config.verify_on_host {
+        return Ok(());
+    }
+
+    let height = header.height().value();
+    let app_version = header_app_version(header)?;
+    if config.skip_storage_proof {
+        proof.verify_without_storage_proof(
+            height,
+            &blob.data,
+            blob.namespace,
+            &blob.commitment,
+            app_version,
+        )?;
+    } else {
+        proof.verify(
+            height,
+            &blob.data,
+            blob.namespace,
+            &blob.commitment,
+            app_version,
+            contract.data_commitments_slot(),
+        )?;
+        debug!("Succesfully verified storage proof for Blobstream data commitment");
+    }
+
+    Ok(())
+}
+
+/// Returns the app version the block of `header` was produced under, erroring if it is unknown.
+fn header_app_version(header: &ExtendedHeader) -> Result<AppVersion, anyhow::Error> {
+    let version = header.header.version.app;
+    AppVersion::from_u64(version).ok_or_else(|| {
+        anyhow::anyhow!(
+            "Celestia block at height {} has the unknown app version {version}",
+            header.height().value()
+        )
+    })
+}
 
-    let encoded_data_root_tuple = encode_data_root_tuple(height, &data_root);
+/// Returns the index of the blob's first share, erroring instead of panicking if the node did not
+/// return it.
+fn blob_index(blob: &Blob, height: u64) -> Result<u64, MissingBlobIndex> {
+    blob.index.ok_or(MissingBlobIndex {
+        height,
+        commitment: blob.commitment,
+    })
+}
+
+fn assemble_blobstream_proof(
+    data_root: Hash,
+    share_proof: ShareProof,
+    inclusion_proof: DataRootInclusionProof,
+) -> BlobstreamProof {
+    BlobstreamProof::new(
+        data_root,
+        inclusion_proof.data_commitment,
+        inclusion_proof.data_root_tuple_proof,
+        share_proof,
+        inclusion_proof.proof_nonce,
+        inclusion_proof.storage_root,
+        inclusion_proof.storage_proof,
+    )
+    .with_storage_proof_block(inclusion_proof.storage_proof_block)
+}
+
+/// Fetches the proof that `namespace` holds no data at the given height, along with the
+/// Blobstream inclusion proof of the block's data root.
+///
+/// Errors if the namespace does hold data at the given height.
+pub async fn get_namespace_absence_proof<C: BlobstreamContract>(
+    celestia_node: &Client,
+    l1_provider: &RootProvider,
+    height: u64,
+    namespace: Namespace,
+    contract: &C,
+    config: &ProofConfig,
+) -> Result<
+    (
+        DataAvailabilityHeader,
+        NamespaceData,
+        DataRootInclusionProof,
+    ),
+    anyhow::Error,
+> {
+    let header = with_timeout(
+        "header.GetByHeight",
+        config.rpc_timeout,
+        celestia_node.header_get_by_height(height),
+    )
+    .await??;
+
+    let namespace_data = with_timeout(
+        "share.GetNamespaceData",
+        config.rpc_timeout,
+        celestia_node.share_get_namespace_data(&header, namespace),
+    )
+    .await??;
+
+    if namespace_data.rows.iter().any(|row| !row.shares.is_empty()) {
+        anyhow::bail!("namespace {:?} holds data at height {}", namespace, height);
+    }
+
+    let event = find_data_commitment(height, contract, l1_provider, config).await?;
 
-    data_root_proof
-        .verify(encoded_data_root_tuple, *event.data_commitment.clone())
-        .expect("failed to verify data root tuple inclusion proof");
+    let inclusion_proof =
+        get_data_root_inclusion_proof(celestia_node, l1_provider, height, contract, &event, config)
+            .await?;
+    if config.verify_on_host {
+        let data_root = header.dah.hash();
+        if config.skip_storage_proof {
+            inclusion_proof.verify_without_storage_proof(height, &data_root)?;
+        } else {
+            inclusion_proof.verify(height, &data_root, contract.data_commitments_slot())?;
+        }
+    }
 
-    let slot = calculate_mapping_slot(DATA_COMMITMENTS_SLOT, event.proof_nonce);
+    Ok((header.dah, namespace_data, inclusion_proof))
+}
+
+/// Checks that the Blobstream contract is not frozen, as a frozen contract may hold stale or
+/// invalid commitments, and that it runs the expected program if one is pinned in `config`.
+pub async fn check_blobstream_status(
+    l1_provider: &RootProvider,
+    blobstream_address: Address,
+    config: &ProofConfig,
+) -> Result<(), anyhow::Error> {
+    let blobstream = SP1Blobstream::new(blobstream_address, l1_provider.clone());
+
+    let frozen = with_timeout("frozen", config.rpc_timeout, blobstream.frozen().call())
+        .await??
+        ._0;
+    if frozen {
+        anyhow::bail!("Blobstream contract {} is frozen", blobstream_address);
+    }
+
+    if let Some(expected_vkey) = config.expected_vkey {
+        let vkey = with_timeout(
+            "blobstreamProgramVkey",
+            config.rpc_timeout,
+            blobstream.blobstreamProgramVkey().call(),
+        )
+        .await??
+        ._0;
+        if vkey != expected_vkey {
+            anyhow::bail!(
+                "Blobstream contract {} has program verification key {}, expected {}",
+                blobstream_address,
+                vkey,
+                expected_vkey
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Fetches the proof that the data root of the block at the given height was committed to by the
+/// Blobstream `contract` in the given data commitment event, leaving its verification to the
+/// caller.
+pub async fn get_data_root_inclusion_proof<C: BlobstreamContract>(
+    celestia_node: &Client,
+    l1_provider: &RootProvider,
+    height: u64,
+    contract: &C,
+    event: &SP1BlobstreamDataCommitmentStored,
+    config: &ProofConfig,
+) -> Result<DataRootInclusionProof, anyhow::Error> {
+    contract.check_status(l1_provider, config).await?;
+
+    let state_proof_nonce = with_timeout(
+        "state_proofNonce",
+        config.rpc_timeout,
+        contract.proof_nonce(l1_provider),
+    )
+    .await??;
+    check_proof_nonce(event, state_proof_nonce)?;
+
+    if config.skip_storage_proof {
+        let data_root_proof = with_timeout(
+            "blobstream.GetDataRootTupleInclusionProof",
+            config.rpc_timeout,
+            celestia_node.blobstream_get_data_root_tuple_inclusion_proof(
+                height,
+                event.start_block,
+                event.end_block,
+            ),
+        )
+        .await??;
+
+        return Ok(DataRootInclusionProof {
+            data_commitment: event.data_commitment,
+            data_root_tuple_proof: data_root_proof,
+            proof_nonce: event.proof_nonce,
+            storage_root: B256::ZERO,
+            storage_proof: None,
+            storage_proof_block: None,
+        });
+    }
+
+    let blobstream_address = contract.address();
+    let slot = calculate_mapping_slot(contract.data_commitments_slot(), event.proof_nonce);
 
     let slot_b256 = B256::from_slice(slot.as_slice());
 
-    let proof_response = l1_provider
-        .get_proof(blobstream_address, vec![slot_b256])
-        .await?;
+    // The storage proof is taken at the pinned L1 block if any, or else at the block the event
+    // was observed in, by hash, so a reorg since the event was found fails the proof instead of
+    // proving against another view of L1.
+    let pinned_block = resolve_l1_proof_block(l1_provider, config).await?;
+    let proof_block = match (pinned_block, event.l1_block_hash) {
+        (Some((_, hash)), _) | (None, Some(hash)) => hash,
+        (None, None) => {
+            with_timeout(
+                "eth_getBlockByNumber",
+                config.rpc_timeout,
+                l1_provider.get_block_by_number(BlockNumberOrTag::Latest),
+            )
+            .await??
+            .ok_or_else(|| anyhow::anyhow!("L1 node returned no latest block"))?
+            .header
+            .hash
+        }
+    };
+
+    // The tuple proof comes from Celestia and the storage proof from L1, and both only depend on
+    // the event, so fetch them concurrently.
+    let (data_root_proof, proof_response) = tokio::join!(
+        with_timeout(
+            "blobstream.GetDataRootTupleInclusionProof",
+            config.rpc_timeout,
+            celestia_node.blobstream_get_data_root_tuple_inclusion_proof(
+                height,
+                event.start_block,
+                event.end_block
+            ),
+        ),
+        with_timeout(
+            "eth_getProof",
+            config.rpc_timeout,
+            l1_provider
+                .get_proof(blobstream_address, vec![slot_b256])
+                .block_id(BlockId::hash(proof_block)),
+        ),
+    );
+    let data_root_proof = data_root_proof??;
+    let proof_response = proof_response?.map_err(|err| -> anyhow::Error {
+        if is_method_unsupported_error(&err) {
+            StorageProofUnsupported {
+                blobstream_address,
+                reason: err.to_string(),
+            }
+            .into()
+        } else {
+            err.into()
+        }
+    })?;
 
     let proof_bytes: Vec<Bytes> = proof_response
         .storage_proof
@@ -164,25 +1383,48 @@ pub async fn get_blobstream_proof(
         })
         .collect();
 
-    match verify_data_commitment_storage(
-        proof_response.storage_hash,
-        proof_bytes.clone(),
-        event.proof_nonce,
-        event.data_commitment,
-    ) {
-        Ok(_) => {
-            println!("Succesfully verified storage proof for Blobstream data commitment");
-
-            return Ok(BlobstreamProof::new(
-                data_root,
-                event.data_commitment,
-                data_root_proof,
-                share_proof,
-                event.proof_nonce,
-                proof_response.storage_hash.clone(),
-                proof_bytes,
-            ));
-        }
-        Err(err) => anyhow::bail!("Error verifying storage proof {}", err),
-    }
+    Ok(DataRootInclusionProof {
+        data_commitment: event.data_commitment,
+        data_root_tuple_proof: data_root_proof,
+        proof_nonce: event.proof_nonce,
+        storage_root: proof_response.storage_hash,
+        storage_proof: Some(proof_bytes),
+        storage_proof_block: Some(proof_block),
+    })
+}
+
+/// Reads the data commitment stored by the Blobstream contract for the given proof nonce with
+/// `eth_getStorageAt`, without any proof.
+///
+/// Meant for diagnostics against L1 nodes that do not serve `eth_getProof`: the value is not
+/// proven and must not be used to build oracle payloads.
+pub async fn read_data_commitment_storage<C: BlobstreamContract>(
+    l1_provider: &RootProvider,
+    contract: &C,
+    proof_nonce: U256,
+    config: &ProofConfig,
+) -> Result<B256, anyhow::Error> {
+    let slot = calculate_mapping_slot(contract.data_commitments_slot(), proof_nonce);
+
+    let value = with_timeout(
+        "eth_getStorageAt",
+        config.rpc_timeout,
+        l1_provider.get_storage_at(contract.address(), slot.into()),
+    )
+    .await??;
+
+    Ok(B256::from(value))
+}
+
+/// Returns whether `err` is a node rejecting a call to a method it does not serve.
+fn is_method_unsupported_error(err: &TransportError) -> bool {
+    let Some(payload) = err.as_error_resp() else {
+        return false;
+    };
+
+    let message = payload.message.to_lowercase();
+    payload.code == METHOD_NOT_FOUND_CODE
+        || METHOD_UNSUPPORTED_MESSAGES
+            .iter()
+            .any(|fragment| message.contains(fragment))
 }
@@ -0,0 +1,166 @@
+//! Abstraction over the Blobstream-compatible bridges data commitments are proven against.
+
+use alloy_primitives::{Address, B256, U256};
+use alloy_provider::RootProvider;
+use alloy_rpc_types_eth::Log;
+use alloy_sol_types::SolEvent;
+use core::future::Future;
+use hana_blobstream::blobstream::{
+    SP1Blobstream, SP1BlobstreamDataCommitmentStored, DATA_COMMITMENTS_SLOT,
+};
+
+use crate::blobstream_inclusion::{check_blobstream_status, ProofConfig};
+
+/// A deployed Blobstream-compatible bridge, committing to ranges of Celestia data roots in a
+/// mapping from proof nonce to data commitment, and emitting an event for each commitment.
+pub trait BlobstreamContract: Send + Sync {
+    /// Returns the address the bridge is deployed at.
+    fn address(&self) -> Address;
+
+    /// Returns the storage slot of the mapping from proof nonce to data commitment.
+    fn data_commitments_slot(&self) -> u32;
+
+    /// Returns the topic of the event emitted for each stored data commitment.
+    fn data_commitment_topic(&self) -> B256;
+
+    /// Decodes a data commitment event, returning `None` if the log is not one.
+    fn decode_data_commitment(&self, log: &Log) -> Option<SP1BlobstreamDataCommitmentStored>;
+
+    /// Reads the latest Celestia block committed to by the bridge as of L1 block `block`.
+    fn latest_block(
+        &self,
+        l1_provider: &RootProvider,
+        block: u64,
+    ) -> impl Future<Output = Result<u64, alloy_contract::Error>> + Send;
+
+    /// Reads the maximum number of Celestia blocks the bridge commits to at once.
+    fn data_commitment_max(
+        &self,
+        l1_provider: &RootProvider,
+    ) -> impl Future<Output = Result<u64, alloy_contract::Error>> + Send;
+
+    /// Reads the proof nonce the bridge will store its next data commitment at. Every stored
+    /// commitment has a lower, non-zero nonce.
+    fn proof_nonce(
+        &self,
+        l1_provider: &RootProvider,
+    ) -> impl Future<Output = Result<U256, alloy_contract::Error>> + Send;
+
+    /// Reads the data commitment the bridge stored at `proof_nonce`, zero if none was.
+    fn data_commitment(
+        &self,
+        l1_provider: &RootProvider,
+        proof_nonce: U256,
+    ) -> impl Future<Output = Result<B256, alloy_contract::Error>> + Send;
+
+    /// Checks that the bridge's commitments can be trusted before proving against them.
+    fn check_status(
+        &self,
+        l1_provider: &RootProvider,
+        config: &ProofConfig,
+    ) -> impl Future<Output = Result<(), anyhow::Error>> + Send;
+}
+
+/// The SP1Blobstream contract
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sp1Blobstream {
+    address: Address,
+    data_commitments_slot: u32,
+}
+
+impl Sp1Blobstream {
+    /// Creates the contract deployed at `address`, with the canonical storage layout.
+    pub const fn new(address: Address) -> Self {
+        Self {
+            address,
+            data_commitments_slot: DATA_COMMITMENTS_SLOT,
+        }
+    }
+
+    /// Sets the storage slot of the `state_dataCommitments` mapping, for redeployed or forked
+    /// contracts whose storage layout differs from the canonical deployments.
+    pub const fn with_data_commitments_slot(mut self, data_commitments_slot: u32) -> Self {
+        self.data_commitments_slot = data_commitments_slot;
+        self
+    }
+}
+
+impl BlobstreamContract for Sp1Blobstream {
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn data_commitments_slot(&self) -> u32 {
+        self.data_commitments_slot
+    }
+
+    fn data_commitment_topic(&self) -> B256 {
+        SP1Blobstream::DataCommitmentStored::SIGNATURE_HASH
+    }
+
+    fn decode_data_commitment(&self, log: &Log) -> Option<SP1BlobstreamDataCommitmentStored> {
+        let event =
+            SP1Blobstream::DataCommitmentStored::decode_log(&log.clone().into(), true).ok()?;
+
+        Some(SP1BlobstreamDataCommitmentStored {
+            proof_nonce: event.proofNonce,
+            start_block: event.startBlock,
+            end_block: event.endBlock,
+            data_commitment: event.dataCommitment,
+            l1_block_number: log.block_number,
+            l1_block_hash: log.block_hash,
+        })
+    }
+
+    async fn latest_block(
+        &self,
+        l1_provider: &RootProvider,
+        block: u64,
+    ) -> Result<u64, alloy_contract::Error> {
+        let blobstream = SP1Blobstream::new(self.address, l1_provider.clone());
+
+        Ok(blobstream
+            .latestBlock()
+            .block(block.into())
+            .call()
+            .await?
+            ._0)
+    }
+
+    async fn data_commitment_max(
+        &self,
+        l1_provider: &RootProvider,
+    ) -> Result<u64, alloy_contract::Error> {
+        let blobstream = SP1Blobstream::new(self.address, l1_provider.clone());
+
+        Ok(blobstream.DATA_COMMITMENT_MAX().call().await?._0)
+    }
+
+    async fn proof_nonce(&self, l1_provider: &RootProvider) -> Result<U256, alloy_contract::Error> {
+        let blobstream = SP1Blobstream::new(self.address, l1_provider.clone());
+
+        Ok(blobstream.state_proofNonce().call().await?._0)
+    }
+
+    async fn data_commitment(
+        &self,
+        l1_provider: &RootProvider,
+        proof_nonce: U256,
+    ) -> Result<B256, alloy_contract::Error> {
+        let blobstream = SP1Blobstream::new(self.address, l1_provider.clone());
+
+        Ok(blobstream
+            .state_dataCommitments(proof_nonce)
+            .call()
+            .await?
+            ._0)
+    }
+
+    async fn check_status(
+        &self,
+        l1_provider: &RootProvider,
+        config: &ProofConfig,
+    ) -> Result<(), anyhow::Error> {
+        check_blobstream_status(l1_provider, self.address, config).await
+    }
+}
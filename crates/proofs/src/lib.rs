@@ -5,3 +5,7 @@
 extern crate alloc;
 
 pub mod blobstream_inclusion;
+
+pub mod contract;
+
+pub mod rate_limit;
@@ -0,0 +1,73 @@
+//! Rate limiting of the L1 RPC calls made while scanning for Blobstream events, to stay within
+//! the request quotas of hosted providers.
+
+use alloc::sync::Arc;
+use core::{fmt, num::NonZeroU32, time::Duration};
+use std::sync::Mutex;
+use tokio::time::{sleep, Instant};
+
+/// A token bucket allowing a number of requests per second, with bursts of up to one second's
+/// worth of requests.
+///
+/// Clones share the same bucket, so a limiter handed to several scans limits them together.
+#[derive(Clone)]
+pub struct RateLimiter {
+    requests_per_second: NonZeroU32,
+    bucket: Arc<Mutex<Bucket>>,
+}
+
+/// The tokens left in a [RateLimiter] as of the last refill
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl RateLimiter {
+    /// Creates a limiter allowing `requests_per_second` requests per second, starting full.
+    pub fn new(requests_per_second: NonZeroU32) -> Self {
+        Self {
+            requests_per_second,
+            bucket: Arc::new(Mutex::new(Bucket {
+                tokens: requests_per_second.get() as f64,
+                refilled_at: Instant::now(),
+            })),
+        }
+    }
+
+    /// Returns the number of requests allowed per second.
+    pub const fn requests_per_second(&self) -> NonZeroU32 {
+        self.requests_per_second
+    }
+
+    /// Waits until a request is allowed and takes its token.
+    pub async fn acquire(&self) {
+        let rate = self.requests_per_second.get() as f64;
+
+        loop {
+            let wait = {
+                let mut bucket = self.bucket.lock().expect("rate limiter lock poisoned");
+                let now = Instant::now();
+                let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+                bucket.tokens = (bucket.tokens + elapsed * rate).min(rate);
+                bucket.refilled_at = now;
+
+                if bucket.tokens >= 1.0 {
+                    bucket.tokens -= 1.0;
+                    return;
+                }
+                Duration::from_secs_f64((1.0 - bucket.tokens) / rate)
+            };
+
+            sleep(wait).await;
+        }
+    }
+}
+
+impl fmt::Debug for RateLimiter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RateLimiter")
+            .field("requests_per_second", &self.requests_per_second)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,64 @@
+//! A blob without the index of its first share cannot be proven, which must surface as
+//! [MissingBlobIndex] before any call to the Celestia node or L1.
+
+use alloy_json_rpc::{RequestPacket, ResponsePacket};
+use alloy_primitives::Address;
+use alloy_provider::RootProvider;
+use alloy_rpc_client::RpcClient;
+use alloy_transport::{TransportError, TransportFut};
+use celestia_rpc::Client;
+use celestia_types::{nmt::Namespace, AppVersion, Blob};
+use hana_proofs::{
+    blobstream_inclusion::{get_blobstream_proof, MissingBlobIndex, ProofConfig},
+    contract::Sp1Blobstream,
+};
+use std::task::{Context, Poll};
+use tower::Service;
+
+/// A transport failing the test on any request
+#[derive(Clone)]
+struct Unreachable;
+
+impl Service<RequestPacket> for Unreachable {
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: RequestPacket) -> Self::Future {
+        panic!("unexpected L1 request: {request:?}");
+    }
+}
+
+#[tokio::test]
+async fn blobs_without_an_index_fail_with_missing_blob_index() {
+    // Nothing listens on the discard port, so any call to the node fails instead of returning
+    // MissingBlobIndex.
+    let celestia_node = Client::new("http://127.0.0.1:9", None).await.unwrap();
+    let l1_provider = RootProvider::new(RpcClient::new(Unreachable, true));
+
+    let namespace = Namespace::new_v0(b"hana").unwrap();
+    let blob = Blob::new(namespace, b"no index".to_vec(), AppVersion::latest()).unwrap();
+    assert_eq!(blob.index, None);
+    let commitment = blob.commitment;
+
+    let err = get_blobstream_proof(
+        &celestia_node,
+        &l1_provider,
+        42,
+        blob,
+        &Sp1Blobstream::new(Address::ZERO),
+        &ProofConfig::default(),
+    )
+    .await
+    .unwrap_err();
+
+    let missing = err
+        .downcast_ref::<MissingBlobIndex>()
+        .unwrap_or_else(|| panic!("expected MissingBlobIndex, got {err:#}"));
+    assert_eq!(missing.height, 42);
+    assert_eq!(missing.commitment, commitment);
+}
@@ -0,0 +1,168 @@
+//! The Blobstream event scan against a provider capping the number of results of `get_logs`,
+//! which it has to shrink its windows for, and one rate limiting it, which it must not.
+
+use alloy_json_rpc::{ErrorPayload, RequestPacket, Response, ResponsePacket, ResponsePayload};
+use alloy_primitives::Address;
+use alloy_provider::RootProvider;
+use alloy_rpc_client::RpcClient;
+use alloy_transport::{TransportError, TransportFut};
+use hana_proofs::{
+    blobstream_inclusion::{
+        scan_data_commitments, FindDataCommitmentError, ProofConfig, ScanStats,
+    },
+    contract::Sp1Blobstream,
+};
+use serde_json::{value::RawValue, Value};
+use std::{
+    borrow::Cow,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+use tower::Service;
+
+/// Largest number of blocks the capped provider serves the logs of at once
+const MAX_BLOCKS: u64 = 1000;
+
+/// A transport answering `eth_getLogs` like a provider capping its results: queries spanning more
+/// than [MAX_BLOCKS] blocks fail with `error`, smaller ones match no logs.
+#[derive(Clone)]
+struct CappedLogs {
+    error: ErrorPayload,
+    /// The block ranges queried, in order
+    queries: Arc<Mutex<Vec<(u64, u64)>>>,
+}
+
+impl CappedLogs {
+    fn new(code: i64, message: &'static str) -> Self {
+        Self {
+            error: ErrorPayload {
+                code,
+                message: Cow::Borrowed(message),
+                data: None,
+            },
+            queries: Arc::default(),
+        }
+    }
+
+    fn provider(&self) -> RootProvider {
+        RootProvider::new(RpcClient::new(self.clone(), true))
+    }
+
+    fn queries(&self) -> Vec<(u64, u64)> {
+        self.queries.lock().unwrap().clone()
+    }
+}
+
+/// Returns whether the capped provider serves the logs of L1 blocks `[from, to]`.
+fn is_served(from: u64, to: u64) -> bool {
+    to - from < MAX_BLOCKS
+}
+
+/// Parses a hex block number of a filter.
+fn block_number(value: &Value) -> u64 {
+    let hex = value.as_str().expect("block number");
+    u64::from_str_radix(hex.trim_start_matches("0x"), 16).expect("hex block number")
+}
+
+impl Service<RequestPacket> for CappedLogs {
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: RequestPacket) -> Self::Future {
+        let RequestPacket::Single(request) = request else {
+            panic!("unexpected batch request");
+        };
+        assert_eq!(request.method(), "eth_getLogs");
+
+        let params: Value =
+            serde_json::from_str(request.params().expect("filter").get()).expect("JSON params");
+        let from = block_number(&params[0]["fromBlock"]);
+        let to = block_number(&params[0]["toBlock"]);
+        self.queries.lock().unwrap().push((from, to));
+
+        let payload = if is_served(from, to) {
+            ResponsePayload::Success(RawValue::from_string("[]".into()).unwrap())
+        } else {
+            ResponsePayload::Failure(self.error.clone())
+        };
+        let response = Response {
+            id: request.id().clone(),
+            payload,
+        };
+
+        Box::pin(async move { Ok(ResponsePacket::Single(response)) })
+    }
+}
+
+/// Scans L1 blocks `[0, to_block]` through `transport` with the default configuration.
+async fn scan(
+    transport: &CappedLogs,
+    to_block: u64,
+) -> (Result<(), FindDataCommitmentError>, ScanStats) {
+    let mut stats = ScanStats::default();
+    let result = scan_data_commitments(
+        0,
+        to_block,
+        &Sp1Blobstream::new(Address::ZERO),
+        &transport.provider(),
+        &ProofConfig::default(),
+        &mut stats,
+    )
+    .await
+    .map(|events| assert!(events.is_empty()));
+
+    (result, stats)
+}
+
+/// Asserts that the served queries of a scan of `[0, to_block]` cover it without gaps.
+fn assert_covered(transport: &CappedLogs, to_block: u64) {
+    let served: Vec<_> = transport
+        .queries()
+        .into_iter()
+        .filter(|(from, to)| is_served(*from, *to))
+        .collect();
+
+    assert_eq!(served.first().map(|(from, _)| *from), Some(0));
+    assert_eq!(served.last().map(|(_, to)| *to), Some(to_block));
+    for pair in served.windows(2) {
+        assert_eq!(pair[1].0, pair[0].1 + 1, "gap between {pair:?}");
+    }
+}
+
+#[tokio::test]
+async fn shrinks_the_window_on_a_result_limit() {
+    let transport = CappedLogs::new(-32005, "query returned more than 10000 results");
+
+    let (result, stats) = scan(&transport, 9_999).await;
+
+    result.expect("scan succeeds with smaller windows");
+    assert_covered(&transport, 9_999);
+    assert!(stats.get_logs_calls > stats.windows);
+    assert_eq!(stats.blocks_scanned, 10_000);
+}
+
+#[tokio::test]
+async fn shrinks_the_window_on_a_result_limit_message_with_any_code() {
+    let transport = CappedLogs::new(-32000, "Log response size exceeded");
+
+    let (result, _) = scan(&transport, 9_999).await;
+
+    result.expect("scan succeeds with smaller windows");
+    assert_covered(&transport, 9_999);
+}
+
+#[tokio::test]
+async fn does_not_retry_a_rate_limit_with_the_limit_exceeded_code() {
+    let transport = CappedLogs::new(-32005, "daily request count exceeded, request rate limited");
+
+    let (result, stats) = scan(&transport, 9_999).await;
+
+    assert!(matches!(result, Err(FindDataCommitmentError::Rpc(_))));
+    assert_eq!(stats.get_logs_calls, 1);
+    assert_eq!(transport.queries().len(), 1);
+}
@@ -0,0 +1,20 @@
+//! Feeds arbitrary hint data to the Celestia hint decoding, which must reject malformed data
+//! with an error instead of panicking, and re-encode decoded hints to the same data.
+
+#![no_main]
+
+use hana_oracle::hint::{CelestiaHint, HintWrapper};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    for hint in [
+        HintWrapper::CelestiaDA,
+        HintWrapper::CelestiaDAIndex,
+        HintWrapper::CelestiaDABlobs,
+    ] {
+        if let Ok(decoded) = CelestiaHint::decode(hint, data) {
+            assert_eq!(decoded.hint_type(), hint);
+            assert_eq!(decoded.encode(), data);
+        }
+    }
+});
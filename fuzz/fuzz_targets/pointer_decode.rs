@@ -0,0 +1,27 @@
+//! Feeds arbitrary batcher data to the Celestia pointer decoding, which must reject malformed
+//! data with an error instead of panicking.
+
+#![no_main]
+
+use hana_celestia::{CelestiaPointer, CELESTIA_DA_LAYER_BYTE};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let decoded = CelestiaPointer::try_from(data);
+    assert_eq!(decoded.is_ok(), CelestiaPointer::decode(data).is_some());
+
+    if let Ok(pointer) = decoded {
+        assert_eq!(data[2], CELESTIA_DA_LAYER_BYTE);
+        pointer
+            .check_height(0, pointer.height())
+            .expect("height is in bounds");
+    }
+
+    // Decode the rest of the data with its first byte as the DA layer byte
+    if let Some((&da_layer_byte, rest)) = data.split_first() {
+        if let Ok(pointer) = CelestiaPointer::decode_with_da_layer_byte(rest, da_layer_byte) {
+            assert_eq!(rest[2], da_layer_byte);
+            let _ = pointer.height();
+        }
+    }
+});